@@ -0,0 +1,62 @@
+/* C interface to the rle byte format. Build the library with:
+ *
+ *     cargo rustc --release --features ffi --crate-type staticlib
+ *
+ * See src/ffi.rs for the full documentation of each function. */
+
+#ifndef RLE_H
+#define RLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque table of uint32_t symbols. */
+typedef struct RleTable RleTable;
+
+/* The result of a call into the library. */
+typedef enum RleStatus {
+  RLE_STATUS_OK = 0,
+  RLE_STATUS_NULL_POINTER = 1,
+  /* The required length was written to out_len; retry with a larger buffer. */
+  RLE_STATUS_BUFFER_TOO_SMALL = 2,
+  /* See rle_last_error(). */
+  RLE_STATUS_FAILED = 3,
+} RleStatus;
+
+/* Creates a table from len symbols, in index order. Free with rle_table_free(). */
+RleTable *rle_table_new(const uint32_t *items, size_t len);
+
+/* Releases a table. Passing NULL does nothing. */
+void rle_table_free(RleTable *table);
+
+/* The number of symbols in the table. */
+size_t rle_table_len(const RleTable *table);
+
+/* Encodes len symbols into out, writing the byte count to out_len. */
+RleStatus rle_encode(const RleTable *table,
+                     const uint32_t *items,
+                     size_t len,
+                     uint8_t *out,
+                     size_t out_cap,
+                     size_t *out_len);
+
+/* Decodes len bytes into out, writing the symbol count to out_len. */
+RleStatus rle_decode(const RleTable *table,
+                     const uint8_t *bytes,
+                     size_t len,
+                     uint32_t *out,
+                     size_t out_cap,
+                     size_t *out_len);
+
+/* The message for the last failure on this thread, or NULL. */
+const char *rle_last_error(void);
+
+#ifdef __cplusplus
+} /* extern "C" */
+#endif
+
+#endif /* RLE_H */
@@ -0,0 +1,192 @@
+use crate::bulk::read_run;
+use crate::run::ArchivedRun;
+use crate::{Index, IntoRun, Run, RunLen, Table};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::primitive::FixedUsize;
+use rkyv::rancor::Fallible;
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{munge::munge, Archive, Archived, Deserialize, Place, Portable, Serialize};
+use std::convert::TryFrom;
+use std::iter::repeat_n;
+
+/// The archived form of a [Table], which can be read straight out of a
+/// buffer (such as a [MappedBytes](crate::MappedBytes)) with `rkyv::access`,
+/// without rebuilding the table. Only available with the `rkyv` feature.
+///
+/// It stores the items in index order along with their sorted indices, so
+/// that it can decode runs and bytes, and look up the index of an item,
+/// just like the table it was archived from.
+///
+/// ```
+/// # use rle::{ArchivedTable, Run, Table};
+/// use rkyv::rancor::Error;
+///
+/// let table = Table::from_slice(&['A', 'B', 'C']);
+/// let runs = vec![Run::new(2, 3usize), Run::new(0, 2)];
+/// let table_bytes = rkyv::to_bytes::<Error>(&table).unwrap();
+/// let run_bytes = rkyv::to_bytes::<Error>(&runs).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedTable<char>, Error>(&table_bytes).unwrap();
+/// let archived_runs = rkyv::access::<rkyv::Archived<Vec<Run>>, Error>(&run_bytes).unwrap();
+/// assert_eq!(archived.index_of(&'B'.into()), Some(1));
+/// let str: String = archived.decode(archived_runs).map(|c| c.to_native()).collect();
+/// assert_eq!(str, "CCCAA");
+/// ```
+#[derive(Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(C)]
+pub struct ArchivedTable<T: Archive> {
+    items: ArchivedVec<T::Archived>,
+    sorted: ArchivedVec<Archived<u32>>,
+}
+
+/// The resolver for an [ArchivedTable].
+pub struct TableResolver {
+    items: VecResolver,
+    sorted: VecResolver,
+}
+
+impl<T: Archive> Archive for Table<T> {
+    type Archived = ArchivedTable<T>;
+    type Resolver = TableResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedTable { items, sorted } = out);
+        ArchivedVec::resolve_from_len(self.len(), resolver.items, items);
+        ArchivedVec::resolve_from_len(self.len(), resolver.sorted, sorted);
+    }
+}
+
+impl<T, S> Serialize<S> for Table<T>
+where
+    T: Ord + Clone + Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let sorted: Vec<u32> = self.sorted_indices().iter().map(|&i| i as u32).collect();
+        Ok(TableResolver {
+            items: ArchivedVec::serialize_from_slice(self.as_ref(), serializer)?,
+            sorted: ArchivedVec::serialize_from_slice(&sorted, serializer)?,
+        })
+    }
+}
+
+impl<T, D> Deserialize<Table<T>, D> for ArchivedTable<T>
+where
+    T: Archive + Ord + Clone,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Table<T>, D::Error> {
+        let mut table = Table::default();
+        for item in self.items.iter() {
+            table.insert(item.deserialize(deserializer)?);
+        }
+        Ok(table)
+    }
+}
+
+impl<T: Archive> ArchivedTable<T> {
+    /// The number of items in the table.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the table has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The archived items, in index order.
+    pub fn as_slice(&self) -> &[T::Archived] {
+        self.items.as_slice()
+    }
+
+    /// Returns the archived item at `index`, or `None` if out of bounds.
+    pub fn item(&self, index: Index) -> Option<&T::Archived> {
+        self.items.get(index)
+    }
+
+    /// Returns the index of the archived item, or `None` if it is not in the table.
+    pub fn index_of(&self, item: &T::Archived) -> Option<Index>
+    where
+        T::Archived: Ord,
+    {
+        let sorted = self.sorted.as_slice();
+        let pos = sorted
+            .binary_search_by(|&ind| match self.item(ind.to_native() as usize) {
+                Some(x) => x.cmp(item),
+                None => std::cmp::Ordering::Greater,
+            })
+            .ok()?;
+        Some(sorted[pos].to_native() as usize)
+    }
+
+    /// Returns an iterator that decodes the runs into the archived items,
+    /// like [decode_as](Table::decode_as). Decoding stops at the first index
+    /// that isn't in the table.
+    pub fn decode<'a, R: IntoRun>(
+        &'a self,
+        runs: &'a [R],
+    ) -> impl Iterator<Item = &'a T::Archived> + 'a {
+        runs.iter()
+            .map_while(move |run| {
+                let run = run.into_run();
+                Some(repeat_n(self.item(run.index)?, run.len))
+            })
+            .flatten()
+    }
+
+    /// Returns an iterator that decodes run-length encoded bytes (see
+    /// [encode_bytes](Table::encode_bytes)) into the archived items. Decoding
+    /// stops at malformed bytes or an index that isn't in the table.
+    pub fn decode_bytes<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = &'a T::Archived> + 'a {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            let (ind, len, size) = read_run(bytes, offset).ok()??;
+            offset += size;
+            Some(repeat_n(self.item(ind)?, len))
+        })
+        .flatten()
+    }
+}
+
+impl<L> Clone for ArchivedRun<L>
+where
+    L: Archive,
+    L::Archived: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L> Copy for ArchivedRun<L>
+where
+    L: Archive,
+    L::Archived: Copy,
+{
+}
+
+impl<L> IntoRun for ArchivedRun<L>
+where
+    L: RunLen + Archive,
+    L::Archived: Copy,
+    usize: TryFrom<L::Archived>,
+{
+    fn into_run(self) -> Run {
+        // Lengths too large for this platform can't be decoded anyway, so
+        // they are read as an unknown index to stop decoding
+        match (
+            <usize as TryFrom<FixedUsize>>::try_from(self.index.to_native()),
+            usize::try_from(self.len),
+        ) {
+            (Ok(index), Ok(len)) => Run::new(index, len),
+            _ => Run::new(usize::MAX, 0),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{Error, Index, IntoRun, Table, TableStore};
+use arrow_array::types::{
+    Int16Type, Int32Type, Int64Type, Int8Type, RunEndIndexType, UInt16Type, UInt32Type, UInt64Type,
+    UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, BooleanArray, PrimitiveArray, RunArray, StringArray};
+use arrow_buffer::ArrowNativeType;
+use std::sync::Arc;
+
+/// A type that can be stored in the values of an Arrow array, so that runs
+/// of it can be converted to and from a [RunArray]. See [to_run_array](Table::to_run_array).
+///
+/// Implementations are provided for the primitive integer types (up to 64
+/// bits), `bool` and `String`. Only available with the `arrow` feature.
+pub trait ArrowItem: Sized {
+    /// Builds an array of the items.
+    fn to_array(items: Vec<Self>) -> ArrayRef;
+
+    /// Reads the items out of an array, or returns `None` if the array is of
+    /// the wrong type or contains nulls.
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>>;
+}
+
+macro_rules! impl_arrow_item_int {
+    ($($t:ty => $arrow:ty),*) => {
+        $(
+            impl ArrowItem for $t {
+                fn to_array(items: Vec<Self>) -> ArrayRef {
+                    Arc::new(PrimitiveArray::<$arrow>::from(items))
+                }
+
+                fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+                    let array = array.as_any().downcast_ref::<PrimitiveArray<$arrow>>()?;
+                    (array.null_count() == 0).then(|| array.values().to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_arrow_item_int!(
+    u8 => UInt8Type, u16 => UInt16Type, u32 => UInt32Type, u64 => UInt64Type,
+    i8 => Int8Type, i16 => Int16Type, i32 => Int32Type, i64 => Int64Type
+);
+
+impl ArrowItem for bool {
+    fn to_array(items: Vec<Self>) -> ArrayRef {
+        Arc::new(BooleanArray::from(items))
+    }
+
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+        let array = array.as_any().downcast_ref::<BooleanArray>()?;
+        (array.null_count() == 0).then(|| array.values().iter().collect())
+    }
+}
+
+impl ArrowItem for String {
+    fn to_array(items: Vec<Self>) -> ArrayRef {
+        Arc::new(StringArray::from(items))
+    }
+
+    fn from_array(array: &dyn Array) -> Option<Vec<Self>> {
+        let array = array.as_any().downcast_ref::<StringArray>()?;
+        array.iter().map(|item| item.map(String::from)).collect()
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone + ArrowItem,
+    S: TableStore<T>,
+{
+    /// Exports the runs as an Arrow run-end encoded array, with one value
+    /// per run looked up from the table, so that they can be handed to Arrow
+    /// without decoding them. Runs of zero length are skipped. Only available
+    /// with the `arrow` feature.
+    ///
+    /// The run ends are stored as `R`, which is one of Arrow's [RunEndIndexType]s.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// use arrow_array::types::Int32Type;
+    /// use arrow_array::{cast::AsArray, Array, RunArray};
+    ///
+    /// let table = Table::from_slice(&[10u8, 20]);
+    /// let array: RunArray<Int32Type> = table.to_run_array(&[(0, 3usize), (1, 2)]).unwrap();
+    /// assert_eq!(array.len(), 5);
+    /// assert_eq!(array.run_ends().values(), [3, 5]);
+    ///
+    /// let (table, runs) = Table::<u8>::from_run_array(&array).unwrap();
+    /// assert_eq!(table.as_ref(), [10, 20]);
+    /// assert_eq!(runs, [(0, 3), (1, 2)]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if a run refers to
+    /// an index not in the table, or a [RunTooLong](Error::RunTooLong) error,
+    /// containing the position of the run and where it ends (or `usize::MAX`
+    /// if that overflows), if a run ends past the largest value of `R`.
+    pub fn to_run_array<R, U>(&self, runs: &[U]) -> Result<RunArray<R>, Error>
+    where
+        R: RunEndIndexType,
+        U: IntoRun,
+    {
+        let mut ends = Vec::with_capacity(runs.len());
+        let mut values = Vec::with_capacity(runs.len());
+        let mut end = 0usize;
+        for (i, run) in runs.iter().enumerate() {
+            let run = run.into_run();
+            let item = self.item(run.index).ok_or(Error::UnknownIndex(run.index))?;
+            if run.len == 0 {
+                continue;
+            }
+            end = end
+                .checked_add(run.len)
+                .ok_or(Error::RunTooLong(i, usize::MAX))?;
+            ends.push(R::Native::from_usize(end).ok_or(Error::RunTooLong(i, end))?);
+            values.push(item.clone());
+        }
+        let ends = PrimitiveArray::<R>::from_iter_values(ends);
+        Ok(RunArray::try_new(&ends, T::to_array(values).as_ref())
+            .expect("run ends are positive and increasing"))
+    }
+
+    /// Imports the runs of an Arrow run-end encoded array, adding each value
+    /// to the table if it isn't already in it, and returns the runs. Only
+    /// available with the `arrow` feature.
+    ///
+    /// Sliced arrays are supported, and only the runs inside the slice are
+    /// imported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidArray](Error::InvalidArray) error if the array's
+    /// values are not of the item type or contain nulls.
+    pub fn import_run_array<R>(&mut self, array: &RunArray<R>) -> Result<Vec<(Index, usize)>, Error>
+    where
+        R: RunEndIndexType,
+    {
+        let values = T::from_array(array.values().as_ref()).ok_or(Error::InvalidArray)?;
+        let ends = array.run_ends();
+        let start = ends.get_start_physical_index();
+        let mut runs = Vec::new();
+        let mut prev = 0;
+        for (i, end) in ends.sliced_values().enumerate() {
+            let end = end.as_usize();
+            let item = values.get(start + i).ok_or(Error::InvalidArray)?;
+            if end > prev {
+                runs.push((self.insert_or_get(item), end - prev));
+            }
+            prev = end;
+        }
+        Ok(runs)
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone + ArrowItem,
+{
+    /// Constructs a table of the values of an Arrow run-end encoded array,
+    /// along with the array's runs. See [import_run_array](Table::import_run_array).
+    /// Only available with the `arrow` feature.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [import_run_array](Table::import_run_array).
+    pub fn from_run_array<R>(array: &RunArray<R>) -> Result<(Self, Vec<(Index, usize)>), Error>
+    where
+        R: RunEndIndexType,
+    {
+        let mut table = Self::default();
+        let runs = table.import_run_array(array)?;
+        Ok((table, runs))
+    }
+}
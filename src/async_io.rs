@@ -0,0 +1,93 @@
+use crate::{Error, Table, TableStore};
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const BLOCK_SIZE: usize = 8 * 1024;
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes (see [encode_bytes](Table::encode_bytes))
+    /// and writes them into `writer` in blocks, returning the number of bytes written.
+    ///
+    /// This is the async version of [encode_bytes_to](Table::encode_bytes_to),
+    /// and is only available with the `tokio` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing, or an [InvalidInput](io::ErrorKind::InvalidInput)
+    /// error wrapping the [Error] if the items could not be encoded, in which
+    /// case nothing is written.
+    pub async fn encode_bytes_async<W>(&self, items: &[T], mut writer: W) -> io::Result<usize>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let invalid = |err: Error| io::Error::new(io::ErrorKind::InvalidInput, err);
+        self.encode_bytes(items).map_err(invalid)?;
+
+        let mut encoder = self.encode_streaming();
+        let mut buf = Vec::with_capacity(BLOCK_SIZE);
+        let mut written = 0;
+        for block in items.chunks(BLOCK_SIZE) {
+            encoder.push_slice(block).map_err(invalid)?;
+            encoder.write_bytes(&mut buf).map_err(invalid)?;
+            writer.write_all(&buf).await?;
+            written += buf.len();
+            buf.clear();
+        }
+        encoder.finish_bytes(&mut buf).map_err(invalid)?;
+        writer.write_all(&buf).await?;
+        writer.flush().await?;
+        Ok(written + buf.len())
+    }
+
+    /// Reads run-length encoded bytes (see [decode_bytes](Table::decode_bytes))
+    /// from `reader` until it ends, appending the decoded items to `items` and
+    /// returning how many there were.
+    ///
+    /// Only available with the `tokio` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from reading, or an [InvalidData](io::ErrorKind::InvalidData)
+    /// error wrapping the [Error] if the bytes could not be decoded. If an error
+    /// occurs, `items` is left as it was.
+    pub async fn decode_bytes_async<R>(
+        &self,
+        mut reader: R,
+        items: &mut Vec<T>,
+    ) -> io::Result<usize>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let start = items.len();
+        let mut decoder = self.decode_streaming();
+        let mut buf = vec![0; BLOCK_SIZE];
+        let result = loop {
+            let read = match reader.read(&mut buf).await {
+                Ok(0) => break decoder.finish(),
+                Ok(read) => read,
+                Err(err) => {
+                    items.truncate(start);
+                    return Err(err);
+                }
+            };
+            // Errors are held by the decoder, which stops at the first one
+            for item in decoder.feed(&buf[..read]).flatten() {
+                items.push(item.clone());
+            }
+            if decoder.error.is_some() {
+                break decoder.finish();
+            }
+        };
+        match result {
+            Ok(()) => Ok(items.len() - start),
+            Err(err) => {
+                items.truncate(start);
+                Err(io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{BytesDecoderMut, Table, TableItem};
+
+/// The header byte of [encode_bytes_auto](crate::Table::encode_bytes_auto)
+/// output that was run-length encoded.
+const ENCODED: u8 = 0;
+
+/// The header byte of [encode_bytes_auto](crate::Table::encode_bytes_auto)
+/// output that was stored raw.
+const RAW: u8 = 1;
+
+/// An iterator that decodes bytes produced by [encode_bytes_auto](crate::Table::encode_bytes_auto)
+/// into a series of `T` values, whether they were run-length encoded or
+/// stored raw. See [decode_bytes_auto](crate::Table::decode_bytes_auto).
+pub enum AutoBytesDecoder<'a, T> {
+    /// The bytes were run-length encoded.
+    Encoded(BytesDecoderMut<'a, T>),
+
+    /// The bytes were the items stored one after another.
+    Raw(&'a [u8]),
+}
+
+impl<'a, T> Iterator for AutoBytesDecoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Encoded(decoder) => decoder.next(),
+            Self::Raw(bytes) => {
+                let (item, size) = T::read_bytes(bytes)?;
+                *bytes = &bytes[size..];
+                Some(item)
+            }
+        }
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Encodes the items as a self-describing sequence of bytes that is
+    /// never more than one byte larger than the items themselves.
+    ///
+    /// The first byte is a header. If it is `0`, the rest of the bytes are in
+    /// the same format as [encode_bytes_inline](Table::encode_bytes_inline),
+    /// and any new items have been added to the table. If run-length encoding
+    /// would have produced more bytes than storing the items directly (because
+    /// there are too few repeated items, or too many unique ones), encoding
+    /// stops as soon as that is known, the header is `1`, and the rest of the
+    /// bytes are each item's bytes (see [TableItem]) one after another. In
+    /// that case, the table is left unchanged.
+    ///
+    /// Use [decode_bytes_auto](Table::decode_bytes_auto) to decode either case.
+    pub fn encode_bytes_auto(&mut self, items: &[T]) -> Vec<u8> {
+        let mut raw = vec![RAW];
+        for item in items {
+            item.write_bytes(&mut raw);
+        }
+
+        let len = self.len();
+        let mut bytes = Vec::with_capacity(raw.len());
+        bytes.push(ENCODED);
+        if let Ok(encoder) = self.encode_bytes_inline(items) {
+            for byte in encoder {
+                match byte {
+                    Ok(byte) if bytes.len() < raw.len() => bytes.push(byte),
+                    _ => {
+                        self.truncate(len);
+                        return raw;
+                    }
+                }
+            }
+            return bytes;
+        }
+        raw
+    }
+
+    /// Return an iterator that decodes bytes produced by
+    /// [encode_bytes_auto](Table::encode_bytes_auto), adding any items
+    /// embedded in run-length encoded bytes to this table.
+    pub fn decode_bytes_auto<'a>(&'a mut self, bytes: &'a [u8]) -> AutoBytesDecoder<'a, T> {
+        match bytes.split_first() {
+            Some((&ENCODED, bytes)) => AutoBytesDecoder::Encoded(self.decode_bytes_mut(bytes)),
+            Some((_, bytes)) => AutoBytesDecoder::Raw(bytes),
+            None => AutoBytesDecoder::Raw(bytes),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Error, Table, TableStore};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes (see [encode_bytes](Table::encode_bytes)),
+    /// returned as a base64 string (with the standard alphabet and padding)
+    /// that can be embedded in text formats like JSON or TOML. Enabled with
+    /// the `base64` feature.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let text = table.encode_base64(&str).unwrap();
+    /// assert_eq!(text, "AQUDCgUDAQo=");
+    /// assert_eq!(table.decode_base64(&text).unwrap(), str);
+    /// ```
+    pub fn encode_base64(&self, items: &[T]) -> Result<String, Error> {
+        let mut bytes = Vec::new();
+        self.encode_bytes_into(items, &mut bytes)?;
+        let mut str = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - i * 8));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    str.push(ALPHABET[(n >> (18 - i * 6)) as usize & 63] as char);
+                } else {
+                    str.push('=');
+                }
+            }
+        }
+        Ok(str)
+    }
+
+    /// Decodes a base64 string written by [encode_base64](Table::encode_base64).
+    /// Enabled with the `base64` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBase64](Error::InvalidBase64) error if the string
+    /// is not valid base64, or any error from [decode_bytes_into](Table::decode_bytes_into).
+    pub fn decode_base64(&self, str: &str) -> Result<Vec<T>, Error> {
+        let str = str.trim_end_matches('=').as_bytes();
+        let mut bytes = Vec::with_capacity(str.len() * 3 / 4);
+        for (c, chunk) in str.chunks(4).enumerate() {
+            if chunk.len() == 1 {
+                return Err(Error::InvalidBase64(c * 4));
+            }
+            let mut n = 0u32;
+            for (i, &b) in chunk.iter().enumerate() {
+                let value = match b {
+                    b'A'..=b'Z' => b - b'A',
+                    b'a'..=b'z' => b - b'a' + 26,
+                    b'0'..=b'9' => b - b'0' + 52,
+                    b'+' => 62,
+                    b'/' => 63,
+                    _ => return Err(Error::InvalidBase64(c * 4 + i)),
+                };
+                n |= (value as u32) << (18 - i * 6);
+            }
+            bytes.extend((0..chunk.len() - 1).map(|i| (n >> (16 - i * 8)) as u8));
+        }
+        let mut items = Vec::new();
+        self.decode_bytes_into(&bytes, &mut items)?;
+        Ok(items)
+    }
+}
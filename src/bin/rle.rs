@@ -0,0 +1,205 @@
+//! A command-line tool for encoding and decoding files with the `rle` crate.
+//!
+//! Only built with the `cli` feature. Run `rle --help` for usage.
+
+use rle::{EncodeOptions, Table};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+const USAGE: &str = "\
+Usage: rle <encode|decode> [options] <input> <output>
+
+Encodes or decodes a file, treating each byte as an item. Use `-` as the
+input or output to read from stdin or write to stdout.
+
+Options:
+  -f, --format <format>  The byte format to use (default: container)
+                           container       bytes with the table embedded
+                           bytes           the plain byte format
+                           varint          bytes with LEB128 run lengths
+                           literal-blocks  bytes with literal blocks
+  -t, --table <file>     Where to write the table when encoding, or read it
+                         from when decoding (required for every format
+                         except container)
+  -q, --quiet            Don't print the compression ratio when encoding
+  -h, --help             Print this message";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Container,
+    Bytes,
+    Varint,
+    LiteralBlocks,
+}
+
+struct Args {
+    encode: bool,
+    format: Format,
+    table: Option<String>,
+    quiet: bool,
+    input: String,
+    output: String,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut mode = None;
+    let mut format = Format::Container;
+    let mut table = None;
+    let mut quiet = false;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-f" | "--format" => {
+                format = match args.next().as_deref() {
+                    Some("container") => Format::Container,
+                    Some("bytes") => Format::Bytes,
+                    Some("varint") => Format::Varint,
+                    Some("literal-blocks") => Format::LiteralBlocks,
+                    Some(other) => return Err(format!("unknown format `{}`", other)),
+                    None => return Err(String::from("missing format")),
+                }
+            }
+            "-t" | "--table" => {
+                table = Some(args.next().ok_or("missing table file")?);
+            }
+            "-q" | "--quiet" => quiet = true,
+            "encode" | "decode" if mode.is_none() => mode = Some(arg == "encode"),
+            _ => paths.push(arg),
+        }
+    }
+    let encode = mode.ok_or("expected `encode` or `decode`")?;
+    if paths.len() != 2 {
+        return Err(String::from("expected an input and an output"));
+    }
+    if format != Format::Container && table.is_none() {
+        return Err(String::from("this format needs a --table file"));
+    }
+    let output = paths.pop().unwrap();
+    let input = paths.pop().unwrap();
+    Ok(Args {
+        encode,
+        format,
+        table,
+        quiet,
+        input,
+        output,
+    })
+}
+
+fn read(path: &str) -> io::Result<Vec<u8>> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    } else {
+        fs::read(path)
+    }
+}
+
+fn write(path: &str, bytes: &[u8]) -> io::Result<()> {
+    if path == "-" {
+        io::stdout().write_all(bytes)
+    } else {
+        fs::write(path, bytes)
+    }
+}
+
+fn encode(args: &Args, input: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut table = Table::default();
+    table.optimize_for(input);
+    let output = match args.format {
+        Format::Container => table.encode_container(input)?,
+        Format::Bytes => table.encode_bytes_with(input, EncodeOptions::new())?,
+        Format::Varint => table.encode_bytes_varint(input)?,
+        Format::LiteralBlocks => {
+            table.encode_bytes_with(input, EncodeOptions::new().literal_blocks())?
+        }
+    };
+    if let Some(path) = &args.table {
+        write(path, &table.to_bytes()?)?;
+    }
+    if !args.quiet {
+        let ratio = output.len() as f64 / input.len().max(1) as f64;
+        eprintln!(
+            "{} bytes -> {} bytes ({:.1}%), {} symbols",
+            input.len(),
+            output.len(),
+            ratio * 100.0,
+            table.len()
+        );
+    }
+    Ok(output)
+}
+
+fn decode(args: &Args, input: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if args.format == Format::Container {
+        return Ok(Table::<u8>::decode_container(input)?.1);
+    }
+    let table = Table::<u8>::from_bytes(&read(args.table.as_ref().unwrap())?)?;
+    Ok(match args.format {
+        Format::Container | Format::Bytes => {
+            table.decode_bytes_with(input, EncodeOptions::new())?
+        }
+        Format::Varint => table.decode_bytes_varint(input)?,
+        Format::LiteralBlocks => {
+            table.decode_bytes_with(input, EncodeOptions::new().literal_blocks())?
+        }
+    })
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|err| {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let result = read(&args.input)
+        .map_err(Into::into)
+        .and_then(|input| {
+            if args.encode {
+                encode(&args, &input)
+            } else {
+                decode(&args, &input)
+            }
+        })
+        .and_then(|output| Ok(write(&args.output, &output)?));
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = b"AAAAABBBBBBBBBBCCCAAAAAAAAAA\n\n\n..........XX";
+        let table = std::env::temp_dir().join(format!("rle-cli-{}.table", process::id()));
+        let formats = [
+            Format::Container,
+            Format::Bytes,
+            Format::Varint,
+            Format::LiteralBlocks,
+        ];
+        for &format in formats.iter() {
+            let args = Args {
+                encode: true,
+                format,
+                table: Some(table.to_string_lossy().into_owned()),
+                quiet: true,
+                input: String::from("-"),
+                output: String::from("-"),
+            };
+            let encoded = encode(&args, input).unwrap();
+            assert_eq!(decode(&args, &encoded).unwrap(), input);
+        }
+        fs::remove_file(&table).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+//! Table-free encoding for sequences of bits, such as collision masks and
+//! visibility grids.
+//!
+//! Bits can be given either as a slice of `bool`, or packed into `u64` words,
+//! where bit `i` is bit `i % 64` of word `i / 64` (least significant first).
+//! Either way, runs are found a whole word at a time rather than one bit at
+//! a time, which is much faster for the long runs these usually contain.
+//!
+//! ```
+//! use rle::bitmap::{decode_words, encode_words, encode_bools};
+//!
+//! let mask = [false, false, true, true, true, false];
+//! assert_eq!(encode_bools(&mask), [(false, 2), (true, 3), (false, 1)]);
+//!
+//! let words = [0b011100];
+//! let runs = encode_words(&words, 6);
+//! assert_eq!(runs, [(false, 2), (true, 3), (false, 1)]);
+//! assert_eq!(decode_words(&runs), words);
+//! ```
+
+use std::convert::TryInto;
+use std::iter::repeat_n;
+
+/// Returns the value of bit `i`.
+pub(crate) fn get_bit(words: &[u64], i: usize) -> bool {
+    words[i / 64] >> (i % 64) & 1 == 1
+}
+
+/// Sets every bit from `start` up to (but not including) `end`.
+pub(crate) fn set_bits(words: &mut [u64], mut start: usize, end: usize) {
+    while start < end {
+        let shift = start % 64;
+        let n = (64 - shift).min(end - start);
+        let mask = if n == 64 { !0 } else { ((1 << n) - 1) << shift };
+        words[start / 64] |= mask;
+        start += n;
+    }
+}
+
+/// Returns how many bits starting at `start` (and before `end`) are equal
+/// to the bit at `start`.
+pub(crate) fn bit_run_len(words: &[u64], start: usize, end: usize) -> usize {
+    let bit = get_bit(words, start);
+    let mut pos = start;
+    while pos < end {
+        let shift = pos % 64;
+        let word = words[pos / 64] >> shift;
+        let same = if bit {
+            word.trailing_ones()
+        } else {
+            word.trailing_zeros()
+        };
+        let same = (same as usize).min(64 - shift);
+        pos += same;
+        if same < 64 - shift {
+            break;
+        }
+    }
+    pos.min(end) - start
+}
+
+/// Returns how many bools at the start of the slice are equal to the first,
+/// comparing them 8 at a time.
+fn bool_run_len(bits: &[bool]) -> usize {
+    let first = match bits.first() {
+        Some(&first) => first,
+        None => return 0,
+    };
+    let splat = u64::from_le_bytes([first as u8; 8]);
+    let mut chunks = bits.chunks_exact(8);
+    let mut len = 0;
+    for chunk in &mut chunks {
+        let chunk: [bool; 8] = chunk.try_into().unwrap();
+        let diff = u64::from_le_bytes(chunk.map(u8::from)) ^ splat;
+        if diff != 0 {
+            return len + diff.trailing_zeros() as usize / 8;
+        }
+        len += 8;
+    }
+    len + chunks
+        .remainder()
+        .iter()
+        .take_while(|&&bit| bit == first)
+        .count()
+}
+
+/// Run-length encodes the bools, returning each run's value and length.
+pub fn encode_bools(bits: &[bool]) -> Vec<(bool, usize)> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < bits.len() {
+        let len = bool_run_len(&bits[i..]);
+        runs.push((bits[i], len));
+        i += len;
+    }
+    runs
+}
+
+/// Run-length encodes the first `len` bits of a packed bitmap, returning
+/// each run's value and length.
+///
+/// # Panics
+///
+/// Panics if `len` is more than the number of bits in `words`.
+pub fn encode_words(words: &[u64], len: usize) -> Vec<(bool, usize)> {
+    assert!(len <= words.len() * 64, "len is out of bounds");
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < len {
+        let run = bit_run_len(words, i, len);
+        runs.push((get_bit(words, i), run));
+        i += run;
+    }
+    runs
+}
+
+/// Decodes runs produced by [encode_bools] (or [encode_words]) into bools.
+pub fn decode_bools(runs: &[(bool, usize)]) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(runs.iter().map(|&(_, len)| len).sum());
+    for &(bit, len) in runs {
+        bits.extend(repeat_n(bit, len));
+    }
+    bits
+}
+
+/// Decodes runs produced by [encode_words] (or [encode_bools]) into a packed
+/// bitmap. Any bits in the last word past the end of the runs are zero.
+pub fn decode_words(runs: &[(bool, usize)]) -> Vec<u64> {
+    let total: usize = runs.iter().map(|&(_, len)| len).sum();
+    let mut words = vec![0; total.div_ceil(64)];
+    let mut pos = 0;
+    for &(bit, len) in runs {
+        if bit {
+            set_bits(&mut words, pos, pos + len);
+        }
+        pos += len;
+    }
+    words
+}
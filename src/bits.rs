@@ -0,0 +1,149 @@
+use crate::{Error, Table, TableStore};
+use std::iter::repeat_n;
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items into a bit-packed format for tables with
+    /// only two items, such as masks or collision maps.
+    ///
+    /// Since the runs must alternate between the two items, only the item of
+    /// the first run is stored, taking a single bit. After that, each run
+    /// only stores its length, using an [Elias gamma code](https://en.wikipedia.org/wiki/Elias_gamma_coding):
+    /// the number of bits in the length minus one as zeros, followed by the
+    /// length itself. A run of 1 takes a single bit, and a run of 100 takes
+    /// 13 bits. The bits are packed into bytes starting from the highest bit,
+    /// and the last byte is padded with zeros.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mask: Vec<char> = "....XX.......".chars().collect();
+    /// let table = Table::from_slice(&['.', 'X']);
+    ///
+    /// let bits = table.encode_bits(&mask).unwrap();
+    /// assert_eq!(bits, [0b0_00100_01, 0b0_00111_00]);
+    /// assert_eq!(table.decode_bits(&bits).unwrap(), mask);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the table contains more than 2 items, this will return a
+    /// [TableTooLarge](Error::TableTooLarge) error, and if any items are not
+    /// in the table, a [TableMissingItems](Error::TableMissingItems) error.
+    pub fn encode_bits(&self, items: &[T]) -> Result<Vec<u8>, Error> {
+        if self.len() > 2 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut bits = BitWriter::default();
+        let mut i = 0;
+        while i < items.len() {
+            let ind = self
+                .get_index(&items[i])
+                .ok_or(Error::TableMissingItems(i))?;
+            if i == 0 {
+                bits.push(ind as u64, 1);
+            }
+            let len = self.run_len(&items[i..]);
+            let width = usize::BITS - len.leading_zeros();
+            bits.push(0, width - 1);
+            bits.push(len as u64, width);
+            i += len;
+        }
+        Ok(bits.finish())
+    }
+
+    /// Decodes bytes that were encoded with [encode_bits](Table::encode_bits).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if a run refers to
+    /// an index not in the table, [TruncatedStream](Error::TruncatedStream)
+    /// if the bytes end in the middle of a run length, or [InvalidBytes](Error::InvalidBytes)
+    /// if a run length is too large to decode.
+    pub fn decode_bits(&self, bytes: &[u8]) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        let mut bits = BitReader { bytes, pos: 0 };
+        let mut ind = match bits.read(1) {
+            Some(ind) => ind as usize,
+            None => return Ok(items),
+        };
+        loop {
+            // The padding at the end is all zeros, so running out of bits
+            // before finding the end of the prefix means there are no more runs
+            let start = bits.pos / 8;
+            let mut width = 1;
+            loop {
+                match bits.read(1) {
+                    Some(0) => width += 1,
+                    Some(_) => break,
+                    None => return Ok(items),
+                }
+            }
+            if width > usize::BITS {
+                return Err(Error::InvalidBytes(start));
+            }
+            let rest = bits.read(width - 1).ok_or(Error::TruncatedStream)?;
+            let len = ((1 << (width - 1)) | rest) as usize;
+            let item = self.item(ind).ok_or(Error::UnknownIndex(ind))?;
+            // The length comes from the bytes, so reserving for it may fail
+            items
+                .try_reserve(len)
+                .map_err(|_| Error::InvalidBytes(start))?;
+            items.extend(repeat_n(item.clone(), len));
+            ind ^= 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Writes the lowest `count` bits of `value`, highest bit first.
+    fn push(&mut self, value: u64, mut count: u32) {
+        while count > 0 {
+            let num = count.min(8);
+            count -= num;
+            self.acc = (self.acc << num) | ((value >> count) & ((1 << num) - 1));
+            self.count += num;
+            while self.count >= 8 {
+                self.count -= 8;
+                self.bytes.push((self.acc >> self.count) as u8);
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push((self.acc << (8 - self.count)) as u8);
+        }
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Reads `count` bits, highest bit first, or `None` if there aren't enough.
+    fn read(&mut self, count: u32) -> Option<u64> {
+        if self.pos + count as usize > self.bytes.len() * 8 {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..count {
+            let bit = (self.bytes[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.pos += 1;
+        }
+        Some(value)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::bitmap::{decode_words, encode_bools, encode_words};
+use std::iter::repeat_n;
+
+/// A sequence of bits stored as alternating runs of zeros and ones, with set
+/// operations that work directly on the runs rather than on each bit.
+///
+/// Two sets are only equal if they have the same length, so trailing zeros
+/// are significant. The set operations treat the shorter set as if it were
+/// padded with zeros, and produce a set as long as the longer one.
+///
+/// ```
+/// # use rle::RleBitSet;
+/// let walls = RleBitSet::from_bools(&[true, true, false, false, false, true]);
+/// let water = RleBitSet::from_bools(&[false, false, false, true, true, true]);
+///
+/// let blocked = walls.union(&water);
+/// assert_eq!(blocked.count_ones(), 5);
+/// assert!(!blocked.contains(2));
+/// assert_eq!(walls.intersection(&water).count_ones(), 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RleBitSet {
+    /// The end position of each run, starting with a (possibly empty) run of
+    /// zeros and then alternating. Only the first run can be empty.
+    ends: Vec<usize>,
+}
+
+impl RleBitSet {
+    /// Constructs a new, empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a set of `len` bits that are all zero.
+    pub fn zeros(len: usize) -> Self {
+        Self::from_runs(&[(false, len)])
+    }
+
+    /// Constructs a set of `len` bits that are all one.
+    pub fn ones(len: usize) -> Self {
+        Self::from_runs(&[(true, len)])
+    }
+
+    /// Constructs a set from a sequence of runs. The runs don't have to
+    /// alternate, and empty runs are skipped.
+    pub fn from_runs(runs: &[(bool, usize)]) -> Self {
+        let mut set = Self::new();
+        for &(bit, len) in runs {
+            set.push_run(bit, len);
+        }
+        set
+    }
+
+    /// Constructs a set from a slice of bools.
+    pub fn from_bools(bits: &[bool]) -> Self {
+        Self::from_runs(&encode_bools(bits))
+    }
+
+    /// Constructs a set from the first `len` bits of a packed bitmap (see
+    /// [bitmap](crate::bitmap)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is more than the number of bits in `words`.
+    pub fn from_words(words: &[u64], len: usize) -> Self {
+        Self::from_runs(&encode_words(words, len))
+    }
+
+    /// The number of bits in the set.
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the set contains no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `len` bits to the end of the set.
+    pub fn push_run(&mut self, bit: bool, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let end = self.len();
+        if self.ends.is_empty() && bit {
+            self.ends.push(0);
+        }
+        let last_is_ones = self.ends.len().is_multiple_of(2);
+        match self.ends.last_mut() {
+            Some(last) if last_is_ones == bit => *last += len,
+            _ => self.ends.push(end + len),
+        }
+    }
+
+    /// Returns `true` if bit `i` is one. Bits past the end are zero.
+    pub fn contains(&self, i: usize) -> bool {
+        let run = self.ends.partition_point(|&end| end <= i);
+        run < self.ends.len() && run % 2 == 1
+    }
+
+    /// The number of bits that are one.
+    pub fn count_ones(&self) -> usize {
+        self.ends
+            .chunks_exact(2)
+            .map(|pair| pair[1] - pair[0])
+            .sum()
+    }
+
+    /// The number of bits that are zero.
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns an iterator over the runs, as each run's value and length.
+    pub fn runs(&self) -> impl Iterator<Item = (bool, usize)> + '_ {
+        let mut start = 0;
+        self.ends.iter().enumerate().filter_map(move |(i, &end)| {
+            let len = end - start;
+            start = end;
+            (len > 0).then_some((i % 2 == 1, len))
+        })
+    }
+
+    /// Returns the bits as bools.
+    pub fn to_bools(&self) -> Vec<bool> {
+        let mut bits = Vec::with_capacity(self.len());
+        for (bit, len) in self.runs() {
+            bits.extend(repeat_n(bit, len));
+        }
+        bits
+    }
+
+    /// Returns the bits as a packed bitmap (see [bitmap](crate::bitmap)).
+    pub fn to_words(&self) -> Vec<u64> {
+        decode_words(&self.runs().collect::<Vec<_>>())
+    }
+
+    /// Returns the bits that are one in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Returns the bits that are one in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Returns the bits that are one in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Returns the bits that are one in exactly one of the sets.
+    pub fn xor(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    /// Walks the runs of both sets together, applying `op` to each span
+    /// where neither set changes value.
+    fn combine(&self, other: &Self, op: fn(bool, bool) -> bool) -> Self {
+        let len = self.len().max(other.len());
+        let mut out = Self::new();
+        let (mut i, mut j, mut pos) = (0, 0, 0);
+        while pos < len {
+            let a_end = self.ends.get(i).copied().unwrap_or(len);
+            let b_end = other.ends.get(j).copied().unwrap_or(len);
+            let a = i < self.ends.len() && i % 2 == 1;
+            let b = j < other.ends.len() && j % 2 == 1;
+            let end = a_end.min(b_end);
+            out.push_run(op(a, b), end - pos);
+            pos = end;
+            if a_end == end {
+                i += 1;
+            }
+            if b_end == end {
+                j += 1;
+            }
+        }
+        out
+    }
+}
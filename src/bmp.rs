@@ -0,0 +1,228 @@
+use crate::byte_scan::byte_run_len;
+use crate::Error;
+
+const END_OF_LINE: u8 = 0;
+const END_OF_BITMAP: u8 = 1;
+const DELTA: u8 = 2;
+
+/// Encodes 8-bit palette indices with the Windows BMP `BI_RLE8` compression.
+///
+/// The pixels are rows of `width` pixels, in the order they are stored in
+/// the bitmap (usually bottom-up).
+///
+/// # Format
+///
+/// The data is a series of byte pairs. If the first byte of a pair is not
+/// zero, it is a run of that many pixels using the index in the second byte.
+/// Otherwise, the second byte is an escape:
+///
+/// - `0`: end of line, moving to the start of the next row.
+/// - `1`: end of bitmap.
+/// - `2`: delta, followed by two bytes to move right and down by.
+/// - `3..=255`: absolute mode, followed by that many pixel indices which
+///   are copied as they are, padded to an even number of bytes.
+///
+/// ```
+/// # use rle::{decode_bmp_rle8, encode_bmp_rle8};
+/// let pixels = [3, 3, 3, 3, 1, 2, 3, 5];
+/// let data = encode_bmp_rle8(&pixels, 8);
+/// assert_eq!(data, [4, 3, 0, 4, 1, 2, 3, 5, 0, 1]);
+/// assert_eq!(decode_bmp_rle8(&data, 8, 1).unwrap(), pixels);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the pixels cannot be split evenly into rows of `width` pixels.
+pub fn encode_bmp_rle8(pixels: &[u8], width: usize) -> Vec<u8> {
+    encode(pixels, width, false)
+}
+
+/// Encodes 4-bit palette indices with the Windows BMP `BI_RLE4` compression.
+///
+/// Each pixel is given as a separate byte, of which only the low 4 bits are
+/// used. The format is the same as [encode_bmp_rle8], except that the index
+/// byte of a run holds two pixels which alternate for the length of the run,
+/// and absolute mode packs two pixels into each byte.
+///
+/// ```
+/// # use rle::{decode_bmp_rle4, encode_bmp_rle4};
+/// let pixels = [3, 3, 3, 3, 1, 2, 3, 5];
+/// let data = encode_bmp_rle4(&pixels, 8);
+/// assert_eq!(data, [4, 0x33, 0, 4, 0x12, 0x35, 0, 1]);
+/// assert_eq!(decode_bmp_rle4(&data, 8, 1).unwrap(), pixels);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the pixels cannot be split evenly into rows of `width` pixels.
+pub fn encode_bmp_rle4(pixels: &[u8], width: usize) -> Vec<u8> {
+    encode(pixels, width, true)
+}
+
+fn encode(pixels: &[u8], width: usize, nibbles: bool) -> Vec<u8> {
+    assert!(
+        width > 0 && pixels.len().is_multiple_of(width),
+        "pixels must be whole rows"
+    );
+    let mask = if nibbles { 0x0F } else { 0xFF };
+    let pixels: Vec<u8> = pixels.iter().map(|&p| p & mask).collect();
+
+    let mut data = Vec::with_capacity(pixels.len() / 2 + 2);
+    for row in pixels.chunks(width) {
+        let mut literal = 0;
+        let mut i = 0;
+        while i < row.len() {
+            let len = byte_run_len(&row[i..]);
+            if len < 3 {
+                i += len;
+                continue;
+            }
+            push_absolute(&mut data, &row[literal..i], nibbles);
+            push_run(&mut data, row[i], len, nibbles);
+            i += len;
+            literal = i;
+        }
+        push_absolute(&mut data, &row[literal..], nibbles);
+        data.extend_from_slice(&[0, END_OF_LINE]);
+    }
+
+    // The last end of line is replaced with the end of the bitmap
+    data.truncate(data.len().saturating_sub(2));
+    data.extend_from_slice(&[0, END_OF_BITMAP]);
+    data
+}
+
+fn push_run(data: &mut Vec<u8>, pixel: u8, mut len: usize, nibbles: bool) {
+    let pixel = if nibbles { (pixel << 4) | pixel } else { pixel };
+    while len > 0 {
+        let num = len.min(255);
+        data.extend_from_slice(&[num as u8, pixel]);
+        len -= num;
+    }
+}
+
+fn push_absolute(data: &mut Vec<u8>, pixels: &[u8], nibbles: bool) {
+    for chunk in pixels.chunks(255) {
+        if chunk.len() < 3 {
+            // Absolute mode can't hold fewer than 3 pixels
+            for &pixel in chunk {
+                push_run(data, pixel, 1, nibbles);
+            }
+            continue;
+        }
+        data.extend_from_slice(&[0, chunk.len() as u8]);
+        let start = data.len();
+        if nibbles {
+            data.extend(
+                chunk
+                    .chunks(2)
+                    .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0)),
+            );
+        } else {
+            data.extend_from_slice(chunk);
+        }
+        if (data.len() - start) % 2 == 1 {
+            data.push(0);
+        }
+    }
+}
+
+/// Decodes data compressed with `BI_RLE8` (see [encode_bmp_rle8]) into a
+/// bitmap of `width * height` palette indices. Pixels that are skipped over
+/// with end of line or delta escapes are left as zero.
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the data ends
+/// in the middle of a pair or escape, or an [InvalidBytes](Error::InvalidBytes)
+/// error with the offset of a pair that writes or moves outside of the bitmap.
+pub fn decode_bmp_rle8(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>, Error> {
+    decode(data, width, height, false)
+}
+
+/// Decodes data compressed with `BI_RLE4` (see [encode_bmp_rle4]) into a
+/// bitmap of `width * height` palette indices, one per byte. Pixels that are
+/// skipped over with end of line or delta escapes are left as zero.
+///
+/// # Errors
+///
+/// Returns the same errors as [decode_bmp_rle8].
+pub fn decode_bmp_rle4(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>, Error> {
+    decode(data, width, height, true)
+}
+
+fn decode(data: &[u8], width: usize, height: usize, nibbles: bool) -> Result<Vec<u8>, Error> {
+    let mut pixels = vec![0; width * height];
+    let (mut x, mut y) = (0, 0);
+    let mut offset = 0;
+
+    // The range of pixels from the cursor, if `len` of them fit in the row
+    let span = |x: usize, y: usize, len: usize, offset: usize| {
+        if y < height && x + len <= width {
+            Ok(y * width + x..y * width + x + len)
+        } else {
+            Err(Error::InvalidBytes(offset))
+        }
+    };
+
+    while offset < data.len() {
+        let pair = data.get(offset..offset + 2).ok_or(Error::TruncatedStream)?;
+        let (count, value) = (pair[0] as usize, pair[1]);
+        match (count, value) {
+            (0, END_OF_LINE) => {
+                x = 0;
+                y += 1;
+                offset += 2;
+            }
+            (0, END_OF_BITMAP) => break,
+            (0, DELTA) => {
+                let delta = data
+                    .get(offset + 2..offset + 4)
+                    .ok_or(Error::TruncatedStream)?;
+                x += delta[0] as usize;
+                y += delta[1] as usize;
+                span(x, y, 0, offset)?;
+                offset += 4;
+            }
+            (0, len) => {
+                let len = len as usize;
+                let size = if nibbles { len.div_ceil(2) } else { len };
+                let bytes = data
+                    .get(offset + 2..offset + 2 + size)
+                    .ok_or(Error::TruncatedStream)?;
+                let out = &mut pixels[span(x, y, len, offset)?];
+                if nibbles {
+                    for (i, out) in out.iter_mut().enumerate() {
+                        *out = nibble(bytes[i / 2], i);
+                    }
+                } else {
+                    out.copy_from_slice(bytes);
+                }
+                x += len;
+                offset += 2 + size + size % 2;
+            }
+            (len, value) => {
+                let out = &mut pixels[span(x, y, len, offset)?];
+                if nibbles {
+                    for (i, out) in out.iter_mut().enumerate() {
+                        *out = nibble(value, i);
+                    }
+                } else {
+                    out.fill(value);
+                }
+                x += len;
+                offset += 2;
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+/// The high nibble for even pixels, the low nibble for odd pixels.
+fn nibble(byte: u8, i: usize) -> u8 {
+    if i.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0x0F
+    }
+}
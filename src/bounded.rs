@@ -0,0 +1,265 @@
+use crate::bulk::read_run;
+use crate::{Table, TableItem};
+use std::cmp::Ordering;
+
+/// The marker written to a bounded byte stream when the table is cleared.
+const RESET: u8 = 127 << 1;
+
+/// The marker written to a bounded byte stream when a single slot in the
+/// table is replaced. It is followed by the slot and the bytes of the new item.
+const EVICT: u8 = (127 << 1) | 1;
+
+/// How a [BoundedTable] makes room for a new item once it is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Replace the item that was least recently used.
+    Lru,
+
+    /// Clear the whole table and start filling it again.
+    Reset,
+}
+
+/// A table with a maximum size, for streaming unbounded alphabets with
+/// [encode_bytes](BoundedTable::encode_bytes) over many calls.
+///
+/// When an item is encountered and the table is full, an item is evicted
+/// according to the [EvictionPolicy], and the eviction is recorded in the
+/// byte stream. A receiver decoding the stream with its own `BoundedTable`
+/// (created with the same size and policy) will stay in sync without ever
+/// being sent the table itself.
+///
+/// Unlike [Table], a bounded table always uses the default [SortedStore](crate::SortedStore),
+/// because evicting an item replaces it in place, which isn't something a
+/// [TableStore](crate::TableStore) can do.
+#[derive(Clone, Debug)]
+pub struct BoundedTable<T> {
+    table: Table<T>,
+    max_len: usize,
+    policy: EvictionPolicy,
+    last_used: Vec<u64>,
+    clock: u64,
+}
+
+impl<T> BoundedTable<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Constructs a new, empty table that holds at most `max_len` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is 0 or greater than 127.
+    pub fn new(max_len: usize, policy: EvictionPolicy) -> Self {
+        assert!(
+            max_len > 0 && max_len < 128,
+            "max_len must be between 1 and 127"
+        );
+        Self {
+            table: Table::with_capacity(max_len),
+            max_len,
+            policy,
+            last_used: Vec::with_capacity(max_len),
+            clock: 0,
+        }
+    }
+
+    /// The items currently in the table.
+    pub fn table(&self) -> &Table<T> {
+        &self.table
+    }
+
+    /// The maximum number of items the table can hold.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// How items are evicted once the table is full.
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// Returns an iterator to run-length encode the items as a sequence of
+    /// bytes, inserting and evicting items in the table as required.
+    ///
+    /// The format is the same as [encode_bytes_inline](Table::encode_bytes_inline),
+    /// with two additional markers using the reserved index `127`: a byte of
+    /// `0xFE` means the table was cleared, and a byte of `0xFF` means the slot
+    /// in the following byte was replaced with the item whose bytes come after it.
+    pub fn encode_bytes<'a>(&'a mut self, items: &'a [T]) -> BoundedBytesEncoder<'a, T> {
+        BoundedBytesEncoder {
+            table: self,
+            items,
+            index: 0,
+            packet: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Return an iterator that decodes a sequence of bytes produced by
+    /// [encode_bytes](BoundedTable::encode_bytes), applying the insertions
+    /// and evictions recorded in it to this table.
+    pub fn decode_bytes<'a>(&'a mut self, bytes: &'a [u8]) -> BoundedBytesDecoder<'a, T> {
+        BoundedBytesDecoder {
+            table: self,
+            bytes,
+            run: None,
+        }
+    }
+
+    fn touch(&mut self, index: usize) {
+        self.clock += 1;
+        if index < self.last_used.len() {
+            self.last_used[index] = self.clock;
+        } else {
+            self.last_used.push(self.clock);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.table.clear();
+        self.last_used.clear();
+    }
+}
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// compressed byte format using a [BoundedTable]. See [encode_bytes](BoundedTable::encode_bytes).
+pub struct BoundedBytesEncoder<'a, T> {
+    table: &'a mut BoundedTable<T>,
+    items: &'a [T],
+    index: usize,
+    packet: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a, T> Iterator for BoundedBytesEncoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&byte) = self.packet.get(self.pos) {
+            self.pos += 1;
+            return Some(byte);
+        }
+        if self.index >= self.items.len() {
+            return None;
+        }
+        self.packet.clear();
+        self.pos = 1;
+
+        let item = &self.items[self.index];
+        let mut len = 1;
+        while len < 127
+            && self.index + len < self.items.len()
+            && item.cmp(&self.items[self.index + len]) == Ordering::Equal
+        {
+            len += 1;
+        }
+        self.index += len;
+
+        let table = &mut *self.table;
+        let mut is_new = false;
+        let ind = match table.table.get_index(item) {
+            Some(ind) => ind,
+            None if table.table.len() < table.max_len => {
+                is_new = true;
+                table.table.insert_or_get(item)
+            }
+            None => match table.policy {
+                EvictionPolicy::Reset => {
+                    self.packet.push(RESET);
+                    table.clear();
+                    is_new = true;
+                    table.table.insert_or_get(item)
+                }
+                EvictionPolicy::Lru => {
+                    let slot = (0..table.last_used.len())
+                        .min_by_key(|&i| table.last_used[i])
+                        .unwrap();
+                    self.packet.push(EVICT);
+                    self.packet.push(slot as u8);
+                    item.write_bytes(&mut self.packet);
+                    table.table.replace(slot, item.clone());
+                    slot
+                }
+            },
+        };
+        table.touch(ind);
+
+        if len > 1 {
+            self.packet.push(((ind as u8) << 1) | 1);
+            self.packet.push(len as u8);
+        } else {
+            self.packet.push((ind as u8) << 1);
+        }
+        if is_new {
+            item.write_bytes(&mut self.packet);
+        }
+        Some(self.packet[0])
+    }
+}
+
+/// An iterator that decodes a sequence of bytes into a series of `T`
+/// values using a [BoundedTable]. See [decode_bytes](BoundedTable::decode_bytes).
+pub struct BoundedBytesDecoder<'a, T> {
+    table: &'a mut BoundedTable<T>,
+    bytes: &'a [u8],
+    run: Option<(usize, usize)>,
+}
+
+impl<'a, T> BoundedBytesDecoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    fn read_item(&mut self) -> Option<T> {
+        let (item, size) = T::read_bytes(self.bytes)?;
+        self.bytes = &self.bytes[size..];
+        Some(item)
+    }
+
+    fn read_run(&mut self) -> Option<(usize, usize)> {
+        loop {
+            let (&ind, rest) = self.bytes.split_first()?;
+            match ind {
+                RESET => {
+                    self.bytes = rest;
+                    self.table.clear();
+                }
+                EVICT => {
+                    let (&slot, rest) = rest.split_first()?;
+                    self.bytes = rest;
+                    let item = self.read_item()?;
+                    if (slot as usize) >= self.table.table.len() {
+                        return None;
+                    }
+                    self.table.table.replace(slot as usize, item);
+                }
+                _ => {
+                    let (ind, len, size) = read_run(self.bytes, 0).ok()??;
+                    self.bytes = &self.bytes[size..];
+                    if ind == self.table.table.len() {
+                        let item = self.read_item()?;
+                        self.table.table.insert(item);
+                    }
+                    return Some((ind, len));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for BoundedBytesDecoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.run.take().or_else(|| self.read_run())?;
+        if len > 1 {
+            self.run = Some((ind, len - 1));
+        }
+        self.table.table.get(ind).cloned()
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Error, Index, Table, TableStore};
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes (see [encode_bytes](Table::encode_bytes)),
+    /// appending them to `bytes`.
+    ///
+    /// Rather than producing one byte at a time like [BytesEncoder](crate::BytesEncoder),
+    /// this writes each run in one go, which is much faster for large inputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [encode_bytes](Table::encode_bytes). If an
+    /// error occurs, `bytes` is left as it was.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let mut bytes = Vec::new();
+    /// table.encode_bytes_into(&str, &mut bytes).unwrap();
+    /// assert_eq!(bytes, [0x01, 0x05, 0x03, 0x0A, 0x05, 0x03, 0x01, 0x0A]);
+    /// ```
+    pub fn encode_bytes_into(&self, items: &[T], bytes: &mut Vec<u8>) -> Result<(), Error> {
+        if self.len() >= 128 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let start = bytes.len();
+        let mut i = 0;
+        while i < items.len() {
+            let ind = match self.get_index(&items[i]) {
+                Some(ind) => ind as u8,
+                None => {
+                    bytes.truncate(start);
+                    return Err(Error::TableMissingItems(i));
+                }
+            };
+            let len = self.run_len(&items[i..]);
+            i += len;
+            push_run(bytes, ind, len);
+        }
+        Ok(())
+    }
+
+    /// Decodes run-length encoded bytes (see [decode_bytes](Table::decode_bytes)),
+    /// appending the decoded items to `items`.
+    ///
+    /// Rather than producing one item at a time like [BytesDecoder](crate::BytesDecoder),
+    /// this writes each run in one go, which is much faster for large inputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if a run refers
+    /// to an index not in the table, or [TruncatedStream](Error::TruncatedStream)
+    /// if the bytes end in the middle of a run. If an error occurs, `items`
+    /// is left as it was.
+    pub fn decode_bytes_into(&self, bytes: &[u8], items: &mut Vec<T>) -> Result<(), Error> {
+        let start = items.len();
+        let mut offset = 0;
+        loop {
+            let (ind, len, size) = match read_run(bytes, offset) {
+                Ok(Some(run)) => run,
+                Ok(None) => return Ok(()),
+                Err(err) => {
+                    items.truncate(start);
+                    return Err(err);
+                }
+            };
+            match self.item(ind) {
+                Some(item) => items.resize(items.len() + len, item.clone()),
+                None => {
+                    items.truncate(start);
+                    return Err(Error::UnknownIndex(ind));
+                }
+            }
+            offset += size;
+        }
+    }
+
+    /// Decodes the runs (see [decode](Table::decode)), extending `items`
+    /// with each run in one go rather than item by item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidRun](Error::InvalidRun) error if a run refers to an
+    /// index not in the table. Runs before the offending one will already have
+    /// been added to `items`.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// use std::collections::VecDeque;
+    ///
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let mut items = VecDeque::new();
+    /// table.decode_into(&[(0, 2), (1, 3)], &mut items).unwrap();
+    /// assert_eq!(items, ['A', 'A', 'B', 'B', 'B']);
+    /// ```
+    pub fn decode_into<E: Extend<T>>(
+        &self,
+        runs: &[(Index, usize)],
+        items: &mut E,
+    ) -> Result<(), Error> {
+        for (i, &(ind, len)) in runs.iter().enumerate() {
+            match self.item(ind) {
+                Some(item) => items.extend(std::iter::repeat_n(item.clone(), len)),
+                None => return Err(Error::InvalidRun(i, ind)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes run-length encoded bytes (see [decode_bytes](Table::decode_bytes))
+    /// into the start of `items`, returning how many items were written.
+    ///
+    /// The bytes are checked in full before anything is written, so `items`
+    /// is left untouched if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [decode_bytes_into](Table::decode_bytes_into),
+    /// or [BufferTooSmall](Error::BufferTooSmall) if the decoded items do not
+    /// fit in `items`.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let mut items = ['-'; 8];
+    /// let len = table.decode_bytes_to_slice(&[0x01, 0x03, 0x02], &mut items).unwrap();
+    /// assert_eq!(len, 4);
+    /// assert_eq!(items, ['A', 'A', 'A', 'B', '-', '-', '-', '-']);
+    /// ```
+    pub fn decode_bytes_to_slice(&self, bytes: &[u8], items: &mut [T]) -> Result<usize, Error> {
+        // First pass validates the runs and totals their lengths
+        let mut total = 0;
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(bytes, offset)? {
+            if ind >= self.len() {
+                return Err(Error::UnknownIndex(ind));
+            }
+            total += len;
+            offset += size;
+        }
+        if total > items.len() {
+            return Err(Error::BufferTooSmall(total));
+        }
+
+        let mut pos = 0;
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(bytes, offset)? {
+            if let Some(item) = self.item(ind) {
+                items[pos..pos + len].fill(item.clone());
+            }
+            pos += len;
+            offset += size;
+        }
+        Ok(total)
+    }
+}
+
+/// Reads the run at `offset` in the byte format (see [encode_bytes](Table::encode_bytes)),
+/// returning its index, length and size in bytes, or `None` at the end of the bytes.
+///
+/// A length byte of 0 is read as a run of 1, the same as a run with no length
+/// byte. Every reader of the format goes through this, so they all agree.
+pub(crate) fn read_run(
+    bytes: &[u8],
+    offset: usize,
+) -> Result<Option<(Index, usize, usize)>, Error> {
+    let ind = match bytes.get(offset) {
+        Some(&ind) => ind,
+        None => return Ok(None),
+    };
+    if (ind & 1) == 1 {
+        let len = *bytes.get(offset + 1).ok_or(Error::TruncatedStream)?;
+        Ok(Some(((ind >> 1) as Index, (len as usize).max(1), 2)))
+    } else {
+        Ok(Some(((ind >> 1) as Index, 1, 1)))
+    }
+}
+
+/// Writes a run in the byte format (see [encode_bytes](Table::encode_bytes)).
+pub(crate) fn push_run(bytes: &mut Vec<u8>, ind: u8, len: usize) {
+    push_run_max(bytes, ind, len, MAX_RUN);
+}
+
+/// The longest run written in a single header by [encode_bytes](Table::encode_bytes).
+pub(crate) const MAX_RUN: usize = 127;
+
+/// The most items a table can hold and still be encoded with [encode_bytes](Table::encode_bytes),
+/// since every index has to fit in the 7 bits of a run header.
+pub(crate) const MAX_TABLE_LEN: usize = 127;
+
+/// Writes a run like [push_run], but splitting it into runs of at most
+/// `max_run` (which must be from 1 to 255).
+pub(crate) fn push_run_max(bytes: &mut Vec<u8>, ind: u8, len: usize, max_run: usize) {
+    // Runs longer than the maximum are split into as many full runs as
+    // needed, followed by whatever is left over
+    let ind = ind << 1;
+    let (full, rest) = (len / max_run, len % max_run);
+    bytes.reserve(full * 2 + 2);
+    for _ in 0..full {
+        match max_run {
+            1 => bytes.push(ind),
+            _ => bytes.extend_from_slice(&[ind | 1, max_run as u8]),
+        }
+    }
+    match rest {
+        0 => {}
+        1 => bytes.push(ind),
+        _ => bytes.extend_from_slice(&[ind | 1, rest as u8]),
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{BytesEncoder, Encoder, Error, Table, TableStore};
+use std::convert::TryInto;
+
+/// Returns how many items at the start of the slice are equal to the first.
+pub(crate) fn run_len<T: PartialEq>(items: &[T]) -> usize {
+    match items.split_first() {
+        Some((first, rest)) => 1 + rest.iter().take_while(|&item| item == first).count(),
+        None => 0,
+    }
+}
+
+/// The same as [run_len], but compares the bytes 8 at a time.
+pub(crate) fn byte_run_len(bytes: &[u8]) -> usize {
+    let first = match bytes.first() {
+        Some(&first) => first,
+        None => return 0,
+    };
+    let splat = u64::from_le_bytes([first; 8]);
+    let mut chunks = bytes.chunks_exact(8);
+    let mut len = 0;
+    for chunk in &mut chunks {
+        // Every byte equal to `first` will be zero, so the first non-zero
+        // byte is where the run ends
+        let diff = u64::from_le_bytes(chunk.try_into().unwrap()) ^ splat;
+        if diff != 0 {
+            return len + diff.trailing_zeros() as usize / 8;
+        }
+        len += 8;
+    }
+    len + chunks
+        .remainder()
+        .iter()
+        .take_while(|&&b| b == first)
+        .count()
+}
+
+impl<S> Table<u8, S>
+where
+    S: TableStore<u8>,
+{
+    /// The same as [encode](Table::encode), but finds the end of each run by
+    /// comparing 8 bytes at a time instead of one, which is much faster when
+    /// the input has long runs.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&[0, 255]);
+    /// let mut pixels = vec![0u8; 1000];
+    /// pixels[900..].fill(255);
+    ///
+    /// let runs: Vec<_> = table.encode_u8(&pixels).unwrap().collect();
+    /// assert_eq!(runs, [(0, 900), (1, 100)]);
+    /// ```
+    pub fn encode_u8<'a>(&'a self, items: &'a [u8]) -> Result<Encoder<'a, u8, S>, Error> {
+        self.encode_with(items, |_, items| byte_run_len(items))
+    }
+
+    /// The same as [encode_bytes](Table::encode_bytes), but scans for runs
+    /// like [encode_u8](Table::encode_u8).
+    pub fn encode_bytes_u8<'a>(
+        &'a self,
+        items: &'a [u8],
+    ) -> Result<BytesEncoder<'a, u8, S>, Error> {
+        if self.len() < 128 {
+            Ok(BytesEncoder {
+                rle: self.encode_u8(items)?,
+                run: None,
+                len: None,
+                metrics: None,
+            })
+        } else {
+            Err(Error::TableTooLarge(self.len()))
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! A table-free byte format for raw byte data, where each byte value is
+//! its own symbol.
+//!
+//! # Format
+//!
+//! Each run is written as a packet of two bytes: the length of the run
+//! (from 1 to 255), followed by the byte that is repeated. Runs longer than
+//! 255 are split across several packets.
+//!
+//! ```
+//! use rle::bytes::{decode_u8, encode_u8};
+//!
+//! let data = [7, 7, 7, 7, 0, 255, 255];
+//! let packed = encode_u8(&data);
+//! assert_eq!(packed, [4, 7, 1, 0, 2, 255]);
+//! assert_eq!(decode_u8(&packed).unwrap(), data);
+//! ```
+
+use crate::byte_scan::byte_run_len;
+use crate::Error;
+
+/// Run-length encodes the bytes, treating each byte value as its own symbol.
+pub fn encode_u8(bytes: &[u8]) -> Vec<u8> {
+    let mut packed = Vec::new();
+    encode_u8_into(bytes, &mut packed);
+    packed
+}
+
+/// Run-length encodes the bytes like [encode_u8], appending the packets to `packed`.
+pub fn encode_u8_into(bytes: &[u8], packed: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < bytes.len() {
+        let len = byte_run_len(&bytes[i..]);
+        let (full, rest) = (len / 255, len % 255);
+        packed.reserve(full * 2 + 2);
+        for _ in 0..full {
+            packed.extend_from_slice(&[255, bytes[i]]);
+        }
+        if rest > 0 {
+            packed.extend_from_slice(&[rest as u8, bytes[i]]);
+        }
+        i += len;
+    }
+}
+
+/// Decodes bytes that were encoded with [encode_u8].
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes end
+/// in the middle of a packet, or an [InvalidBytes](Error::InvalidBytes) error
+/// if a packet has a length of 0.
+pub fn decode_u8(packed: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    decode_u8_into(packed, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decodes bytes like [decode_u8], appending them to `bytes`. If an error
+/// occurs, `bytes` is left as it was.
+///
+/// # Errors
+///
+/// Returns the same errors as [decode_u8].
+pub fn decode_u8_into(packed: &[u8], bytes: &mut Vec<u8>) -> Result<(), Error> {
+    if packed.len() % 2 == 1 {
+        return Err(Error::TruncatedStream);
+    }
+    let total: usize = packed
+        .chunks_exact(2)
+        .map(|packet| packet[0] as usize)
+        .sum();
+    if let Some(i) = packed.chunks_exact(2).position(|packet| packet[0] == 0) {
+        return Err(Error::InvalidBytes(i * 2));
+    }
+    bytes.reserve(total);
+    for packet in packed.chunks_exact(2) {
+        bytes.resize(bytes.len() + packet[0] as usize, packet[1]);
+    }
+    Ok(())
+}
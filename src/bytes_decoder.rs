@@ -1,44 +1,222 @@
-use crate::{Index, Table};
+use crate::bulk::read_run;
+use crate::{DecoderState, Index, Metrics, SortedStore, Table, TableStore};
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::time::Instant;
 
 /// An iterator that decodes a run-length encoded sequence of bytes into
 /// a series of `T` values fetched from the table. See [decode_bytes](crate::Table::decode_bytes).
-pub struct BytesDecoder<'a, T>
+pub struct BytesDecoder<'a, T, S = SortedStore<T>>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
 {
-    pub(crate) table: &'a Table<T>,
+    pub(crate) table: &'a Table<T, S>,
     pub(crate) bytes: &'a [u8],
     pub(crate) run: Option<(Index, usize)>,
+    pub(crate) offset: usize,
+    pub(crate) position: usize,
+    pub(crate) metrics: Option<&'a Metrics>,
+
+    /// Runs that were read ahead to decode from the back.
+    pub(crate) back: VecDeque<(Index, usize)>,
+}
+
+impl<'a, T, S> BytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// A snapshot of how far this decoder has progressed, which can be
+    /// used to resume decoding later with [resume_bytes](crate::Table::resume_bytes).
+    ///
+    /// Once items have been taken from the back with
+    /// [next_back](DoubleEndedIterator::next_back), the remaining runs are
+    /// buffered inside the decoder, so its state can no longer be resumed.
+    pub fn state(&self) -> DecoderState {
+        DecoderState {
+            byte_offset: self.offset,
+            element_offset: self.position,
+            run: self.run,
+        }
+    }
+
+    /// Records the bytes, runs and items this decoder reads, and the time
+    /// spent decoding them, in the collector.
+    pub fn metrics(mut self, metrics: &'a Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn read_run(&mut self) -> Option<(Index, usize)> {
+        // Timed once per run, rather than once per item
+        let start = self.metrics.map(|_| Instant::now());
+        let (ind, len, size) = match read_run(self.bytes, 0) {
+            Ok(Some(run)) => run,
+            Ok(None) => return None,
+            Err(_) => {
+                // A truncated run ends the bytes
+                self.bytes = &[];
+                return None;
+            }
+        };
+        self.bytes = &self.bytes[size..];
+        self.offset += size;
+        if let (Some(metrics), Some(start)) = (self.metrics, start) {
+            metrics.add_decoded_run(size, start);
+        }
+        Some((ind, len))
+    }
+
+    /// Reads the next run from the bytes, or from the runs that were
+    /// read ahead if the bytes have all been read.
+    fn next_run(&mut self) -> Option<(Index, usize)> {
+        self.read_run().or_else(|| self.back.pop_front())
+    }
+
+    /// Stops decoding for good, after an index that isn't in the table.
+    fn stop(&mut self) {
+        self.bytes = &[];
+        self.run = None;
+        self.back.clear();
+    }
+
+    fn next_item(&mut self) -> Option<&'a T> {
+        let (ind, len) = self.run.take().or_else(|| self.next_run())?;
+        match self.table.item(ind) {
+            Some(item) => {
+                if len > 1 {
+                    self.run = Some((ind, len - 1));
+                }
+                self.position += 1;
+                Some(item)
+            }
+            None => {
+                self.stop();
+                None
+            }
+        }
+    }
 }
 
-impl<'a, T> Iterator for BytesDecoder<'a, T>
+impl<'a, T, S> Iterator for BytesDecoder<'a, T, S>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.run
-            .take()
-            .or_else(|| {
-                self.bytes.get(0).and_then(|&ind| {
-                    self.bytes = &self.bytes[1..];
-                    let ind = ind as usize;
-                    if (ind & 1) == 1 {
-                        self.bytes.get(0).and_then(|&len| {
-                            self.bytes = &self.bytes[1..];
-                            Some((ind >> 1, len as usize))
-                        })
-                    } else {
-                        Some((ind >> 1, 1))
-                    }
-                })
-            })
-            .and_then(|(ind, len)| {
+        let item = self.next_item();
+        if let Some(metrics) = self.metrics {
+            metrics.add_decoded_items(item.is_some() as usize);
+        }
+        item
+    }
+
+    /// The lower bound is the rest of the current run, and the upper bound
+    /// assumes every remaining pair of bytes is a run of 255 items.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let run = match self.run {
+            Some((ind, len)) if self.table.item(ind).is_some() => len,
+            _ => 0,
+        };
+        let max = (self.bytes.len() / 2) * 255 + self.bytes.len() % 2;
+        let back: usize = self.back.iter().map(|&(_, len)| len.max(1)).sum();
+        (run, Some(run + max + back))
+    }
+
+    /// Skips whole runs at a time, rather than decoding every element, but
+    /// still stops at the first run with an index that isn't in the table.
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        while n > 0 {
+            let (ind, len) = self.run.take().or_else(|| self.next_run())?;
+            if self.table.item(ind).is_none() {
+                self.stop();
+                return None;
+            }
+            let num = len.min(n);
+            if len > num {
+                self.run = Some((ind, len - num));
+            }
+            if let Some(metrics) = self.metrics {
+                metrics.add_decoded_items(num);
+            }
+            self.position += num;
+            n -= num;
+        }
+        self.next()
+    }
+}
+
+/// Since the byte format can only be read forwards, the first call to
+/// `next_back` reads all of the remaining runs ahead into a buffer.
+impl<'a, T, S> DoubleEndedIterator for BytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(run) = self.read_run() {
+            self.back.push_back(run);
+        }
+        let (ind, len) = self.back.pop_back().or_else(|| self.run.take())?;
+        match self.table.item(ind) {
+            Some(item) => {
                 if len > 1 {
-                    self.run = Some((ind, len - 1));
+                    self.back.push_back((ind, len - 1));
                 }
-                self.table.get(ind)
-            })
+                Some(item)
+            }
+            None => {
+                self.stop();
+                None
+            }
+        }
+    }
+}
+
+impl<'a, T, S> FusedIterator for BytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
+
+/// An iterator that decodes a run-length encoded sequence of bytes into a
+/// series of `T` values fetched from the table, along with the position of
+/// each value in the sequence. See [decode_enumerated](crate::Table::decode_enumerated).
+pub struct EnumeratedDecoder<'a, T, S = SortedStore<T>>
+where
+    T: PartialEq + Clone,
+{
+    pub(crate) decoder: BytesDecoder<'a, T, S>,
+}
+
+impl<'a, T, S> Iterator for EnumeratedDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.decoder.position;
+        self.decoder.next().map(|item| (pos, item))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let item = self.decoder.nth(n)?;
+        Some((self.decoder.position - 1, item))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.decoder.size_hint()
+    }
+}
+
+impl<'a, T, S> FusedIterator for EnumeratedDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
 }
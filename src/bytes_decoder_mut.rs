@@ -0,0 +1,44 @@
+use crate::bulk::read_run;
+use crate::{Index, SortedStore, Table, TableItem, TableStore};
+
+/// An iterator that decodes a self-describing run-length encoded sequence
+/// of bytes into a series of `T` values, adding the elements embedded in
+/// the byte stream to the table as it encounters them. See [decode_bytes_mut](crate::Table::decode_bytes_mut).
+pub struct BytesDecoderMut<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a mut Table<T, S>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<'a, T, S> BytesDecoderMut<'a, T, S>
+where
+    T: PartialEq + Clone + TableItem,
+    S: TableStore<T>,
+{
+    fn read_run(&mut self) -> Option<(Index, usize)> {
+        let (ind, len, size) = read_run(self.bytes, 0).ok()??;
+        self.bytes = &self.bytes[size..];
+        if ind == self.table.len() {
+            let (item, size) = T::read_bytes(self.bytes)?;
+            self.bytes = &self.bytes[size..];
+            self.table.insert(item);
+        }
+        Some((ind, len))
+    }
+}
+
+impl<'a, T, S> Iterator for BytesDecoderMut<'a, T, S>
+where
+    T: PartialEq + Clone + TableItem,
+    S: TableStore<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.run.take().or_else(|| self.read_run())?;
+        if len > 1 {
+            self.run = Some((ind, len - 1));
+        }
+        self.table.item(ind).cloned()
+    }
+}
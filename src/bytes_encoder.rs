@@ -1,40 +1,87 @@
-use crate::Encoder;
+use crate::bulk::MAX_RUN;
+use crate::{Encoder, Metrics, SortedStore, TableStore};
+use std::iter::FusedIterator;
+use std::time::Instant;
 
 /// An iterator that run-length encodes a sequence of `T` values
 /// into a compressed byte format. See [encode_bytes](crate::Table::encode_bytes).
-pub struct BytesEncoder<'a, T> {
-    pub(crate) rle: Encoder<'a, T>,
+pub struct BytesEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) rle: Encoder<'a, T, S>,
     pub(crate) run: Option<(u8, usize)>,
     pub(crate) len: Option<u8>,
+    pub(crate) metrics: Option<&'a Metrics>,
 }
 
-impl<'a, T> Iterator for BytesEncoder<'a, T>
+impl<'a, T, S> BytesEncoder<'a, T, S>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
 {
-    type Item = u8;
+    /// Records the items, runs and bytes this encoder outputs, and the time
+    /// spent encoding them, in the collector.
+    pub fn metrics(mut self, metrics: &'a Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_byte(&mut self) -> Option<u8> {
         if let Some(len) = self.len.take() {
-            Some(len)
-        } else if let Some((ind, len)) = self.run.take().or_else(|| {
-            self.rle
-                .next()
-                .and_then(|(ind, len)| Some((ind as u8, len)))
-        }) {
-            let num = len.min(127);
-            let ind_bits = if len > 1 {
-                self.len = Some(num as u8);
-                (ind << 1) | 1
-            } else {
-                ind << 1
-            };
-            if len > num {
-                self.run = Some((ind, len - num));
-            }
-            Some(ind_bits)
+            return Some(len);
+        }
+        // Timed once per run, rather than once per byte
+        let start = self.metrics.map(|_| Instant::now());
+        let (ind, len) = self
+            .run
+            .take()
+            .or_else(|| self.rle.next().map(|(ind, len)| (ind as u8, len)))?;
+        let num = len.min(MAX_RUN);
+        let ind_bits = if len > 1 {
+            self.len = Some(num as u8);
+            (ind << 1) | 1
         } else {
-            None
+            ind << 1
+        };
+        if len > num {
+            self.run = Some((ind, len - num));
+        }
+        if let (Some(metrics), Some(start)) = (self.metrics, start) {
+            metrics.add_encoded_run(num, start);
+        }
+        Some(ind_bits)
+    }
+}
+
+impl<'a, T, S> Iterator for BytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = self.next_byte();
+        if let Some(metrics) = self.metrics {
+            metrics.add_encoded_bytes(byte.is_some() as usize);
         }
+        byte
+    }
+
+    /// A run of `n` items never takes more than `n` bytes, and there is
+    /// at least one byte for each pending run.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len.is_some() as usize;
+        let (run_min, run_max) = self.run.map_or((0, 0), |(_, len)| (1, len));
+        let items = self.rle.items.len() - self.rle.index;
+        (
+            len + run_min + (items > 0) as usize,
+            Some(len + run_max + items),
+        )
     }
 }
+
+impl<'a, T, S> FusedIterator for BytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
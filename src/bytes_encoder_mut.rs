@@ -1,17 +1,20 @@
-use crate::{EncoderMut, Error};
+use crate::bulk::MAX_RUN;
+use crate::{EncoderMut, Error, SortedStore, TableStore};
+use std::iter::FusedIterator;
 
 /// An iterator that run-length encodes a sequence of `T` values
 /// into a compressed byte format, and also adds elements to the
 /// table as it encounters them. See [encode_bytes_mut](crate::Table::encode_bytes_mut).
-pub struct BytesEncoderMut<'a, T> {
-    pub(crate) rle: EncoderMut<'a, T>,
+pub struct BytesEncoderMut<'a, T, S = SortedStore<T>> {
+    pub(crate) rle: EncoderMut<'a, T, S>,
     pub(crate) run: Option<(u8, usize)>,
     pub(crate) len: Option<u8>,
 }
 
-impl<'a, T> Iterator for BytesEncoderMut<'a, T>
+impl<'a, T, S> Iterator for BytesEncoderMut<'a, T, S>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
 {
     type Item = Result<u8, Error>;
 
@@ -30,8 +33,8 @@ where
             } else {
                 None
             })
-            .and_then(|(ind, len)| {
-                let num = len.min(127);
+            .map(|(ind, len)| {
+                let num = len.min(MAX_RUN);
                 let ind_bits = if len > 1 {
                     self.len = Some(num as u8);
                     (ind << 1) | 1
@@ -41,8 +44,27 @@ where
                 if len > num {
                     self.run = Some((ind, len - num));
                 }
-                Some(Ok(ind_bits))
+                Ok(ind_bits)
             })
         }
     }
+
+    /// A run of `n` items never takes more than `n` bytes, and there is
+    /// at least one byte for each pending run.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len.is_some() as usize;
+        let (run_min, run_max) = self.run.map_or((0, 0), |(_, len)| (1, len));
+        let items = self.rle.items.len() - self.rle.index;
+        (
+            len + run_min + (items > 0) as usize,
+            Some(len + run_max + items),
+        )
+    }
+}
+
+impl<'a, T, S> FusedIterator for BytesEncoderMut<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
 }
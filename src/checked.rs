@@ -0,0 +1,147 @@
+use crate::bulk::read_run;
+use crate::{Error, Index, SortedStore, Table, TableStore};
+
+/// An iterator that decodes a run-length encoded sequence of bytes into a
+/// series of `T` values fetched from the table, reporting corrupted input as
+/// an error instead of stopping early. See [decode_bytes_checked](crate::Table::decode_bytes_checked).
+pub struct CheckedBytesDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) run: Option<(&'a T, usize)>,
+    pub(crate) failed: bool,
+}
+
+impl<'a, T, S> CheckedBytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn read_run(&mut self) -> Option<Result<(&'a T, usize), Error>> {
+        let (ind, len, size) = match read_run(self.bytes, 0) {
+            Ok(run) => run?,
+            Err(err) => return Some(Err(err)),
+        };
+        self.bytes = &self.bytes[size..];
+        Some(match self.table.item(ind) {
+            Some(item) => Ok((item, len)),
+            None => Err(Error::UnknownIndex(ind)),
+        })
+    }
+}
+
+impl<'a, T, S> Iterator for CheckedBytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = Result<&'a T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let (item, len) = match self.run.take() {
+            Some(run) => run,
+            None => match self.read_run()? {
+                Ok(run) => run,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            },
+        };
+        if len > 1 {
+            self.run = Some((item, len - 1));
+        }
+        Some(Ok(item))
+    }
+}
+
+/// An iterator that decodes a sequence of runs into a series of `T` values
+/// fetched from the table, reporting runs with indices not in the table as an
+/// error. See [try_decode](crate::Table::try_decode).
+pub struct CheckedDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) runs: &'a [(Index, usize)],
+    pub(crate) position: usize,
+    pub(crate) run: Option<(&'a T, usize)>,
+    pub(crate) failed: bool,
+}
+
+impl<'a, T, S> Iterator for CheckedDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = Result<&'a T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let (item, len) = match self.run.take() {
+                Some(run) => run,
+                None => {
+                    let (&(ind, len), rest) = self.runs.split_first()?;
+                    self.runs = rest;
+                    self.position += 1;
+                    match self.table.item(ind) {
+                        Some(item) => (item, len),
+                        None => {
+                            self.failed = true;
+                            return Some(Err(Error::InvalidRun(self.position - 1, ind)));
+                        }
+                    }
+                }
+            };
+            if len > 1 {
+                self.run = Some((item, len - 1));
+            }
+            if len > 0 {
+                return Some(Ok(item));
+            }
+        }
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Return an iterator that decodes the series of runs using this table as
+    /// the index lookup for the elements, like [decode](Table::decode).
+    ///
+    /// Instead of silently stopping, the iterator yields an [InvalidRun](Error::InvalidRun)
+    /// error if a run refers to an index not in the table, which usually means
+    /// the runs were encoded with a different table. Nothing more is decoded
+    /// after an error. As with [decode](Table::decode), runs of zero length
+    /// decode to nothing, but their index is still checked.
+    pub fn try_decode<'a>(&'a self, runs: &'a [(Index, usize)]) -> CheckedDecoder<'a, T, S> {
+        CheckedDecoder {
+            table: self,
+            runs,
+            position: 0,
+            run: None,
+            failed: false,
+        }
+    }
+
+    /// Return an iterator that decodes the run-length encoded bytes using this
+    /// table as the index lookup for the elements, like [decode_bytes](Table::decode_bytes).
+    ///
+    /// Instead of silently stopping, the iterator yields an [UnknownIndex](Error::UnknownIndex)
+    /// error if a run refers to an index not in the table, or a [TruncatedStream](Error::TruncatedStream)
+    /// error if the bytes end before a run's length. Nothing more is decoded
+    /// after an error. As with [decode_bytes](Table::decode_bytes), a length
+    /// byte of 0 decodes to a single item.
+    pub fn decode_bytes_checked<'a>(&'a self, bytes: &'a [u8]) -> CheckedBytesDecoder<'a, T, S> {
+        CheckedBytesDecoder {
+            table: self,
+            bytes,
+            run: None,
+            failed: false,
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{Error, GridLayout, Table, TableStore};
+use std::convert::TryInto;
+
+/// The magic bytes at the start of every chunk.
+const CHUNK_MAGIC: &[u8; 4] = b"RLEC";
+
+/// The size of the chunk header in bytes.
+const HEADER_LEN: usize = 13;
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Encodes a grid of items, stored in row-major order, into a single
+    /// self-contained chunk that records its own dimensions, such as a
+    /// section of a tilemap. It can be decoded with [decode_chunk](Table::decode_chunk).
+    ///
+    /// # Format
+    ///
+    /// The chunk starts with a 13 byte header:
+    ///
+    /// - The magic bytes `RLEC`.
+    /// - The width and height as little-endian `u32`s.
+    /// - The number of items in the table, as a `u8`.
+    ///
+    /// The rest of the chunk is the grid encoded as in [encode_bytes](Table::encode_bytes).
+    /// The table itself is not stored, so it must be kept alongside the chunks.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let tiles: Vec<char> = "....##..".chars().collect();
+    /// let table = Table::from_slice(&['.', '#']);
+    ///
+    /// let chunk = table.encode_chunk(4, 2, &tiles).unwrap();
+    /// assert_eq!(&chunk[..4], b"RLEC");
+    /// assert_eq!(table.decode_chunk(&chunk).unwrap(), (4, 2, tiles));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_grid](Table::encode_grid),
+    /// or with an [InvalidGridSize](Error::InvalidGridSize) error if the width
+    /// or height doesn't fit in a `u32`.
+    pub fn encode_chunk(&self, width: usize, height: usize, items: &[T]) -> Result<Vec<u8>, Error> {
+        let (w, h) = match (width.try_into(), height.try_into()) {
+            (Ok(w), Ok(h)) => (w, h),
+            _ => return Err(Error::InvalidGridSize(items.len())),
+        };
+        let encoded = self.encode_grid(GridLayout::new(width, height), items)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + encoded.len());
+        bytes.extend_from_slice(CHUNK_MAGIC);
+        bytes.extend_from_slice(&u32::to_le_bytes(w));
+        bytes.extend_from_slice(&u32::to_le_bytes(h));
+        bytes.push(self.len() as u8);
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+
+    /// Decodes a chunk produced by [encode_chunk](Table::encode_chunk),
+    /// returning its width, height and items in row-major order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TruncatedStream](Error::TruncatedStream) error if the header
+    /// is incomplete, or an [InvalidBytes](Error::InvalidBytes) error with the
+    /// offset of the problem if the magic bytes are wrong or the chunk was
+    /// encoded with a larger table than this one. Otherwise, fails under the
+    /// same conditions as [decode_grid](Table::decode_grid).
+    pub fn decode_chunk(&self, bytes: &[u8]) -> Result<(usize, usize, Vec<T>), Error> {
+        let header = bytes.get(..HEADER_LEN).ok_or(Error::TruncatedStream)?;
+        if &header[..4] != CHUNK_MAGIC {
+            return Err(Error::InvalidBytes(0));
+        }
+        let width = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+        let height = u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize;
+        if header[12] as usize > self.len() {
+            return Err(Error::InvalidBytes(12));
+        }
+        let items = self.decode_grid(GridLayout::new(width, height), &bytes[HEADER_LEN..])?;
+        Ok((width, height, items))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::bulk::read_run;
+use crate::{Index, Table};
+
+/// An iterator that decodes a run-length encoded sequence of bytes into
+/// fixed-size blocks of `T` values. See [decode_chunks](crate::Table::decode_chunks).
+pub struct ChunkDecoder<'a, T> {
+    pub(crate) table: &'a Table<T>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) size: usize,
+}
+
+impl<'a, T> ChunkDecoder<'a, T>
+where
+    T: Ord + Clone,
+{
+    fn read_run(&mut self) -> Option<(Index, usize)> {
+        let (ind, len, size) = read_run(self.bytes, 0).ok()??;
+        self.bytes = &self.bytes[size..];
+        Some((ind, len))
+    }
+
+    /// Takes up to `max` items from the current run, returning the item and
+    /// how many of it were taken.
+    fn take(&mut self, max: usize) -> Option<(&'a T, usize)> {
+        let (ind, len) = self.run.take().or_else(|| self.read_run())?;
+        let num = len.min(max);
+        if len > num {
+            self.run = Some((ind, len - num));
+        }
+        Some((self.table.get(ind)?, num))
+    }
+
+    /// Decodes items into the buffer until it is full or the sequence ends,
+    /// returning how many items were written. This does not allocate, so the
+    /// same buffer can be reused for every block.
+    pub fn fill(&mut self, buf: &mut [T]) -> usize {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.take(buf.len() - filled) {
+                Some((item, num)) => {
+                    buf[filled..filled + num].fill(item.clone());
+                    filled += num;
+                }
+                None => break,
+            }
+        }
+        filled
+    }
+}
+
+impl<'a, T> Iterator for ChunkDecoder<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.size);
+        while chunk.len() < self.size {
+            match self.take(self.size - chunk.len()) {
+                Some((item, num)) => chunk.extend(std::iter::repeat_n(item.clone(), num)),
+                None => break,
+            }
+        }
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone,
+{
+    /// Return an iterator that decodes the run-length encoded bytes into
+    /// blocks of `size` items, using this table as the index lookup for the
+    /// elements. Runs are split across blocks as needed, and the final block
+    /// may be shorter if the sequence doesn't divide evenly.
+    ///
+    /// To decode into the same buffer for every block instead of allocating
+    /// new ones, use [fill](ChunkDecoder::fill).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn decode_chunks<'a>(&'a self, bytes: &'a [u8], size: usize) -> ChunkDecoder<'a, T> {
+        assert!(size > 0, "chunk size must be greater than 0");
+        ChunkDecoder {
+            table: self,
+            bytes,
+            run: None,
+            size,
+        }
+    }
+}
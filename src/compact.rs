@@ -0,0 +1,159 @@
+use crate::{Encoder, Error, Index, SortedStore, Table, TableStore};
+use std::iter::FusedIterator;
+
+/// The longest run that fits in one nibble-packed run.
+pub(crate) const MAX_RUN: usize = 255 + 16;
+
+/// An iterator that run-length encodes a sequence of `T` values into
+/// the nibble-packed byte format. See [encode_bytes_compact](crate::Table::encode_bytes_compact).
+pub struct CompactBytesEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) rle: Encoder<'a, T, S>,
+    pub(crate) run: Option<(u8, usize)>,
+    pub(crate) len: Option<u8>,
+}
+
+impl<'a, T, S> Iterator for CompactBytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(len) = self.len.take() {
+            return Some(len);
+        }
+        let (ind, len) = self
+            .run
+            .take()
+            .or_else(|| self.rle.next().map(|(ind, len)| (ind as u8, len)))?;
+        let num = len.min(MAX_RUN);
+        if len > num {
+            self.run = Some((ind, len - num));
+        }
+        if num < 16 {
+            Some((ind << 4) | num as u8)
+        } else {
+            self.len = Some((num - 16) as u8);
+            Some(ind << 4)
+        }
+    }
+}
+
+impl<'a, T, S> FusedIterator for CompactBytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
+
+/// An iterator that decodes the nibble-packed byte format into a series
+/// of `T` values fetched from the table. See [decode_bytes_compact](crate::Table::decode_bytes_compact).
+pub struct CompactBytesDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<'a, T, S> CompactBytesDecoder<'a, T, S> {
+    fn read_run(&mut self) -> Option<(Index, usize)> {
+        let (&byte, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        let len = match byte & 0x0F {
+            0 => {
+                let (&len, rest) = self.bytes.split_first()?;
+                self.bytes = rest;
+                len as usize + 16
+            }
+            len => len as usize,
+        };
+        Some(((byte >> 4) as Index, len))
+    }
+}
+
+impl<'a, T, S> Iterator for CompactBytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.run.take().or_else(|| self.read_run())?;
+        match self.table.item(ind) {
+            Some(item) => {
+                if len > 1 {
+                    self.run = Some((ind, len - 1));
+                }
+                Some(item)
+            }
+            None => {
+                // Stop decoding at the first index that isn't in the table
+                self.bytes = &[];
+                None
+            }
+        }
+    }
+}
+
+impl<'a, T, S> FusedIterator for CompactBytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Returns an iterator to run-length encode the items into a compact
+    /// byte format for tables with up to 16 items.
+    ///
+    /// # Format
+    ///
+    /// Each run starts with a byte where the high 4 bits are the index, and
+    /// the low 4 bits are the length of the run, so a run of up to 15 items
+    /// takes only a single byte. If the low 4 bits are `0`, the length is
+    /// instead stored in the next byte, plus 16. Runs longer than 271 items
+    /// are split into multiple runs.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAAAAAAAAAAAA".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let bytes: Vec<u8> = table.encode_bytes_compact(&str).unwrap().collect();
+    /// assert_eq!(bytes, [0x05, 0x1A, 0x23, 0x00, 0x04]);
+    /// assert!(table.decode_bytes_compact(&bytes).eq(&str));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the provided table contains more than 16 items, this will return a
+    /// [TableTooLarge](Error::TableTooLarge) error.
+    pub fn encode_bytes_compact<'a>(
+        &'a self,
+        items: &'a [T],
+    ) -> Result<CompactBytesEncoder<'a, T, S>, Error> {
+        if self.len() > 16 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        Ok(CompactBytesEncoder {
+            rle: self.encode(items)?,
+            run: None,
+            len: None,
+        })
+    }
+
+    /// Return an iterator that decodes bytes encoded with [encode_bytes_compact](Table::encode_bytes_compact)
+    /// using this table as the index lookup for the elements.
+    pub fn decode_bytes_compact<'a>(&'a self, bytes: &'a [u8]) -> CompactBytesDecoder<'a, T, S> {
+        CompactBytesDecoder {
+            table: self,
+            bytes,
+            run: None,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::bulk::read_run;
+use crate::{Index, Table, TableStore};
+
+/// Returns `true` if two sequences of runs decode to the same items, even if
+/// they were encoded with different tables or split into runs differently.
+///
+/// The runs are walked in lockstep without decoding them, stopping at the
+/// first difference. Sequences containing an index not in their table are
+/// never equal to anything.
+///
+/// ```
+/// # use rle::{runs_eq, Table};
+/// let a = Table::from_slice(&['A', 'B']);
+/// let b = Table::from_slice(&['B', 'A']);
+/// assert!(runs_eq(&[(0, 5), (1, 2)], &a, &[(1, 2), (1, 3), (0, 2)], &b));
+/// assert!(!runs_eq(&[(0, 5), (1, 2)], &a, &[(1, 5), (0, 1)], &b));
+/// ```
+pub fn runs_eq<T, S, S2>(
+    a_runs: &[(Index, usize)],
+    a_table: &Table<T, S>,
+    b_runs: &[(Index, usize)],
+    b_table: &Table<T, S2>,
+) -> bool
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    S2: TableStore<T>,
+{
+    lockstep_eq(lookup_runs(a_runs, a_table), lookup_runs(b_runs, b_table))
+}
+
+/// Like [runs_eq], but for run-length encoded bytes (see [encode_bytes](Table::encode_bytes)).
+/// Malformed bytes are never equal to anything.
+///
+/// ```
+/// # use rle::{bytes_eq, Table};
+/// let str: Vec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+/// let a = Table::from_slice(&['A', 'B', 'C']);
+/// let b = Table::from_slice(&['C', 'B', 'A']);
+/// let a_bytes: Vec<u8> = a.encode_bytes(&str).unwrap().collect();
+/// let b_bytes: Vec<u8> = b.encode_bytes(&str).unwrap().collect();
+/// assert_ne!(a_bytes, b_bytes);
+/// assert!(bytes_eq(&a_bytes, &a, &b_bytes, &b));
+/// ```
+pub fn bytes_eq<T, S, S2>(
+    a_bytes: &[u8],
+    a_table: &Table<T, S>,
+    b_bytes: &[u8],
+    b_table: &Table<T, S2>,
+) -> bool
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    S2: TableStore<T>,
+{
+    lockstep_eq(
+        lookup_bytes(a_bytes, a_table),
+        lookup_bytes(b_bytes, b_table),
+    )
+}
+
+/// Looks up the item of each run, yielding `None` for an invalid run.
+fn lookup_runs<'a, T, S>(
+    runs: &'a [(Index, usize)],
+    table: &'a Table<T, S>,
+) -> impl Iterator<Item = Option<(&'a T, usize)>> + 'a
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    runs.iter()
+        .map(move |&(ind, len)| table.item(ind).map(|item| (item, len)))
+}
+
+/// Reads and looks up each run of the bytes, yielding `None` for an invalid run.
+fn lookup_bytes<'a, T, S>(
+    bytes: &'a [u8],
+    table: &'a Table<T, S>,
+) -> impl Iterator<Item = Option<(&'a T, usize)>> + 'a
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    let mut offset = 0;
+    std::iter::from_fn(move || match read_run(bytes, offset) {
+        Ok(Some((ind, len, size))) => {
+            offset += size;
+            Some(table.item(ind).map(|item| (item, len)))
+        }
+        Ok(None) => None,
+        Err(_) => {
+            offset = bytes.len();
+            Some(None)
+        }
+    })
+}
+
+/// Compares two sequences of runs item by item, where a `None` run makes
+/// the sequences unequal.
+fn lockstep_eq<'a, T, A, B>(mut a: A, mut b: B) -> bool
+where
+    T: PartialEq + 'a,
+    A: Iterator<Item = Option<(&'a T, usize)>>,
+    B: Iterator<Item = Option<(&'a T, usize)>>,
+{
+    let (mut run_a, mut run_b) = (None, None);
+    loop {
+        // Refill each side with its next non-empty run
+        while run_a.is_none_or(|(_, len)| len == 0) {
+            match a.next() {
+                Some(Some(run)) => run_a = Some(run),
+                Some(None) => return false,
+                None => break,
+            }
+        }
+        while run_b.is_none_or(|(_, len)| len == 0) {
+            match b.next() {
+                Some(Some(run)) => run_b = Some(run),
+                Some(None) => return false,
+                None => break,
+            }
+        }
+        match (&mut run_a, &mut run_b) {
+            (Some((x, x_len)), Some((y, y_len))) if *x_len > 0 && *y_len > 0 => {
+                if x != y {
+                    return false;
+                }
+                let len = (*x_len).min(*y_len);
+                *x_len -= len;
+                *y_len -= len;
+            }
+            (a, b) => {
+                let empty = |run: &Option<(&T, usize)>| run.is_none_or(|(_, len)| len == 0);
+                return empty(a) && empty(b);
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::bulk::{push_run, read_run, MAX_TABLE_LEN};
+use crate::{Error, Index, Table, TableStore};
+
+/// Appends one sequence of runs to another, merging the run at the end of
+/// `runs` with the first run of `other` if they have the same index. Both
+/// sequences must have been encoded with the same table.
+///
+/// ```
+/// # use rle::append_runs;
+/// let mut runs = vec![(0, 3), (1, 2)];
+/// append_runs(&mut runs, &[(1, 4), (0, 1)]);
+/// assert_eq!(runs, [(0, 3), (1, 6), (0, 1)]);
+/// ```
+pub fn append_runs(runs: &mut Vec<(Index, usize)>, other: &[(Index, usize)]) {
+    let mut other = other;
+    if let (Some(last), Some((&(ind, len), rest))) = (runs.last_mut(), other.split_first()) {
+        if last.0 == ind {
+            last.1 += len;
+            other = rest;
+        }
+    }
+    runs.extend_from_slice(other);
+}
+
+/// Appends one run-length encoded byte sequence (see [encode_bytes](crate::Table::encode_bytes))
+/// to another, without decoding either of them. If the last run of `bytes`
+/// and the first run of `other` have the same index, they are merged. Both
+/// sequences must have been encoded with the same table.
+///
+/// Finding the last run means reading the run headers of `bytes` from the
+/// start, and the run headers of `other` are checked before anything is
+/// appended, but the items are never decoded.
+///
+/// ```
+/// # use rle::{append_bytes, Table};
+/// let table = Table::from_slice(&['A', 'B']);
+/// let left: Vec<char> = "AAABB".chars().collect();
+/// let right: Vec<char> = "BBBBA".chars().collect();
+///
+/// let mut bytes: Vec<u8> = table.encode_bytes(&left).unwrap().collect();
+/// let other: Vec<u8> = table.encode_bytes(&right).unwrap().collect();
+/// append_bytes(&mut bytes, &other).unwrap();
+/// assert_eq!(bytes, [0x01, 0x03, 0x03, 0x06, 0x00]);
+/// ```
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if either
+/// sequence ends in the middle of a run.
+pub fn append_bytes(bytes: &mut Vec<u8>, other: &[u8]) -> Result<(), Error> {
+    let (ind, len, size) = match read_run(other, 0)? {
+        Some(run) => run,
+        None => return Ok(()),
+    };
+    let mut offset = size;
+    while let Some((_, _, run_size)) = read_run(other, offset)? {
+        offset += run_size;
+    }
+    let merged = merge_last(bytes, (ind, len))?;
+    bytes.extend_from_slice(&other[if merged { size } else { 0 }..]);
+    Ok(())
+}
+
+/// If the last run of `bytes` has the same index as `run`, replaces it with
+/// a run of their combined length and returns `true`.
+fn merge_last(bytes: &mut Vec<u8>, run: (Index, usize)) -> Result<bool, Error> {
+    let mut last = None;
+    let mut offset = 0;
+    while let Some((ind, len, size)) = read_run(bytes, offset)? {
+        last = Some((offset, (ind, len)));
+        offset += size;
+    }
+    match last {
+        Some((offset, (ind, len))) if ind == run.0 => {
+            bytes.truncate(offset);
+            push_run(bytes, ind as u8, len + run.1);
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Appends runs that were encoded with another table to runs that were
+    /// encoded with this one, adding any of the other table's items that
+    /// are missing to this table and remapping the indices to match. The
+    /// boundary runs are merged as in [append_runs].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if one of the
+    /// other runs refers to an index not in the other table, in which case
+    /// neither `runs` nor this table is changed.
+    pub fn append_runs_from<S2>(
+        &mut self,
+        runs: &mut Vec<(Index, usize)>,
+        other: &Table<T, S2>,
+        other_runs: &[(Index, usize)],
+    ) -> Result<(), Error>
+    where
+        S2: TableStore<T>,
+    {
+        let remapped = self.remap_from(other, other_runs, usize::MAX)?;
+        append_runs(runs, &remapped);
+        Ok(())
+    }
+
+    /// Appends bytes that were encoded with another table to bytes that were
+    /// encoded with this one, adding any of the other table's items that are
+    /// missing to this table and remapping the indices to match. The boundary
+    /// runs are merged as in [append_bytes].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if one of the
+    /// other runs refers to an index not in the other table, a [TruncatedStream](Error::TruncatedStream)
+    /// error if either sequence ends in the middle of a run, or a [TableTooLarge](Error::TableTooLarge)
+    /// error if this table grows past 127 items. If an error occurs, neither
+    /// `bytes` nor this table is changed.
+    pub fn append_bytes_from<S2>(
+        &mut self,
+        bytes: &mut Vec<u8>,
+        other: &Table<T, S2>,
+        other_bytes: &[u8],
+    ) -> Result<(), Error>
+    where
+        S2: TableStore<T>,
+    {
+        let mut other_runs = Vec::new();
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(other_bytes, offset)? {
+            other_runs.push((ind, len));
+            offset += size;
+        }
+        let remapped = self.remap_from(other, &other_runs, MAX_TABLE_LEN)?;
+
+        let mut runs = remapped.into_iter();
+        if let Some(first) = runs.next() {
+            if !merge_last(bytes, first)? {
+                push_run(bytes, first.0 as u8, first.1);
+            }
+        }
+        for (ind, len) in runs {
+            push_run(bytes, ind as u8, len);
+        }
+        Ok(())
+    }
+
+    /// Translates runs encoded with another table into this table's indices.
+    fn remap_from<S2>(
+        &mut self,
+        other: &Table<T, S2>,
+        runs: &[(Index, usize)],
+        max_len: usize,
+    ) -> Result<Vec<(Index, usize)>, Error>
+    where
+        S2: TableStore<T>,
+    {
+        // Check every run before inserting anything, so an error leaves the table as it was
+        let mut missing = vec![false; other.len()];
+        for &(ind, _) in runs {
+            let item = other.item(ind).ok_or(Error::UnknownIndex(ind))?;
+            missing[ind] = self.get_index(item).is_none();
+        }
+        let len = self.len() + missing.iter().filter(|&&m| m).count();
+        if len > max_len {
+            return Err(Error::TableTooLarge(len));
+        }
+
+        let mut remap = vec![None; other.len()];
+        Ok(runs
+            .iter()
+            .map(|&(ind, len)| {
+                let new_ind = match remap[ind] {
+                    Some(new_ind) => new_ind,
+                    None => {
+                        let new_ind = self.insert_or_get(other.item(ind).unwrap());
+                        remap[ind] = Some(new_ind);
+                        new_ind
+                    }
+                };
+                (new_ind, len)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,219 @@
+use crate::bulk::{read_run, MAX_RUN, MAX_TABLE_LEN};
+use crate::byte_scan::run_len;
+use crate::{Error, Index};
+
+/// A table with room for a fixed number of items, stored inline in arrays,
+/// so that the byte format can be encoded and decoded without allocating,
+/// such as on microcontrollers.
+///
+/// The items are kept in insertion order along with a sorted list of their
+/// indices, just like [Table](crate::Table). Since the byte format stores
+/// indices in 7 bits, `N` can be at most 127 (which is checked at compile time).
+///
+/// ```
+/// use rle::ConstTable;
+///
+/// let table: ConstTable<u8, 4> = ConstTable::from_slice(&[7, 3, 9]).unwrap();
+/// let tiles = [3, 3, 3, 9, 7, 7];
+///
+/// let mut bytes = [0; 16];
+/// let len = table.encode_bytes_to_slice(&tiles, &mut bytes).unwrap();
+/// assert_eq!(&bytes[..len], [0x03, 0x03, 0x04, 0x01, 0x02]);
+///
+/// let mut decoded = [0; 6];
+/// table.decode_bytes_to_slice(&bytes[..len], &mut decoded).unwrap();
+/// assert_eq!(decoded, tiles);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ConstTable<T, const N: usize> {
+    items: [T; N],
+    sorted: [u8; N],
+    len: usize,
+}
+
+impl<T, const N: usize> Default for ConstTable<T, N>
+where
+    T: Copy + Default,
+{
+    fn default() -> Self {
+        const {
+            assert!(
+                N <= MAX_TABLE_LEN,
+                "a ConstTable can hold at most 127 items"
+            )
+        };
+        Self {
+            items: [T::default(); N],
+            sorted: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> ConstTable<T, N>
+where
+    T: Copy + Default + Ord,
+{
+    /// Constructs an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a table of the items, skipping any repeated items.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableTooLarge](Error::TableTooLarge) error if there are more
+    /// than `N` different items.
+    pub fn from_slice(items: &[T]) -> Result<Self, Error> {
+        let mut table = Self::new();
+        for &item in items {
+            table.insert(item)?;
+        }
+        Ok(table)
+    }
+
+    /// The number of items in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the table has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of items the table has room for.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The items in the table, in index order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items[..self.len]
+    }
+
+    /// Returns the item at `index`, or `None` if out of bounds.
+    pub fn item(&self, index: Index) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    /// Returns the index of the item, or `None` if it is not in the table.
+    pub fn index_of(&self, item: &T) -> Option<Index> {
+        self.search(item).ok()
+    }
+
+    /// Adds the item to the table if it isn't already in it, returning its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableTooLarge](Error::TableTooLarge) error, containing the
+    /// size the table would need to be, if the table is full.
+    pub fn insert(&mut self, item: T) -> Result<Index, Error> {
+        match self.search(&item) {
+            Ok(ind) => Ok(ind),
+            Err(_) if self.len == N => Err(Error::TableTooLarge(N + 1)),
+            Err(pos) => {
+                let ind = self.len;
+                self.items[ind] = item;
+                self.sorted.copy_within(pos..ind, pos + 1);
+                self.sorted[pos] = ind as u8;
+                self.len += 1;
+                Ok(ind)
+            }
+        }
+    }
+
+    /// Run-length encodes the items as a sequence of bytes in the same
+    /// format as [encode_bytes](crate::Table::encode_bytes), writing them into
+    /// the start of `bytes`. Returns the number of bytes written.
+    ///
+    /// The items are checked in full before anything is written, so `bytes`
+    /// is left untouched if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableMissingItems](Error::TableMissingItems) error if an item
+    /// is not in the table, or [BufferTooSmall](Error::BufferTooSmall) if the
+    /// bytes do not fit in `bytes`.
+    pub fn encode_bytes_to_slice(&self, items: &[T], bytes: &mut [u8]) -> Result<usize, Error> {
+        // First pass validates the items and totals the size of their runs
+        let mut size = 0;
+        let mut i = 0;
+        while i < items.len() {
+            self.index_of(&items[i])
+                .ok_or(Error::TableMissingItems(i))?;
+            let len = run_len(&items[i..]);
+            size += (len / MAX_RUN) * 2 + (len % MAX_RUN).min(2);
+            i += len;
+        }
+        if size > bytes.len() {
+            return Err(Error::BufferTooSmall(size));
+        }
+
+        let mut pos = 0;
+        let mut i = 0;
+        while i < items.len() {
+            let ind = (self.index_of(&items[i]).unwrap() as u8) << 1;
+            let mut len = run_len(&items[i..]);
+            i += len;
+            while len > 0 {
+                let num = len.min(MAX_RUN);
+                if num == 1 {
+                    bytes[pos] = ind;
+                    pos += 1;
+                } else {
+                    bytes[pos] = ind | 1;
+                    bytes[pos + 1] = num as u8;
+                    pos += 2;
+                }
+                len -= num;
+            }
+        }
+        Ok(pos)
+    }
+
+    /// Decodes run-length encoded bytes (see [decode_bytes](crate::Table::decode_bytes))
+    /// into the start of `items`, returning how many items were written.
+    ///
+    /// The bytes are checked in full before anything is written, so `items`
+    /// is left untouched if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if the bytes refer
+    /// to an index not in the table, [TruncatedStream](Error::TruncatedStream)
+    /// if they end in the middle of a run, or [BufferTooSmall](Error::BufferTooSmall)
+    /// if the decoded items do not fit in `items`.
+    pub fn decode_bytes_to_slice(&self, bytes: &[u8], items: &mut [T]) -> Result<usize, Error> {
+        let mut total = 0;
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(bytes, offset)? {
+            if ind >= self.len {
+                return Err(Error::UnknownIndex(ind));
+            }
+            total += len;
+            offset += size;
+        }
+        if total > items.len() {
+            return Err(Error::BufferTooSmall(total));
+        }
+
+        let mut pos = 0;
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(bytes, offset)? {
+            items[pos..pos + len].fill(self.items[ind]);
+            pos += len;
+            offset += size;
+        }
+        Ok(total)
+    }
+
+    /// Binary searches the sorted indices for the item, returning its index
+    /// if found, or else the position in the sorted indices to insert it at.
+    fn search(&self, item: &T) -> Result<Index, usize> {
+        self.sorted[..self.len]
+            .binary_search_by(|&ind| self.items[ind as usize].cmp(item))
+            .map(|pos| self.sorted[pos] as usize)
+    }
+}
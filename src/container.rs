@@ -0,0 +1,184 @@
+use crate::crc::crc32;
+use crate::{Control, Error, Table, TableItem};
+use std::convert::TryFrom;
+
+impl<T> Table<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Encodes the items into a single self-contained blob that embeds this
+    /// table, so it can be decoded later with [decode_container](Table::decode_container)
+    /// without needing a copy of the table.
+    ///
+    /// # Format
+    ///
+    /// The blob starts with the number of items in the table as a little-endian
+    /// `u32`, followed by the bytes of each item (see [TableItem]) in index order.
+    /// The rest of the blob is the items encoded as in [encode_bytes](Table::encode_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table::encode_bytes).
+    pub fn encode_container(&self, items: &[T]) -> Result<Vec<u8>, Error> {
+        let encoder = self.encode_bytes(items)?;
+        let mut bytes = Vec::new();
+        (self.len() as u32).write_bytes(&mut bytes);
+        for item in self.iter() {
+            item.write_bytes(&mut bytes);
+        }
+        bytes.extend(encoder);
+        Ok(bytes)
+    }
+
+    /// Decodes a blob produced by [encode_container](Table::encode_container),
+    /// returning the embedded table along with the decoded items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBytes](Error::InvalidBytes) error if the table header
+    /// is malformed or contains the same item twice, or if the encoded items
+    /// refer to an index not in the table.
+    pub fn decode_container(bytes: &[u8]) -> Result<(Self, Vec<T>), Error> {
+        let (len, mut offset) = u32::read_bytes(bytes).ok_or(Error::InvalidBytes(0))?;
+        let mut table = Self::default();
+        for i in 0..len as usize {
+            let (item, size) =
+                T::read_bytes(&bytes[offset..]).ok_or(Error::InvalidBytes(offset))?;
+            if table.insert_or_get(&item) != i {
+                return Err(Error::InvalidBytes(offset));
+            }
+            offset += size;
+        }
+
+        let payload = &bytes[offset..];
+        let mut items = Vec::new();
+        let read = table.decode_bytes_visit(payload, |item, len| {
+            items.extend(std::iter::repeat_n(item.clone(), len));
+            Control::Continue
+        });
+        if read != payload.len() {
+            return Err(Error::InvalidBytes(offset + read));
+        }
+        Ok((table, items))
+    }
+
+    /// Like [encode_container](Table::encode_container), but appends a CRC-32
+    /// of the blob as a little-endian `u32`, so that corrupted data can be
+    /// detected by [decode_container_crc](Table::decode_container_crc) rather
+    /// than silently decoding into the wrong items.
+    ///
+    /// ```
+    /// # use rle::{Error, Table};
+    /// let tiles = [0u8, 0, 0, 1, 1, 2];
+    /// let table = Table::from_slice(&[0, 1, 2]);
+    /// let mut bytes = table.encode_container_crc(&tiles).unwrap();
+    /// assert_eq!(Table::<u8>::decode_container_crc(&bytes).unwrap().1, tiles);
+    ///
+    /// bytes[8] ^= 1;
+    /// assert!(matches!(
+    ///     Table::<u8>::decode_container_crc(&bytes),
+    ///     Err(Error::ChecksumMismatch(..))
+    /// ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_container](Table::encode_container).
+    pub fn encode_container_crc(&self, items: &[T]) -> Result<Vec<u8>, Error> {
+        let mut bytes = self.encode_container(items)?;
+        crc32(&bytes).write_bytes(&mut bytes);
+        Ok(bytes)
+    }
+
+    /// Decodes a blob produced by [encode_container_crc](Table::encode_container_crc),
+    /// checking it against its CRC-32 before decoding it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [ChecksumMismatch](Error::ChecksumMismatch) error if the blob
+    /// does not match its checksum, or an [InvalidBytes](Error::InvalidBytes)
+    /// error if it is too short to contain one. Otherwise, fails under the same
+    /// conditions as [decode_container](Table::decode_container).
+    pub fn decode_container_crc(bytes: &[u8]) -> Result<(Self, Vec<T>), Error> {
+        let split = bytes.len().checked_sub(4).ok_or(Error::InvalidBytes(0))?;
+        let (blob, stored) = bytes.split_at(split);
+        let (expected, _) = u32::read_bytes(stored).unwrap();
+        let actual = crc32(blob);
+        if expected != actual {
+            return Err(Error::ChecksumMismatch(expected, actual));
+        }
+        Self::decode_container(blob)
+    }
+
+    /// Serializes the table on its own into a compact, versioned binary layout
+    /// that can be loaded again with [from_bytes](Table::from_bytes).
+    ///
+    /// # Format
+    ///
+    /// The bytes start with the magic bytes `RLET` and a one-byte format version
+    /// (currently [TABLE_FORMAT_VERSION]), followed by the number of items as a
+    /// little-endian `u32` and the bytes of each item (see [TableItem]) in index order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [LengthTooLarge](Error::LengthTooLarge) error if the number of
+    /// items doesn't fit in a `u32`.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&[1u16, 2]);
+    /// let bytes = table.to_bytes().unwrap();
+    /// assert_eq!(bytes, [b'R', b'L', b'E', b'T', 1, 2, 0, 0, 0, 1, 0, 2, 0]);
+    /// assert_eq!(Table::<u16>::from_bytes(&bytes).unwrap().as_ref(), table.as_ref());
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let len = u32::try_from(self.len()).map_err(|_| Error::LengthTooLarge(self.len()))?;
+        let mut bytes = Vec::with_capacity(9 + self.len());
+        bytes.extend_from_slice(TABLE_MAGIC);
+        bytes.push(TABLE_FORMAT_VERSION);
+        len.write_bytes(&mut bytes);
+        for item in self.iter() {
+            item.write_bytes(&mut bytes);
+        }
+        Ok(bytes)
+    }
+
+    /// Loads a table serialized with [to_bytes](Table::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnsupportedVersion](Error::UnsupportedVersion) error if the
+    /// bytes were written by a newer format version, or an [InvalidBytes](Error::InvalidBytes)
+    /// error if the header is malformed, an item cannot be read, the same item
+    /// appears twice, or there are bytes left over after the last item.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if !bytes.starts_with(TABLE_MAGIC) {
+            return Err(Error::InvalidBytes(0));
+        }
+        match bytes.get(4) {
+            Some(&TABLE_FORMAT_VERSION) => {}
+            Some(&version) => return Err(Error::UnsupportedVersion(version)),
+            None => return Err(Error::InvalidBytes(4)),
+        }
+        let (len, size) = u32::read_bytes(&bytes[5..]).ok_or(Error::InvalidBytes(5))?;
+        let mut offset = 5 + size;
+        let mut table = Self::default();
+        for i in 0..len as usize {
+            let (item, size) =
+                T::read_bytes(&bytes[offset..]).ok_or(Error::InvalidBytes(offset))?;
+            if table.insert_or_get(&item) != i {
+                return Err(Error::InvalidBytes(offset));
+            }
+            offset += size;
+        }
+        if offset != bytes.len() {
+            return Err(Error::InvalidBytes(offset));
+        }
+        Ok(table)
+    }
+}
+
+const TABLE_MAGIC: &[u8; 4] = b"RLET";
+
+/// The version of the binary layout written by [to_bytes](Table::to_bytes).
+pub const TABLE_FORMAT_VERSION: u8 = 1;
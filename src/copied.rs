@@ -0,0 +1,59 @@
+use crate::{BytesDecoder, Decoder, Index, Table, TableStore};
+use std::iter::{Cloned, Copied};
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Like [decode](Table::decode), but yields clones of the items rather
+    /// than references to them.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&[String::from("ab"), String::from("c")]);
+    /// let strs: Vec<String> = table.decode_cloned(&[(0, 2), (1, 1)]).collect();
+    /// assert_eq!(strs.concat(), "ababc");
+    /// ```
+    pub fn decode_cloned<'a>(&'a self, runs: &'a [(Index, usize)]) -> Cloned<Decoder<'a, T, S>> {
+        self.decode(runs).cloned()
+    }
+
+    /// Like [decode_bytes](Table::decode_bytes), but yields clones of the
+    /// items rather than references to them.
+    pub fn decode_bytes_cloned<'a>(&'a self, bytes: &'a [u8]) -> Cloned<BytesDecoder<'a, T, S>> {
+        self.decode_bytes(bytes).cloned()
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Copy,
+    S: TableStore<T>,
+{
+    /// Like [decode](Table::decode), but yields copies of the items rather
+    /// than references to them.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let str: String = table.decode_copied(&[(0, 2), (1, 3)]).collect();
+    /// assert_eq!(str, "AABBB");
+    /// ```
+    pub fn decode_copied<'a>(&'a self, runs: &'a [(Index, usize)]) -> Copied<Decoder<'a, T, S>> {
+        self.decode(runs).copied()
+    }
+
+    /// Like [decode_bytes](Table::decode_bytes), but yields copies of the
+    /// items rather than references to them.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let str: String = table.decode_bytes_copied(&[0x01, 0x02, 0x03, 0x03]).collect();
+    /// assert_eq!(str, "AABBB");
+    /// ```
+    pub fn decode_bytes_copied<'a>(&'a self, bytes: &'a [u8]) -> Copied<BytesDecoder<'a, T, S>> {
+        self.decode_bytes(bytes).copied()
+    }
+}
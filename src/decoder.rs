@@ -1,36 +1,124 @@
-use crate::{Index, Table};
+use crate::{Index, IntoRun, SortedStore, Table, TableStore};
+use std::iter::FusedIterator;
 
 /// An iterator that decodes a sequence of runs into a series
 /// of `T` values fetched from the table. See [decode](crate::Table::decode).
-pub struct Decoder<'a, T>
+pub struct Decoder<'a, T, S = SortedStore<T>, R = (Index, usize)>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
 {
-    pub(crate) table: &'a Table<T>,
-    pub(crate) runs: &'a [(Index, usize)],
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) runs: &'a [R],
     pub(crate) run: Option<(Index, usize)>,
+    pub(crate) back: Option<(Index, usize)>,
+    pub(crate) remaining: usize,
 }
 
-impl<'a, T> Iterator for Decoder<'a, T>
+impl<'a, T, S, R> Decoder<'a, T, S, R>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+    /// Cuts the runs at the first index that isn't in the table, so that
+    /// every run left to decode is known to be valid and the length is exact.
+    pub(crate) fn new(table: &'a Table<T, S>, runs: &'a [R]) -> Self {
+        let valid = runs
+            .iter()
+            .position(|&run| table.item(run.into_run().index).is_none())
+            .unwrap_or(runs.len());
+        let runs = &runs[..valid];
+        let remaining = runs
+            .iter()
+            .fold(0usize, |sum, &run| sum.saturating_add(run.into_run().len));
+        Self {
+            table,
+            runs,
+            run: None,
+            back: None,
+            remaining,
+        }
+    }
+
+    /// Looks up the item of a run that was taken from either end, putting
+    /// the rest of the run back into `rest`.
+    fn take_item(&mut self, (ind, len): (Index, usize), back: bool) -> &'a T {
+        if len > 1 {
+            let rest = if back { &mut self.back } else { &mut self.run };
+            *rest = Some((ind, len - 1));
+        }
+        self.remaining = self.remaining.saturating_sub(1);
+        self.table.item(ind).unwrap()
+    }
+}
+
+impl<'a, T, S, R> Iterator for Decoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.run
-            .take()
-            .or_else(|| {
-                self.runs.get(0).and_then(|&run| {
-                    self.runs = &self.runs[1..];
-                    Some(run)
+        loop {
+            let run = self
+                .run
+                .take()
+                .or_else(|| {
+                    let (&run, rest) = self.runs.split_first()?;
+                    self.runs = rest;
+                    Some(run.into_run().into())
                 })
-            })
-            .and_then(|(ind, len)| {
-                if len > 1 {
-                    self.run = Some((ind, len - 1));
-                }
-                self.table.get(ind)
-            })
+                .or_else(|| self.back.take())?;
+            // Runs of zero length hold no items
+            if run.1 > 0 {
+                return Some(self.take_item(run, false));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
+
+impl<'a, T, S, R> DoubleEndedIterator for Decoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let run = self
+                .back
+                .take()
+                .or_else(|| {
+                    let (&run, rest) = self.runs.split_last()?;
+                    self.runs = rest;
+                    Some(run.into_run().into())
+                })
+                .or_else(|| self.run.take())?;
+            if run.1 > 0 {
+                return Some(self.take_item(run, true));
+            }
+        }
+    }
+}
+
+impl<'a, T, S, R> ExactSizeIterator for Decoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+}
+
+impl<'a, T, S, R> FusedIterator for Decoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+}
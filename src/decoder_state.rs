@@ -0,0 +1,20 @@
+use crate::Index;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The progress of a [BytesDecoder](crate::BytesDecoder), which can be
+/// stored and later used to resume decoding exactly where it stopped.
+/// See [resume_bytes](crate::Table::resume_bytes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecoderState {
+    /// How many bytes of the encoded sequence have been read.
+    pub byte_offset: usize,
+
+    /// How many items have been decoded so far.
+    pub element_offset: usize,
+
+    /// The index and remaining length of a run that was only partially
+    /// decoded, if any.
+    pub run: Option<(Index, usize)>,
+}
@@ -0,0 +1,71 @@
+use crate::{IntoRun, SortedStore, Table, TableStore};
+use std::fmt::{Display, Formatter, Result};
+
+/// Formats a sequence of runs as their lengths followed by their items, such
+/// as `5A 10B 3C 10A`. See [display_runs](crate::Table::display_runs).
+///
+/// Runs whose index is not in the table are written with a `?` in place of
+/// their item, since this is mostly useful for debugging.
+///
+/// ```
+/// # use rle::Table;
+/// let table = Table::from_slice(&['A', 'B', 'C']);
+/// let runs: [(usize, usize); 4] = [(0, 5), (1, 10), (2, 3), (0, 10)];
+/// assert_eq!(table.display_runs(&runs).to_string(), "5A 10B 3C 10A");
+/// assert_eq!(
+///     table.display_runs(&runs).separator(", ").len_separator("x").to_string(),
+///     "5xA, 10xB, 3xC, 10xA"
+/// );
+/// ```
+pub struct DisplayRuns<'a, T, R, S = SortedStore<T>> {
+    table: &'a Table<T, S>,
+    runs: &'a [R],
+    separator: &'a str,
+    len_separator: &'a str,
+}
+
+impl<'a, T, R, S> DisplayRuns<'a, T, R, S> {
+    pub(crate) fn new(table: &'a Table<T, S>, runs: &'a [R]) -> Self {
+        Self {
+            table,
+            runs,
+            separator: " ",
+            len_separator: "",
+        }
+    }
+
+    /// Sets the string written between runs, which is `" "` by default.
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the string written between each run's length and its item, which
+    /// is empty by default.
+    pub fn len_separator(mut self, len_separator: &'a str) -> Self {
+        self.len_separator = len_separator;
+        self
+    }
+}
+
+impl<'a, T, R, S> Display for DisplayRuns<'a, T, R, S>
+where
+    T: PartialEq + Clone + Display,
+    R: IntoRun,
+    S: TableStore<T>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, run) in self.runs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.separator)?;
+            }
+            let run = run.into_run();
+            write!(f, "{}{}", run.len, self.len_separator)?;
+            match self.table.item(run.index) {
+                Some(item) => item.fmt(f)?,
+                None => f.write_str("?")?,
+            }
+        }
+        Ok(())
+    }
+}
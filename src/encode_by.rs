@@ -0,0 +1,120 @@
+use crate::{Error, Index, Table, TableStore};
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items, treating items for which `eq` returns
+    /// `true` as the same item, such as characters that only differ by case
+    /// or colors within a tolerance of each other.
+    ///
+    /// Each run continues for as long as `eq` returns `true` when comparing the
+    /// first item of the run to the next item, so runs don't drift away from
+    /// where they started. Each run is stored as the table entry of its first
+    /// item, or if that isn't in the table, the first entry that is equivalent
+    /// to it (found by searching the table in index order).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableMissingItems](Error::TableMissingItems) error if a run's
+    /// first item has no equivalent entry in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['a', 'b']);
+    /// let str: Vec<char> = "aAaBbb".chars().collect();
+    /// let runs = table.encode_by(&str, |a, b| a.eq_ignore_ascii_case(b)).unwrap();
+    /// assert_eq!(runs, [(0, 3), (1, 3)]);
+    /// ```
+    pub fn encode_by<F>(&self, items: &[T], mut eq: F) -> Result<Vec<(Index, usize)>, Error>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let len = run_len_by(&items[i..], &mut eq);
+            let ind = self
+                .equivalent_index(&items[i], &mut eq)
+                .ok_or(Error::TableMissingItems(i))?;
+            runs.push((ind, len));
+            i += len;
+        }
+        Ok(runs)
+    }
+
+    /// Like [encode_by](Table::encode_by), but adds the first item of a run
+    /// to the table if it has no equivalent entry, so that it becomes the
+    /// canonical entry for the items equivalent to it.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table = Table::default();
+    /// let str: Vec<char> = "AaaBbBaA".chars().collect();
+    /// let runs = table.encode_mut_by(&str, |a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(runs, [(0, 3), (1, 3), (0, 2)]);
+    /// assert_eq!(table.as_ref(), &['A', 'B']);
+    /// ```
+    pub fn encode_mut_by<F>(&mut self, items: &[T], mut eq: F) -> Vec<(Index, usize)>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let len = run_len_by(&items[i..], &mut eq);
+            let ind = match self.equivalent_index(&items[i], &mut eq) {
+                Some(ind) => ind,
+                None => self.insert_or_get(&items[i]),
+            };
+            runs.push((ind, len));
+            i += len;
+        }
+        runs
+    }
+
+    /// Like [encode_by](Table::encode_by), but treats items as the same if
+    /// `key` returns the same key for them.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&[10u8, 20]);
+    /// let runs = table.encode_by_key(&[10, 12, 19, 20, 24, 10], |&x| x / 10).unwrap();
+    /// assert_eq!(runs, [(0, 3), (1, 2), (0, 1)]);
+    /// ```
+    pub fn encode_by_key<K, F>(&self, items: &[T], mut key: F) -> Result<Vec<(Index, usize)>, Error>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        self.encode_by(items, |a, b| key(a) == key(b))
+    }
+
+    /// Like [encode_mut_by](Table::encode_mut_by), but treats items as the
+    /// same if `key` returns the same key for them.
+    pub fn encode_mut_by_key<K, F>(&mut self, items: &[T], mut key: F) -> Vec<(Index, usize)>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        self.encode_mut_by(items, |a, b| key(a) == key(b))
+    }
+
+    /// Finds the item in the table, or else the first entry equivalent to it.
+    fn equivalent_index<F>(&self, item: &T, eq: &mut F) -> Option<Index>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.get_index(item)
+            .or_else(|| (0..self.len()).find(|&i| self.item(i).is_some_and(|x| eq(x, item))))
+    }
+}
+
+/// Returns how many items at the start of the slice are equivalent to the first.
+fn run_len_by<T, F>(items: &[T], eq: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    1 + items[1..].iter().take_while(|x| eq(&items[0], x)).count()
+}
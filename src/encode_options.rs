@@ -0,0 +1,405 @@
+use crate::bulk::{push_run_max, MAX_RUN};
+use crate::varint::{push_varint, read_varint};
+use crate::{decode_packbits, encode_packbits, Error, Index, Table, TableStore};
+use std::iter::repeat_n;
+use std::ops::Range;
+
+/// How the header of each run is laid out. See [EncodeOptions::header].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RunHeader {
+    /// The layout of [encode_bytes](crate::Table::encode_bytes): the index
+    /// shifted left by one, with the lowest bit set if a length byte follows.
+    /// Runs of 1 don't store a length. Tables can hold up to 127 items.
+    Flagged,
+
+    /// Every run is an index byte followed by a length byte. Tables can hold
+    /// up to 256 items.
+    Pair,
+}
+
+/// Options for [encode_bytes_with](crate::Table::encode_bytes_with) and
+/// [decode_bytes_with](crate::Table::decode_bytes_with).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EncodeOptions {
+    /// If `true`, items are written as a hybrid of run packets and literal
+    /// blocks, instead of the usual one header per run.
+    ///
+    /// # Format
+    ///
+    /// Each item is replaced by its index in the table, and the indices are
+    /// packed with [encode_packbits]: runs of 3 or more become a header byte
+    /// followed by the repeated index, and everything else is gathered into
+    /// blocks of up to 128 indices written verbatim after a single header byte.
+    /// Since indices are stored as whole bytes, tables can hold up to 256 items.
+    pub literal_blocks: bool,
+
+    /// The longest run that is written with a single header, from 1 to 255.
+    /// Longer runs are split into several runs of the same index. Values out
+    /// of range are clamped. Defaults to 127. Ignored with literal blocks.
+    pub max_run: usize,
+
+    /// How the header of each run is laid out. Defaults to [RunHeader::Flagged].
+    /// Ignored with literal blocks.
+    pub header: RunHeader,
+
+    /// If set, items that are not in the table are encoded as the item at
+    /// this index (such as an "unknown" tile) instead of failing. Defaults
+    /// to `None`.
+    pub fallback: Option<Index>,
+
+    /// If set, the item at this index (such as an empty tile) is treated as
+    /// the default, and only the spans of other items are stored, which is
+    /// much smaller for mostly empty sequences. Defaults to `None`. Ignored
+    /// with literal blocks.
+    ///
+    /// # Format
+    ///
+    /// The bytes are a series of spans. Each span starts with the number of
+    /// default items before it, then the length in bytes of its runs, both
+    /// as LEB128 varints (see [encode_bytes_varint](crate::Table::encode_bytes_varint)).
+    /// The runs follow, laid out as set by `header` and `max_run`. If the
+    /// sequence ends with default items, a final span with no runs records them.
+    pub sparse: Option<Index>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            literal_blocks: false,
+            max_run: MAX_RUN,
+            header: RunHeader::Flagged,
+            fallback: None,
+            sparse: None,
+        }
+    }
+}
+
+impl EncodeOptions {
+    /// The default options, which use the same format as
+    /// [encode_bytes](crate::Table::encode_bytes).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes items with literal blocks.
+    pub fn literal_blocks(mut self) -> Self {
+        self.literal_blocks = true;
+        self
+    }
+
+    /// Splits runs longer than `max_run`.
+    pub fn max_run(mut self, max_run: usize) -> Self {
+        self.max_run = max_run;
+        self
+    }
+
+    /// Lays out each run's header with `header`.
+    pub fn header(mut self, header: RunHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Encodes items missing from the table as the item at `index`.
+    pub fn fallback(mut self, index: Index) -> Self {
+        self.fallback = Some(index);
+        self
+    }
+
+    /// Only stores the spans of items that are not the item at `index`.
+    pub fn sparse(mut self, index: Index) -> Self {
+        self.sparse = Some(index);
+        self
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes, in the format
+    /// chosen by `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableTooLarge](Error::TableTooLarge) error if the table has
+    /// too many items for the format, or a [TableMissingItems](Error::TableMissingItems)
+    /// error if `items` contains any elements not found in the table and no
+    /// fallback is set. Returns an [UnknownIndex](Error::UnknownIndex) error
+    /// if the fallback is not in the table.
+    ///
+    /// ```
+    /// # use rle::{EncodeOptions, Table};
+    /// let str: Vec<char> = "AAAABCAB".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let options = EncodeOptions::new().literal_blocks();
+    /// let bytes = table.encode_bytes_with(&str, options).unwrap();
+    /// assert_eq!(bytes, [0xFD, 0x00, 0x03, 0x01, 0x02, 0x00, 0x01]);
+    /// assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), str);
+    /// ```
+    ///
+    /// Runs can be capped to match other formats, such as the 63 item runs of PCX:
+    ///
+    /// ```
+    /// # use rle::{EncodeOptions, RunHeader, Table};
+    /// let str = vec!['A'; 100];
+    /// let table = Table::from_slice(&['A']);
+    ///
+    /// let options = EncodeOptions::new().max_run(63).header(RunHeader::Pair);
+    /// let bytes = table.encode_bytes_with(&str, options).unwrap();
+    /// assert_eq!(bytes, [0, 63, 0, 37]);
+    /// assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), str);
+    /// ```
+    ///
+    /// Mostly empty sequences can skip over their default item:
+    ///
+    /// ```
+    /// # use rle::{EncodeOptions, Table};
+    /// let mut tiles = vec![' '; 1000];
+    /// tiles[500..503].copy_from_slice(&['#', '#', '@']);
+    /// let table = Table::from_slice(&[' ', '#', '@']);
+    ///
+    /// let options = EncodeOptions::new().sparse(0);
+    /// let bytes = table.encode_bytes_with(&tiles, options).unwrap();
+    /// assert_eq!(bytes, [0xF4, 0x03, 0x03, 0x03, 0x02, 0x04, 0xF1, 0x03, 0x00]);
+    /// assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), tiles);
+    /// ```
+    pub fn encode_bytes_with(&self, items: &[T], options: EncodeOptions) -> Result<Vec<u8>, Error> {
+        if !options.literal_blocks {
+            return self.encode_runs_with(items, options);
+        }
+        if self.len() > 256 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut indices = Vec::with_capacity(items.len());
+        for (ind, len) in self.runs_with_fallback(items, options.fallback, None)? {
+            indices.resize(indices.len() + len, ind as u8);
+        }
+        Ok(encode_packbits(&indices))
+    }
+
+    /// Decodes bytes written by [encode_bytes_with](Table::encode_bytes_with)
+    /// using the same `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if the bytes refer
+    /// to an index not in the table, [TruncatedStream](Error::TruncatedStream)
+    /// if they end in the middle of a run or block, or [InvalidBytes](Error::InvalidBytes)
+    /// if a [RunHeader::Pair] run has a length of 0 or a sparse span's lengths
+    /// are too large.
+    pub fn decode_bytes_with(&self, bytes: &[u8], options: EncodeOptions) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        if !options.literal_blocks {
+            match options.sparse {
+                Some(default) => self.decode_sparse_into(bytes, default, options, &mut items)?,
+                None => self.decode_runs_into(bytes, options, &mut items)?,
+            }
+            return Ok(items);
+        }
+        let indices = decode_packbits(bytes)?;
+        items.reserve(indices.len());
+        for &ind in &indices {
+            let item = self
+                .item(ind as usize)
+                .ok_or(Error::UnknownIndex(ind as usize))?;
+            items.push(item.clone());
+        }
+        Ok(items)
+    }
+
+    /// Writes each run with the header layout and maximum run length of `options`.
+    fn encode_runs_with(&self, items: &[T], options: EncodeOptions) -> Result<Vec<u8>, Error> {
+        let max_table_len = match options.header {
+            RunHeader::Flagged => 127,
+            RunHeader::Pair => 256,
+        };
+        if self.len() > max_table_len {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let runs = self.runs_with_fallback(items, options.fallback, None)?;
+        let mut bytes = Vec::new();
+        match options.sparse {
+            Some(default) if default >= self.len() => return Err(Error::UnknownIndex(default)),
+            Some(default) => {
+                let mut span = Vec::new();
+                let mut gap = 0;
+                for (ind, len) in runs {
+                    if ind != default {
+                        push_run_with(&mut span, ind, len, options);
+                        continue;
+                    }
+                    if !span.is_empty() {
+                        push_span(&mut bytes, gap, &span);
+                        span.clear();
+                        gap = 0;
+                    }
+                    gap += len;
+                }
+                if gap > 0 || !span.is_empty() {
+                    push_span(&mut bytes, gap, &span);
+                }
+            }
+            None => {
+                for (ind, len) in runs {
+                    push_run_with(&mut bytes, ind, len, options);
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Run-length encodes the items, encoding any items that are not in the
+    /// table as the item at `fallback` rather than failing. Returns the runs,
+    /// along with the ranges of items that were substituted.
+    ///
+    /// Runs that end up with the same index are merged, so a missing item
+    /// next to a run of the fallback item extends that run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if `fallback` is
+    /// not in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['?', 'A', 'B']);
+    /// let str: Vec<char> = "AAXYBB??Z".chars().collect();
+    /// let (runs, substituted) = table.encode_fallback(&str, 0).unwrap();
+    /// assert_eq!(runs, [(1, 2), (0, 2), (2, 2), (0, 3)]);
+    /// assert_eq!(substituted, [2..4, 8..9]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn encode_fallback(
+        &self,
+        items: &[T],
+        fallback: Index,
+    ) -> Result<(Vec<(Index, usize)>, Vec<Range<usize>>), Error> {
+        let mut substituted = Vec::new();
+        let runs = self.runs_with_fallback(items, Some(fallback), Some(&mut substituted))?;
+        Ok((runs, substituted))
+    }
+
+    /// Finds the runs of the items, encoding items missing from the table as
+    /// `fallback` if it is set, and recording where that happened.
+    pub(crate) fn runs_with_fallback(
+        &self,
+        items: &[T],
+        fallback: Option<Index>,
+        mut substituted: Option<&mut Vec<Range<usize>>>,
+    ) -> Result<Vec<(Index, usize)>, Error> {
+        if let Some(fallback) = fallback {
+            if fallback >= self.len() {
+                return Err(Error::UnknownIndex(fallback));
+            }
+        }
+        let mut runs: Vec<(Index, usize)> = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let len = self.run_len(&items[i..]);
+            let ind = match (self.get_index(&items[i]), fallback) {
+                (Some(ind), _) => ind,
+                (None, Some(fallback)) => {
+                    if let Some(substituted) = substituted.as_deref_mut() {
+                        match substituted.last_mut() {
+                            Some(last) if last.end == i => last.end += len,
+                            _ => substituted.push(i..i + len),
+                        }
+                    }
+                    fallback
+                }
+                (None, None) => return Err(Error::TableMissingItems(i)),
+            };
+            match runs.last_mut() {
+                Some(last) if last.0 == ind => last.1 += len,
+                _ => runs.push((ind, len)),
+            }
+            i += len;
+        }
+        Ok(runs)
+    }
+
+    /// Decodes runs laid out with the header of `options`.
+    fn decode_runs_into(
+        &self,
+        bytes: &[u8],
+        options: EncodeOptions,
+        items: &mut Vec<T>,
+    ) -> Result<(), Error> {
+        match options.header {
+            RunHeader::Flagged => self.decode_bytes_into(bytes, items),
+            RunHeader::Pair => self.decode_pairs_into(bytes, items),
+        }
+    }
+
+    /// Decodes the spans of the sparse format, filling the gaps between
+    /// them with the item at `default`.
+    fn decode_sparse_into(
+        &self,
+        bytes: &[u8],
+        default: Index,
+        options: EncodeOptions,
+        items: &mut Vec<T>,
+    ) -> Result<(), Error> {
+        let default = self.item(default).ok_or(Error::UnknownIndex(default))?;
+        let read = |offset: usize| {
+            read_varint(&bytes[offset..])
+                .map(|(value, size)| (value, offset + size))
+                .map_err(|err| err.unwrap_or(Error::InvalidBytes(offset)))
+        };
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let (gap, start) = read(offset)?;
+            let (len, start) = read(start)?;
+            let end = start.checked_add(len).ok_or(Error::InvalidBytes(offset))?;
+            let span = bytes.get(start..end).ok_or(Error::TruncatedStream)?;
+            // The gap is untrusted, so reserving for it may fail
+            if items.len().checked_add(gap).is_none() || items.try_reserve(gap).is_err() {
+                return Err(Error::InvalidBytes(offset));
+            }
+            items.extend(repeat_n(default.clone(), gap));
+            self.decode_runs_into(span, options, items)?;
+            offset = end;
+        }
+        Ok(())
+    }
+
+    /// Decodes runs written with [RunHeader::Pair].
+    fn decode_pairs_into(&self, bytes: &[u8], items: &mut Vec<T>) -> Result<(), Error> {
+        if bytes.len() % 2 == 1 {
+            return Err(Error::TruncatedStream);
+        }
+        for (i, pair) in bytes.chunks_exact(2).enumerate() {
+            let (ind, len) = (pair[0] as usize, pair[1] as usize);
+            if len == 0 {
+                return Err(Error::InvalidBytes(i * 2));
+            }
+            let item = self.item(ind).ok_or(Error::UnknownIndex(ind))?;
+            items.resize(items.len() + len, item.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Writes a run with the header layout and maximum run length of `options`.
+fn push_run_with(bytes: &mut Vec<u8>, ind: Index, mut len: usize, options: EncodeOptions) {
+    let max_run = options.max_run.clamp(1, 255);
+    match options.header {
+        RunHeader::Flagged => push_run_max(bytes, ind as u8, len, max_run),
+        RunHeader::Pair => {
+            while len > 0 {
+                let num = len.min(max_run);
+                bytes.extend_from_slice(&[ind as u8, num as u8]);
+                len -= num;
+            }
+        }
+    }
+}
+
+/// Writes a span of the sparse format: the gap before it, then its runs.
+fn push_span(bytes: &mut Vec<u8>, gap: usize, span: &[u8]) {
+    push_varint(bytes, gap);
+    push_varint(bytes, span.len());
+    bytes.extend_from_slice(span);
+}
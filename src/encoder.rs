@@ -1,32 +1,146 @@
-use crate::{Index, Table};
-use std::cmp::Ordering;
+use crate::{Error, Index, Run, RunLen, SortedStore, Table, TableStore};
+use std::iter::{FusedIterator, Map};
+use std::marker::PhantomData;
 
 /// An iterator that run-length encodes a sequence of `T` values
 /// into a series of runs. See [encode](crate::Table::encode).
-pub struct Encoder<'a, T> {
-    pub(crate) table: &'a Table<T>,
+///
+/// Run lengths are stored as `L` (see [encode_as](crate::Table::encode_as)),
+/// and runs too long for it are split into several runs of the same index.
+pub struct Encoder<'a, T, S = SortedStore<T>, L = usize> {
+    pub(crate) table: &'a Table<T, S>,
     pub(crate) items: &'a [T],
     pub(crate) index: usize,
+    pub(crate) scan: fn(&Table<T, S>, &[T]) -> usize,
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) len: PhantomData<L>,
 }
 
-impl<'a, T> Iterator for Encoder<'a, T>
+impl<'a, T, S, L> Iterator for Encoder<'a, T, S, L>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    L: RunLen,
 {
-    type Item = (Index, usize);
+    type Item = (Index, L);
 
     fn next(&mut self) -> Option<Self::Item> {
-        (self.index < self.items.len()).then(|| {
-            let ind = self.index;
-            let mut len = 1;
-            while ind + len < self.items.len()
-                && self.items[ind].cmp(&self.items[ind + len]) == Ordering::Equal
-            {
-                len += 1;
+        let (ind, len) = match self.run.take() {
+            Some(run) => run,
+            None => {
+                if self.index >= self.items.len() {
+                    return None;
+                }
+                let ind = self.index;
+                let len = (self.scan)(self.table, &self.items[ind..]);
+                self.index += len;
+                (self.table.get_index(&self.items[ind]).unwrap(), len)
             }
-            self.index += len;
-            let ind = self.table.get_index(&self.items[ind]).unwrap();
-            (ind, len)
-        })
+        };
+        if len > L::MAX {
+            self.run = Some((ind, len - L::MAX));
+        }
+        Some((ind, L::from_usize(len.min(L::MAX)).unwrap()))
     }
+
+    /// Every remaining item could be its own run.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let items = self.items.len() - self.index + self.run.map_or(0, |(_, len)| len);
+        ((items > 0) as usize, Some(items))
+    }
+}
+
+impl<'a, T, S, L> FusedIterator for Encoder<'a, T, S, L>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    L: RunLen,
+{
+}
+
+/// An iterator that run-length encodes a sequence of `T` values, checking
+/// each run against the table as it goes. See [try_encode](crate::Table::try_encode).
+///
+/// After yielding an error, the iterator is finished.
+pub struct TryEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) items: &'a [T],
+    pub(crate) index: usize,
+}
+
+impl<'a, T, S> Iterator for TryEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = Result<(Index, usize), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.items.len() {
+            return None;
+        }
+        let i = self.index;
+        match self.table.get_index(&self.items[i]) {
+            Some(ind) => {
+                let len = self.table.run_len(&self.items[i..]);
+                self.index += len;
+                Some(Ok((ind, len)))
+            }
+            None => {
+                self.index = self.items.len();
+                Some(Err(Error::TableMissingItems(i)))
+            }
+        }
+    }
+
+    /// Every remaining item could be its own run.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let items = self.items.len() - self.index;
+        ((items > 0) as usize, Some(items))
+    }
+}
+
+impl<'a, T, S> FusedIterator for TryEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// series of [Run]s. See [encode_runs](crate::Table::encode_runs).
+pub type RunEncoder<'a, T, S = SortedStore<T>> = Map<Encoder<'a, T, S>, fn((Index, usize)) -> Run>;
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// series of `(index, len, start)` runs, where `start` is the position of
+/// the run's first item in the sequence. See [encode_with_offsets](crate::Table::encode_with_offsets).
+pub struct OffsetEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) encoder: Encoder<'a, T, S>,
+    pub(crate) position: usize,
+}
+
+impl<'a, T, S> Iterator for OffsetEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = (Index, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.encoder.next()?;
+        let start = self.position;
+        self.position += len;
+        Some((ind, len, start))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.encoder.size_hint()
+    }
+}
+
+impl<'a, T, S> FusedIterator for OffsetEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
 }
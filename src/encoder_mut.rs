@@ -1,18 +1,19 @@
-use crate::{Index, Table};
-use std::cmp::Ordering;
+use crate::{Index, SortedStore, Table, TableStore};
+use std::iter::FusedIterator;
 
 /// An iterator that run-length encodes a sequence of `T` values
 /// into a series of runs, and also adds elements to the table as
 /// it encounters them. See [encode_mut](crate::Table::encode_mut).
-pub struct EncoderMut<'a, T> {
-    pub(crate) table: &'a mut Table<T>,
+pub struct EncoderMut<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a mut Table<T, S>,
     pub(crate) items: &'a [T],
     pub(crate) index: usize,
 }
 
-impl<'a, T> Iterator for EncoderMut<'a, T>
+impl<'a, T, S> Iterator for EncoderMut<'a, T, S>
 where
-    T: Ord + Clone,
+    T: PartialEq + Clone,
+    S: TableStore<T>,
 {
     type Item = (Index, usize);
 
@@ -21,7 +22,10 @@ where
             let ind = self.index;
             let mut len = 1;
             while ind + len < self.items.len()
-                && self.items[ind].cmp(&self.items[ind + len]) == Ordering::Equal
+                && self
+                    .table
+                    .store()
+                    .same_item(&self.items[ind], &self.items[ind + len])
             {
                 len += 1;
             }
@@ -30,4 +34,17 @@ where
             (ind, len)
         })
     }
+
+    /// Every remaining item could be its own run.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let items = self.items.len() - self.index;
+        ((items > 0) as usize, Some(items))
+    }
+}
+
+impl<'a, T, S> FusedIterator for EncoderMut<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
 }
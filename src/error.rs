@@ -1,9 +1,10 @@
 use std::fmt::{Display, Formatter, Result};
 
-/// A failure to encode.
+/// A failure to encode or decode.
 #[derive(Debug, Clone)]
 pub enum Error {
-    /// Failed to encode as bytes because the table exceeded 127 items.
+    /// Failed to encode as bytes because the table had too many items for
+    /// the format (more than 127 items for most formats).
     ///
     /// The contained value is the size of the table.
     TableTooLarge(usize),
@@ -13,13 +14,176 @@ pub enum Error {
     /// The contained value is the index of the offending item in the
     /// slice that was being encoded.
     TableMissingItems(usize),
+
+    /// Failed to encode because a scanline's bytes did not fit in its
+    /// 16-bit length header.
+    ///
+    /// The contained value is the index of the offending row.
+    ScanlineTooLarge(usize),
+
+    /// Failed to decode because the bytes were malformed or ended early.
+    ///
+    /// The contained value is the offset of the run that could not be read.
+    InvalidBytes(usize),
+
+    /// Failed to decode because a run referred to an index not in the table.
+    ///
+    /// The contained value is the offending index.
+    UnknownIndex(usize),
+
+    /// Failed to decode because the bytes ended in the middle of a run.
+    TruncatedStream,
+
+    /// Failed to decode a sequence of runs because a run referred to an index
+    /// not in the table. This is returned by [try_decode](crate::Table::try_decode),
+    /// which knows the position of each run, while byte decoders return
+    /// [UnknownIndex](Error::UnknownIndex) instead.
+    ///
+    /// The contained values are the position of the offending run in the
+    /// sequence of runs, and the index it referred to.
+    InvalidRun(usize, usize),
+
+    /// Failed to decode because a run in a hexadecimal string was not in
+    /// the form `IND:LEN`.
+    ///
+    /// The contained value is the byte offset of the run in the string.
+    InvalidHex(usize),
+
+    /// Failed to parse because a run in a string was not in the form `LENITEM`
+    /// (such as `5A`), or its item could not be parsed.
+    ///
+    /// The contained value is the byte offset of the run in the string.
+    InvalidRunText(usize),
+
+    /// Failed to decode because a string was not valid base64.
+    ///
+    /// The contained value is the byte offset of the invalid character.
+    InvalidBase64(usize),
+
+    /// Failed to encode or decode a grid because the number of items did not
+    /// match its width and height.
+    ///
+    /// The contained value is the number of items.
+    InvalidGridSize(usize),
+
+    /// Failed to encode or decode because the output did not fit in the
+    /// provided buffer.
+    ///
+    /// The contained value is the length the buffer needed to be: the number
+    /// of items the bytes decode to, or the number of bytes they encode to.
+    BufferTooSmall(usize),
+
+    /// Failed to decode because the bytes were written with a newer version
+    /// of the format than this crate supports.
+    ///
+    /// The contained value is the version found in the bytes.
+    UnsupportedVersion(u8),
+
+    /// Failed to convert runs because a run was too long for the new length type.
+    ///
+    /// The contained values are the position of the offending run in the
+    /// sequence of runs, and its length.
+    RunTooLong(usize, usize),
+
+    /// Failed to decode because the checksum stored with the bytes did not
+    /// match the bytes, meaning they were corrupted.
+    ///
+    /// The contained values are the stored checksum and the checksum of the
+    /// bytes that were read.
+    ChecksumMismatch(u32, u32),
+
+    /// Failed to encode because a length or offset did not fit in the
+    /// format's 32-bit header field.
+    ///
+    /// The contained value is the length.
+    LengthTooLarge(usize),
+
+    /// Failed to decode interleaved data because one of its planes did not
+    /// decode to the same number of items as the first plane.
+    ///
+    /// The contained values are the position of the offending plane, and the
+    /// number of items it decoded to.
+    PlaneLengthMismatch(usize, usize),
+
+    /// Failed to decode interleaved data because the number of planes did not
+    /// match the number of tables.
+    ///
+    /// The contained values are the number of planes and the number of tables.
+    PlaneCountMismatch(usize, usize),
+
+    /// Failed to import an Arrow array because its values were not of the
+    /// item type, or contained nulls. Returned by the Arrow conversions
+    /// enabled with the `arrow` feature.
+    InvalidArray,
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
-            Self::TableTooLarge(size) => write!(f, "Table size is {}, which exceeds the maximum for encoding as bytes (must be <=127 items)", size),
+            Self::TableTooLarge(size) => write!(f, "Table size is {}, which exceeds the maximum for this byte format (most formats must be <=127 items)", size),
             Self::TableMissingItems(index) => write!(f, "Cannot encode because item located at [{}] is not in the Table.", index),
+            Self::ScanlineTooLarge(row) => write!(f, "Scanline [{}] is too large to encode (must be <=65535 bytes)", row),
+            Self::InvalidBytes(offset) => write!(f, "Cannot decode the run at byte [{}] because it is malformed", offset),
+            Self::UnknownIndex(index) => write!(f, "Cannot decode because index [{}] is not in the Table.", index),
+            Self::TruncatedStream => write!(f, "Cannot decode because the bytes ended in the middle of a run"),
+            Self::InvalidRun(run, index) => write!(f, "Cannot decode because run [{}] refers to index [{}], which is not in the Table.", run, index),
+            Self::InvalidHex(offset) => write!(f, "Cannot decode the run at [{}] because it is not a pair of hex numbers", offset),
+            Self::InvalidRunText(offset) => write!(f, "Cannot parse the run at [{}] because it is not a length followed by an item", offset),
+            Self::InvalidBase64(offset) => write!(f, "Cannot decode because the character at [{}] is not valid base64", offset),
+            Self::InvalidGridSize(len) => write!(f, "Grid has {} items, which does not match its width and height", len),
+            Self::BufferTooSmall(len) => write!(f, "The output needs a buffer of length {}, which is larger than the one provided", len),
+            Self::UnsupportedVersion(version) => write!(f, "Cannot decode because format version {} is not supported", version),
+            Self::InvalidArray => write!(f, "Cannot import the array because its values are the wrong type or contain nulls"),
+            Self::RunTooLong(run, len) => write!(f, "Cannot convert run [{}] because its length {} does not fit in the length type", run, len),
+            Self::ChecksumMismatch(expected, actual) => write!(f, "Cannot decode because the checksum {:08X} does not match the bytes (found {:08X})", expected, actual),
+            Self::LengthTooLarge(len) => write!(f, "Cannot encode the length {} because it does not fit in 32 bits", len),
+            Self::PlaneLengthMismatch(plane, len) => write!(f, "Plane [{}] has {} items, which does not match the length of the other planes", plane, len),
+            Self::PlaneCountMismatch(planes, tables) => write!(f, "Cannot decode {} planes with {} tables", planes, tables),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A failure to encode items read from a fallible source.
+/// See [encode_try_iter](crate::Table::encode_try_iter).
+#[derive(Debug, Clone)]
+pub enum EncodeError<E> {
+    /// The source of the items failed.
+    ///
+    /// The contained value is the source's error.
+    Source(E),
+
+    /// The items could not be encoded.
+    ///
+    /// The contained value is the reason why.
+    Encode(Error),
+}
+
+impl<E> From<Error> for EncodeError<E> {
+    fn from(err: Error) -> Self {
+        Self::Encode(err)
+    }
+}
+
+impl<E: Display> Display for EncodeError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Source(err) => write!(
+                f,
+                "Cannot encode because the items could not be read: {}",
+                err
+            ),
+            Self::Encode(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for EncodeError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Source(err) => Some(err),
+            Self::Encode(err) => Some(err),
         }
     }
 }
@@ -0,0 +1,197 @@
+//! A C interface to the byte format, for engines and tools that are not
+//! written in Rust. Only available with the `ffi` feature.
+//!
+//! Symbols are `uint32_t` values (tile ids, palette entries, and so on), and
+//! every function reports failure with an [RleStatus]. After an error, the
+//! message can be read with [rle_last_error] on the same thread.
+//!
+//! The matching header is `include/rle.h`. To build a static library:
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! ```
+
+use crate::{Error, Table};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+/// An opaque table of `uint32_t` symbols.
+pub struct RleTable(Table<u32>);
+
+/// The result of a call into the C interface.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RleStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// The output buffer was too small. The required length has been written
+    /// to `out_len`, so the call can be retried with a larger buffer.
+    BufferTooSmall = 2,
+    /// Encoding or decoding failed. See [rle_last_error].
+    Failed = 3,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(msg: String) {
+    let msg = CString::new(msg).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+fn fail(err: Error) -> RleStatus {
+    set_error(err.to_string());
+    RleStatus::Failed
+}
+
+unsafe fn input<'a, T>(data: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if data.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(data, len))
+    }
+}
+
+/// Creates a table from `len` symbols, in index order. Returns null if
+/// `items` is null and `len` is not zero. The table must be released with
+/// [rle_table_free].
+///
+/// # Safety
+///
+/// `items` must point to `len` readable symbols.
+#[no_mangle]
+pub unsafe extern "C" fn rle_table_new(items: *const u32, len: usize) -> *mut RleTable {
+    match input(items, len) {
+        Some(items) => Box::into_raw(Box::new(RleTable(Table::from_slice(items)))),
+        None => {
+            set_error("items is null".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a table created with [rle_table_new]. Passing null does nothing.
+///
+/// # Safety
+///
+/// `table` must be null or a table from [rle_table_new] that has not already
+/// been released.
+#[no_mangle]
+pub unsafe extern "C" fn rle_table_free(table: *mut RleTable) {
+    if !table.is_null() {
+        drop(Box::from_raw(table));
+    }
+}
+
+/// The number of symbols in the table, or zero if `table` is null.
+///
+/// # Safety
+///
+/// `table` must be null or a live table from [rle_table_new].
+#[no_mangle]
+pub unsafe extern "C" fn rle_table_len(table: *const RleTable) -> usize {
+    table.as_ref().map_or(0, |t| t.0.len())
+}
+
+/// Encodes `len` symbols in the byte format, writing them to `out` and the
+/// number of bytes written to `out_len`.
+///
+/// If `out_cap` is too small, nothing is written to `out`, the required
+/// length is written to `out_len`, and [RleStatus::BufferTooSmall] is
+/// returned. Passing a null `out` with a zero `out_cap` queries the length.
+///
+/// # Safety
+///
+/// `table` must be a live table, `items` must point to `len` readable
+/// symbols, `out` must point to `out_cap` writable bytes, and `out_len`
+/// must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn rle_encode(
+    table: *const RleTable,
+    items: *const u32,
+    len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> RleStatus {
+    let (table, items, out_len) = match (table.as_ref(), input(items, len), out_len.as_mut()) {
+        (Some(table), Some(items), Some(out_len)) => (table, items, out_len),
+        _ => return RleStatus::NullPointer,
+    };
+    let mut bytes = Vec::new();
+    if let Err(err) = table.0.encode_bytes_into(items, &mut bytes) {
+        return fail(err);
+    }
+    *out_len = bytes.len();
+    if bytes.len() > out_cap {
+        return RleStatus::BufferTooSmall;
+    }
+    if !bytes.is_empty() {
+        if out.is_null() {
+            return RleStatus::NullPointer;
+        }
+        ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    }
+    RleStatus::Ok
+}
+
+/// Decodes `len` bytes written by [rle_encode], writing the symbols to `out`
+/// and the number of symbols to `out_len`.
+///
+/// If `out_cap` is too small, the contents of `out` are unspecified, the
+/// required length is written to `out_len`, and [RleStatus::BufferTooSmall]
+/// is returned.
+///
+/// # Safety
+///
+/// `table` must be a live table, `bytes` must point to `len` readable bytes,
+/// `out` must point to `out_cap` writable symbols, and `out_len` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn rle_decode(
+    table: *const RleTable,
+    bytes: *const u8,
+    len: usize,
+    out: *mut u32,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> RleStatus {
+    let (table, bytes, out_len) = match (table.as_ref(), input(bytes, len), out_len.as_mut()) {
+        (Some(table), Some(bytes), Some(out_len)) => (table, bytes, out_len),
+        _ => return RleStatus::NullPointer,
+    };
+    let out: &mut [u32] = if out_cap == 0 {
+        &mut []
+    } else if out.is_null() {
+        return RleStatus::NullPointer;
+    } else {
+        slice::from_raw_parts_mut(out, out_cap)
+    };
+    match table.0.decode_bytes_to_slice(bytes, out) {
+        Ok(n) => {
+            *out_len = n;
+            RleStatus::Ok
+        }
+        Err(Error::BufferTooSmall(n)) => {
+            *out_len = n;
+            RleStatus::BufferTooSmall
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// The message for the last failure on this thread, or null if there has
+/// been none. The string is owned by the library and stays valid until the
+/// next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn rle_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
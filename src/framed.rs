@@ -0,0 +1,167 @@
+use crate::{EncodeOptions, Error, Table, TableStore, Width};
+use std::iter::repeat_n;
+
+const FRAME_MAGIC: &[u8; 4] = b"RLEB";
+
+/// The size of the frame header in bytes.
+const HEADER_LEN: usize = 6;
+
+/// The version of the frame header written by [encode_framed](Table::encode_framed).
+pub const BYTE_FORMAT_VERSION: u8 = 1;
+
+/// A byte format that can be stored in a frame. See [encode_framed](Table::encode_framed).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ByteFormat {
+    /// The format of [encode_bytes](Table::encode_bytes).
+    Bytes,
+
+    /// The format of [encode_bytes_with](Table::encode_bytes_with) with
+    /// [literal_blocks](EncodeOptions::literal_blocks).
+    LiteralBlocks,
+
+    /// The format of [encode_bytes_varint](Table::encode_bytes_varint).
+    Varint,
+
+    /// The format of [encode_bytes_wide](Table::encode_bytes_wide).
+    Wide(Width),
+}
+
+impl ByteFormat {
+    fn flags(self) -> u8 {
+        match self {
+            Self::Bytes | Self::Wide(Width::U8) => 0,
+            Self::LiteralBlocks => 1,
+            Self::Varint => 2,
+            Self::Wide(Width::U16) => 3,
+            Self::Wide(Width::U32) => 4,
+        }
+    }
+
+    fn from_flags(flags: u8) -> Option<Self> {
+        Some(match flags {
+            0 => Self::Bytes,
+            1 => Self::LiteralBlocks,
+            2 => Self::Varint,
+            3 => Self::Wide(Width::U16),
+            4 => Self::Wide(Width::U32),
+            _ => return None,
+        })
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items in the chosen byte format, after a header
+    /// that records which format was used. The bytes can be decoded with
+    /// [decode_framed](Table::decode_framed) without knowing the format.
+    ///
+    /// # Format
+    ///
+    /// The bytes start with a 6 byte header:
+    ///
+    /// - The magic bytes `RLEB`.
+    /// - The version of the header, currently [BYTE_FORMAT_VERSION].
+    /// - A flags byte identifying the format. All other values are reserved.
+    ///
+    /// The rest of the bytes are the items, encoded in that format.
+    ///
+    /// ```
+    /// # use rle::{ByteFormat, Table};
+    /// let str: Vec<char> = "AAAABBC".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let bytes = table.encode_framed(&str, ByteFormat::Varint).unwrap();
+    /// assert_eq!(&bytes[..6], [b'R', b'L', b'E', b'B', 1, 2]);
+    /// assert_eq!(table.decode_framed(&bytes).unwrap(), str);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as the chosen format's encoder.
+    pub fn encode_framed(&self, items: &[T], format: ByteFormat) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(FRAME_MAGIC);
+        bytes.push(BYTE_FORMAT_VERSION);
+        bytes.push(format.flags());
+        match format {
+            ByteFormat::Bytes | ByteFormat::Wide(Width::U8) => {
+                self.encode_bytes_into(items, &mut bytes)?
+            }
+            ByteFormat::LiteralBlocks => {
+                bytes.extend(self.encode_bytes_with(items, EncodeOptions::new().literal_blocks())?)
+            }
+            ByteFormat::Varint => bytes.extend(self.encode_bytes_varint(items)?),
+            ByteFormat::Wide(width) => bytes.extend(self.encode_bytes_wide(items, width)?),
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes bytes written by [encode_framed](Table::encode_framed), using
+    /// the header to choose the decoder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBytes](Error::InvalidBytes) error if the header is
+    /// missing or has unknown flags, or an [UnsupportedVersion](Error::UnsupportedVersion)
+    /// error if it was written by a newer version. Otherwise, fails under the
+    /// same conditions as the format's decoder.
+    pub fn decode_framed(&self, bytes: &[u8]) -> Result<Vec<T>, Error> {
+        let format = framed_format(bytes)?;
+        let payload = &bytes[HEADER_LEN..];
+        match format {
+            ByteFormat::Bytes | ByteFormat::Wide(Width::U8) => {
+                let mut items = Vec::new();
+                self.decode_bytes_into(payload, &mut items)?;
+                Ok(items)
+            }
+            ByteFormat::LiteralBlocks => {
+                self.decode_bytes_with(payload, EncodeOptions::new().literal_blocks())
+            }
+            ByteFormat::Varint => self.decode_bytes_varint(payload),
+            ByteFormat::Wide(width) => self.decode_wide_checked(payload, width),
+        }
+    }
+
+    /// Decodes the wide format, reporting malformed bytes as errors.
+    fn decode_wide_checked(&self, bytes: &[u8], width: Width) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        let mut decoder = self.decode_bytes_wide(bytes, width);
+        loop {
+            let offset = bytes.len() - decoder.bytes.len();
+            let (ind, len) = match decoder.read_run()? {
+                Some(run) => run,
+                None => return Ok(items),
+            };
+            let item = self.item(ind).ok_or(Error::UnknownIndex(ind))?;
+            // The length is untrusted, so reserving for it may fail
+            if items.try_reserve(len).is_err() {
+                return Err(Error::InvalidBytes(HEADER_LEN + offset));
+            }
+            items.extend(repeat_n(item.clone(), len));
+        }
+    }
+}
+
+/// Reads the header of bytes written by [encode_framed](Table::encode_framed),
+/// returning the format of the items that follow it.
+///
+/// # Errors
+///
+/// Fails if the header is invalid, as in [decode_framed](Table::decode_framed).
+pub fn framed_format(bytes: &[u8]) -> Result<ByteFormat, Error> {
+    if !bytes.starts_with(FRAME_MAGIC) {
+        return Err(Error::InvalidBytes(0));
+    }
+    match bytes.get(4) {
+        Some(&BYTE_FORMAT_VERSION) => {}
+        Some(&version) => return Err(Error::UnsupportedVersion(version)),
+        None => return Err(Error::InvalidBytes(4)),
+    }
+    bytes
+        .get(5)
+        .and_then(|&flags| ByteFormat::from_flags(flags))
+        .ok_or(Error::InvalidBytes(5))
+}
@@ -0,0 +1,101 @@
+use crate::{SortedStore, Table, TableStore};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An immutable table that can be cheaply cloned and shared between threads,
+/// such as a palette that many chunks are decoded with at once.
+/// See [freeze](Table::freeze).
+///
+/// Clones share the same table, and it derefs to [Table], so every method
+/// that doesn't modify the table (including all the decoders) can be used
+/// on it directly. It is [Send] and [Sync] as long as the items are.
+///
+/// ```
+/// use rle::Table;
+///
+/// let palette = Table::from_slice(&['A', 'B', 'C']).freeze();
+/// let chunks = [vec![(0, 3), (1, 2)], vec![(2, 4)]];
+/// let decoded: Vec<String> = std::thread::scope(|scope| {
+///     let threads: Vec<_> = chunks
+///         .iter()
+///         .map(|runs| {
+///             let palette = palette.clone();
+///             scope.spawn(move || palette.decode(runs).collect::<String>())
+///         })
+///         .collect();
+///     threads.into_iter().map(|t| t.join().unwrap()).collect()
+/// });
+/// assert_eq!(decoded, ["AAABB", "CCCC"]);
+///
+/// let mut table = palette.thaw();
+/// table.insert('D');
+/// ```
+#[derive(Debug)]
+pub struct FrozenTable<T, S = SortedStore<T>> {
+    table: Arc<Table<T, S>>,
+}
+
+impl<T, S> FrozenTable<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T> + Clone,
+{
+    /// Returns the table so it can be modified again. This only copies the
+    /// table if it is still shared with other clones.
+    pub fn thaw(self) -> Table<T, S> {
+        Arc::try_unwrap(self.table).unwrap_or_else(|table| (*table).clone())
+    }
+}
+
+impl<T, S> FrozenTable<T, S> {
+    /// Returns `true` if both are clones of the same frozen table.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.table, &other.table)
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Makes the table immutable, so that it can be shared between threads.
+    /// Use [thaw](FrozenTable::thaw) to modify it again.
+    pub fn freeze(self) -> FrozenTable<T, S> {
+        FrozenTable {
+            table: Arc::new(self),
+        }
+    }
+}
+
+impl<T, S> Clone for FrozenTable<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            table: Arc::clone(&self.table),
+        }
+    }
+}
+
+impl<T, S> Deref for FrozenTable<T, S> {
+    type Target = Table<T, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.table
+    }
+}
+
+impl<T, S> AsRef<Table<T, S>> for FrozenTable<T, S> {
+    fn as_ref(&self) -> &Table<T, S> {
+        &self.table
+    }
+}
+
+impl<T, S> From<Table<T, S>> for FrozenTable<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn from(table: Table<T, S>) -> Self {
+        table.freeze()
+    }
+}
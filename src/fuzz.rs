@@ -0,0 +1,83 @@
+use crate::{Table, TableItem};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Builds a table from arbitrary items, skipping any repeated items.
+impl<'a, T> Arbitrary<'a> for Table<T>
+where
+    T: Arbitrary<'a> + Ord + Clone,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut table = Table::default();
+        for item in u.arbitrary_iter::<T>()? {
+            table.insert(item?);
+        }
+        Ok(table)
+    }
+}
+
+/// Generates structurally valid run-length encoded bytes (see [encode_bytes](Table::encode_bytes))
+/// for a table of `table_len` items, for fuzzing code that decodes them.
+///
+/// Every run refers to an index below `table_len` (and below 128), and is
+/// written as a single byte for runs of 1 or a header and length byte for
+/// runs of 2 to 255. No bytes are generated for an empty table.
+pub fn arbitrary_bytes(u: &mut Unstructured<'_>, table_len: usize) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if table_len == 0 {
+        return Ok(bytes);
+    }
+    let table_len = table_len.min(128);
+    for run in u.arbitrary_iter::<(u8, u8)>()? {
+        let (ind, len) = run?;
+        let ind = ((ind as usize % table_len) as u8) << 1;
+        match len.max(1) {
+            1 => bytes.push(ind),
+            len => bytes.extend_from_slice(&[ind | 1, len]),
+        }
+    }
+    Ok(bytes)
+}
+
+/// A table of up to 127 items, along with structurally valid bytes encoded
+/// with it (see [arbitrary_bytes]), for fuzzing a pipeline end to end.
+///
+/// Decoding the bytes with the table always succeeds.
+#[derive(Clone, Debug)]
+pub struct EncodedBytes<T> {
+    pub table: Table<T>,
+    pub bytes: Vec<u8>,
+}
+
+impl<'a, T> Arbitrary<'a> for EncodedBytes<T>
+where
+    T: Arbitrary<'a> + Ord + Clone,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut table = Table::default();
+        for item in u.arbitrary_iter::<T>()? {
+            if table.len() == 127 {
+                break;
+            }
+            table.insert(item?);
+        }
+        let bytes = arbitrary_bytes(u, table.len())?;
+        Ok(Self { table, bytes })
+    }
+}
+
+impl<T> EncodedBytes<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Packs the table and bytes into a blob that can be decoded with
+    /// [decode_container](Table::decode_container).
+    pub fn to_container(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        (self.table.len() as u32).write_bytes(&mut bytes);
+        for item in self.table.iter() {
+            item.write_bytes(&mut bytes);
+        }
+        bytes.extend_from_slice(&self.bytes);
+        bytes
+    }
+}
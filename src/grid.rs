@@ -0,0 +1,338 @@
+use crate::bulk::push_run;
+use crate::{Error, Index, Table, TableStore};
+
+/// The order to visit the cells of a grid in when encoding it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScanOrder {
+    /// Visit each row from left to right, from the top row down.
+    RowMajor,
+
+    /// Visit each column from top to bottom, from the left column across.
+    /// Tilemaps with tall features, such as walls or shafts, often compress
+    /// much better in this order.
+    ColumnMajor,
+}
+
+/// The dimensions of a grid of items, and how to scan it when encoding.
+/// See [encode_grid](crate::Table::encode_grid).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GridLayout {
+    pub width: usize,
+    pub height: usize,
+    pub order: ScanOrder,
+
+    /// If `true`, runs end at the end of every row (or column, for
+    /// [ColumnMajor](ScanOrder::ColumnMajor) order), instead of continuing
+    /// into the next one.
+    pub break_lines: bool,
+}
+
+impl GridLayout {
+    /// A row-major layout of the given size, where runs continue from one
+    /// row into the next.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            order: ScanOrder::RowMajor,
+            break_lines: false,
+        }
+    }
+
+    /// Scans the grid in column-major order.
+    pub fn column_major(mut self) -> Self {
+        self.order = ScanOrder::ColumnMajor;
+        self
+    }
+
+    /// Ends runs at the end of every line.
+    pub fn break_lines(mut self) -> Self {
+        self.break_lines = true;
+        self
+    }
+
+    /// The number of cells in the grid, saturating at `usize::MAX` if the
+    /// width and height are too large to multiply.
+    pub fn len(&self) -> usize {
+        self.width.saturating_mul(self.height)
+    }
+
+    /// The number of cells in the grid, or `None` if it overflows.
+    pub(crate) fn checked_len(&self) -> Option<usize> {
+        self.width.checked_mul(self.height)
+    }
+
+    /// Returns `true` if the grid has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Mirrors a grid from left to right, given its runs in the layout's
+    /// scan order, without decoding it. The flipped runs are in the same
+    /// order, and end at the end of every line if [break_lines](GridLayout::break_lines)
+    /// is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidGridSize](Error::InvalidGridSize) error if the runs
+    /// don't cover the grid exactly.
+    ///
+    /// ```
+    /// # use rle::GridLayout;
+    /// let layout = GridLayout::new(3, 2);
+    /// let runs = [(0, 2), (1, 3), (2, 1)];
+    /// assert_eq!(layout.flip_horizontal(&runs).unwrap(), [(1, 1), (0, 2), (2, 1), (1, 2)]);
+    /// ```
+    pub fn flip_horizontal(&self, runs: &[(Index, usize)]) -> Result<Vec<(Index, usize)>, Error> {
+        let lines = self.lines(runs)?;
+        Ok(match self.order {
+            ScanOrder::RowMajor => self.join(lines.iter().map(|line| line.iter().rev())),
+            ScanOrder::ColumnMajor => self.join(lines.iter().rev()),
+        })
+    }
+
+    /// Mirrors a grid from top to bottom, given its runs in the layout's
+    /// scan order, without decoding it. The flipped runs are in the same
+    /// order, and end at the end of every line if [break_lines](GridLayout::break_lines)
+    /// is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidGridSize](Error::InvalidGridSize) error if the runs
+    /// don't cover the grid exactly.
+    ///
+    /// ```
+    /// # use rle::GridLayout;
+    /// let layout = GridLayout::new(3, 2);
+    /// let runs = [(0, 2), (1, 3), (2, 1)];
+    /// assert_eq!(layout.flip_vertical(&runs).unwrap(), [(1, 2), (2, 1), (0, 2), (1, 1)]);
+    /// ```
+    pub fn flip_vertical(&self, runs: &[(Index, usize)]) -> Result<Vec<(Index, usize)>, Error> {
+        let lines = self.lines(runs)?;
+        Ok(match self.order {
+            ScanOrder::RowMajor => self.join(lines.iter().rev()),
+            ScanOrder::ColumnMajor => self.join(lines.iter().map(|line| line.iter().rev())),
+        })
+    }
+
+    /// Rotates a grid a quarter turn clockwise, given its runs in the layout's
+    /// scan order, returning the runs of the rotated grid in the same order.
+    /// The rotated grid is `height` cells wide and `width` cells tall, and its
+    /// runs end at the end of every line if [break_lines](GridLayout::break_lines)
+    /// is set.
+    ///
+    /// Each line keeps a cursor into its runs, so the runs are never decoded,
+    /// but every cell is still visited once. Rotating three times turns the
+    /// grid counterclockwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidGridSize](Error::InvalidGridSize) error if the runs
+    /// don't cover the grid exactly.
+    ///
+    /// ```
+    /// # use rle::GridLayout;
+    /// // AAB
+    /// // CCC
+    /// let layout = GridLayout::new(3, 2);
+    /// let runs = [(0, 2), (1, 1), (2, 3)];
+    ///
+    /// // CA
+    /// // CA
+    /// // CB
+    /// let rotated = layout.rotate90(&runs).unwrap();
+    /// assert_eq!(rotated, [(2, 1), (0, 1), (2, 1), (0, 1), (2, 1), (1, 1)]);
+    /// ```
+    pub fn rotate90(&self, runs: &[(Index, usize)]) -> Result<Vec<(Index, usize)>, Error> {
+        // Each line of the rotated grid takes one cell from every line, from
+        // the last row up for row-major order, or from the bottom of every
+        // column for column-major order
+        let mut lines = self.lines(runs)?;
+        match self.order {
+            ScanOrder::RowMajor => lines.reverse(),
+            ScanOrder::ColumnMajor => lines.iter_mut().for_each(|line| line.reverse()),
+        }
+        let mut cursors = vec![(0, 0); lines.len()];
+        let mut rotated = Vec::new();
+        for _ in 0..self.line_len() {
+            for (i, (line, (run, used))) in lines.iter().zip(&mut cursors).enumerate() {
+                let (ind, len) = line[*run];
+                push_merged(&mut rotated, ind, 1, i > 0 || !self.break_lines);
+                *used += 1;
+                if *used == len {
+                    *run += 1;
+                    *used = 0;
+                }
+            }
+        }
+        Ok(rotated)
+    }
+
+    /// Splits runs of the grid in scan order into the runs of each line.
+    fn lines(&self, runs: &[(Index, usize)]) -> Result<Vec<Vec<(Index, usize)>>, Error> {
+        let total = runs
+            .iter()
+            .try_fold(0usize, |total, &(_, len)| total.checked_add(len));
+        if total.is_none() || total != self.checked_len() {
+            return Err(Error::InvalidGridSize(total.unwrap_or(usize::MAX)));
+        }
+        let line_len = self.line_len();
+        let count = match self.order {
+            ScanOrder::RowMajor => self.height,
+            ScanOrder::ColumnMajor => self.width,
+        };
+        let mut lines = vec![Vec::new(); count];
+        let mut pos = 0;
+        for &(ind, mut len) in runs {
+            while len > 0 {
+                let num = len.min(line_len - pos % line_len);
+                lines[pos / line_len].push((ind, num));
+                pos += num;
+                len -= num;
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Joins the runs of each line, only merging runs across lines if
+    /// [break_lines](GridLayout::break_lines) isn't set.
+    fn join<'r, L, I>(&self, lines: L) -> Vec<(Index, usize)>
+    where
+        L: IntoIterator<Item = I>,
+        I: IntoIterator<Item = &'r (Index, usize)>,
+    {
+        let mut joined = Vec::new();
+        for line in lines {
+            for (i, &(ind, len)) in line.into_iter().enumerate() {
+                push_merged(&mut joined, ind, len, i > 0 || !self.break_lines);
+            }
+        }
+        joined
+    }
+
+    /// The number of cells in each line, in scan order.
+    pub(crate) fn line_len(&self) -> usize {
+        match self.order {
+            ScanOrder::RowMajor => self.width,
+            ScanOrder::ColumnMajor => self.height,
+        }
+    }
+
+    /// The row-major position of the `i`th cell in scan order.
+    pub(crate) fn position(&self, i: usize) -> usize {
+        match self.order {
+            ScanOrder::RowMajor => i,
+            ScanOrder::ColumnMajor => (i % self.height) * self.width + i / self.height,
+        }
+    }
+}
+
+/// Appends a run, merging it with the last run if `merge` is set and they
+/// have the same index.
+fn push_merged(runs: &mut Vec<(Index, usize)>, ind: Index, len: usize, merge: bool) {
+    match runs.last_mut() {
+        Some(last) if merge && last.0 == ind => last.1 += len,
+        _ => runs.push((ind, len)),
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes a grid of items, stored in row-major order, as a
+    /// sequence of bytes (see [encode_bytes](Table::encode_bytes)). The grid
+    /// is scanned in the order given by the layout, so there is no need to
+    /// transpose it first.
+    ///
+    /// ```
+    /// # use rle::{GridLayout, Table};
+    /// let grid: Vec<char> = "\
+    ///     .X..\
+    ///     .X..\
+    ///     .X..".chars().collect();
+    /// let table = Table::from_slice(&['.', 'X']);
+    ///
+    /// let rows = table.encode_grid(GridLayout::new(4, 3), &grid).unwrap();
+    /// let columns = table.encode_grid(GridLayout::new(4, 3).column_major(), &grid).unwrap();
+    /// assert_eq!(rows.len(), 10);
+    /// assert_eq!(columns, [0x01, 0x03, 0x03, 0x03, 0x01, 0x06]);
+    ///
+    /// let decoded = table.decode_grid(GridLayout::new(4, 3).column_major(), &columns).unwrap();
+    /// assert_eq!(decoded, grid);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table::encode_bytes),
+    /// or with an [InvalidGridSize](Error::InvalidGridSize) error if the
+    /// number of items doesn't match the size of the grid.
+    pub fn encode_grid(&self, layout: GridLayout, items: &[T]) -> Result<Vec<u8>, Error> {
+        if layout.checked_len() != Some(items.len()) {
+            return Err(Error::InvalidGridSize(items.len()));
+        }
+        if self.len() >= 128 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut bytes = Vec::new();
+        let mut run: Option<(usize, usize)> = None;
+        for i in 0..items.len() {
+            let pos = layout.position(i);
+            let line_start = layout.break_lines && i % layout.line_len() == 0;
+            match run.as_mut() {
+                Some((start, len))
+                    if !line_start && self.store().same_item(&items[*start], &items[pos]) =>
+                {
+                    *len += 1
+                }
+                _ => {
+                    if let Some((start, len)) = run {
+                        push_run(
+                            &mut bytes,
+                            self.get_index(&items[start]).unwrap() as u8,
+                            len,
+                        );
+                    }
+                    self.get_index(&items[pos])
+                        .ok_or(Error::TableMissingItems(pos))?;
+                    run = Some((pos, 1));
+                }
+            }
+        }
+        if let Some((start, len)) = run {
+            push_run(
+                &mut bytes,
+                self.get_index(&items[start]).unwrap() as u8,
+                len,
+            );
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes a grid encoded with [encode_grid](Table::encode_grid), using
+    /// the same layout, returning the items in row-major order.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [decode_bytes_into](Table::decode_bytes_into),
+    /// or with an [InvalidGridSize](Error::InvalidGridSize) error if the
+    /// number of decoded items doesn't match the size of the grid.
+    pub fn decode_grid(&self, layout: GridLayout, bytes: &[u8]) -> Result<Vec<T>, Error> {
+        // The layout may come from an untrusted header, so the output grows
+        // with the bytes rather than being allocated up front
+        let mut scanned = Vec::new();
+        self.decode_bytes_into(bytes, &mut scanned)?;
+        if layout.checked_len() != Some(scanned.len()) {
+            return Err(Error::InvalidGridSize(scanned.len()));
+        }
+        match layout.order {
+            ScanOrder::RowMajor => Ok(scanned),
+            ScanOrder::ColumnMajor => Ok((0..layout.len())
+                .map(|pos| {
+                    scanned[(pos % layout.width) * layout.height + pos / layout.width].clone()
+                })
+                .collect()),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{Index, StoreLookup, Table, TableStore};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A [Table] that looks up its items by hash, for item types that
+/// implement [Hash] and [Eq] but not [Ord].
+///
+/// ```
+/// use rle::{HashStore, HashTable};
+///
+/// #[derive(Clone, PartialEq, Eq, Hash)]
+/// enum Tile {
+///     Air,
+///     Dirt,
+/// }
+///
+/// let tiles = [Tile::Air, Tile::Air, Tile::Dirt];
+/// let mut table: HashTable<Tile> = HashTable::with_store(HashStore::new());
+/// let runs: Vec<_> = table.encode_mut(&tiles).collect();
+/// assert_eq!(runs, [(0, 2), (1, 1)]);
+/// ```
+pub type HashTable<T> = Table<T, HashStore<T>>;
+
+/// A [TableStore] that keeps the items in a list along with a hash map of
+/// their indices, so items only need to implement [Hash] and [Eq].
+#[derive(Clone, Debug)]
+pub struct HashStore<T> {
+    items: Vec<T>,
+    lookup: HashMap<T, Index>,
+}
+
+impl<T> Default for HashStore<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            lookup: HashMap::new(),
+        }
+    }
+}
+
+impl<T> HashStore<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Constructs a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new, empty store with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            lookup: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// The items in the order they were added.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<T> TableStore<T> for HashStore<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn get(&self, index: Index) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    fn index_of(&self, item: &T) -> Option<Index> {
+        self.lookup.get(item).copied()
+    }
+
+    fn insert_or_get(&mut self, item: &T) -> Index {
+        if let Some(&ind) = self.lookup.get(item) {
+            return ind;
+        }
+        let ind = self.items.len();
+        self.items.push(item.clone());
+        self.lookup.insert(item.clone(), ind);
+        ind
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.lookup.clear();
+    }
+}
+
+impl<T, Q> StoreLookup<Q> for HashStore<T>
+where
+    T: Borrow<Q> + Hash + Eq,
+    Q: Hash + Eq + ?Sized,
+{
+    fn index_of_key(&self, key: &Q) -> Option<Index> {
+        self.lookup.get(key).copied()
+    }
+}
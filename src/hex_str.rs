@@ -0,0 +1,116 @@
+use crate::{Error, Index, SortedStore, Table, TableStore};
+
+/// An iterator that decodes a string of hexadecimal runs into a series of
+/// `T` values fetched from the table. See [decode_hex_str_iter](crate::Table::decode_hex_str_iter).
+pub struct HexStrDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) str: &'a str,
+    pub(crate) offset: usize,
+    pub(crate) run: Option<(&'a T, usize)>,
+    pub(crate) failed: bool,
+}
+
+impl<'a, T, S> HexStrDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn read_run(&mut self) -> Option<Result<(&'a T, usize), Error>> {
+        if self.offset >= self.str.len() {
+            return None;
+        }
+        let rest = &self.str[self.offset..];
+        let (pair, next) = match rest.find(',') {
+            Some(end) => (&rest[..end], end + 1),
+            None => (rest, rest.len()),
+        };
+        let run = pair
+            .split_once(':')
+            .and_then(|(ind, len)| {
+                let ind = Index::from_str_radix(ind, 16).ok()?;
+                let len = usize::from_str_radix(len, 16).ok()?;
+                Some((ind, len))
+            })
+            .ok_or(Error::InvalidHex(self.offset))
+            .and_then(|(ind, len)| match self.table.item(ind) {
+                Some(item) => Ok((item, len)),
+                None => Err(Error::UnknownIndex(ind)),
+            });
+        self.offset += next;
+        Some(run)
+    }
+}
+
+impl<'a, T, S> Iterator for HexStrDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = Result<&'a T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let (item, len) = match self.run.take() {
+                Some(run) => run,
+                None => match self.read_run()? {
+                    Ok(run) => run,
+                    Err(err) => {
+                        self.failed = true;
+                        return Some(Err(err));
+                    }
+                },
+            };
+            // Skip runs of zero length
+            if len > 0 {
+                if len > 1 {
+                    self.run = Some((item, len - 1));
+                }
+                return Some(Ok(item));
+            }
+        }
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Decodes a string of runs written by [encode_hex_str](Table::encode_hex_str).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidHex](Error::InvalidHex) error if a run is not a
+    /// pair of hexadecimal numbers in the form `IND:LEN`, or an
+    /// [UnknownIndex](Error::UnknownIndex) error if a run refers to an index
+    /// not in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let items = table.decode_hex_str("0:3,1:A,").unwrap();
+    /// assert_eq!(items.iter().collect::<String>(), "AAABBBBBBBBBB");
+    /// ```
+    pub fn decode_hex_str(&self, str: &str) -> Result<Vec<T>, Error> {
+        self.decode_hex_str_iter(str)
+            .map(|item| item.cloned())
+            .collect()
+    }
+
+    /// Returns an iterator that decodes a string of runs written by
+    /// [encode_hex_str](Table::encode_hex_str), yielding the same errors as
+    /// [decode_hex_str](Table::decode_hex_str). Nothing more is decoded
+    /// after an error.
+    pub fn decode_hex_str_iter<'a>(&'a self, str: &'a str) -> HexStrDecoder<'a, T, S> {
+        HexStrDecoder {
+            table: self,
+            str,
+            offset: 0,
+            run: None,
+            failed: false,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{Error, HashStore, HashTable, Table, TableStore};
+use image::{ImageBuffer, Pixel};
+use std::hash::Hash;
+use std::ops::Deref;
+
+impl<P> HashTable<P>
+where
+    P: Pixel + Hash + Eq,
+{
+    /// Constructs a new table containing every unique pixel color in the
+    /// image. Enabled with the `image` feature.
+    pub fn from_image<C>(image: &ImageBuffer<P, C>) -> Self
+    where
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        let mut table = Self::with_store(HashStore::new());
+        table.extend(image.pixels().copied());
+        table
+    }
+}
+
+impl<P, S> Table<P, S>
+where
+    P: Pixel + PartialEq,
+    S: TableStore<P>,
+{
+    /// Encodes the pixels of an image into a chunk (see [encode_chunk](Table::encode_chunk)),
+    /// which can be decoded back into an image with [decode_image](Table::decode_image).
+    /// Enabled with the `image` feature.
+    ///
+    /// Pixel types don't implement [Ord], so the table is usually a
+    /// [HashTable], as created by [from_image](Table::from_image). For
+    /// images of palette indices, such as a [GrayImage](image::GrayImage),
+    /// the table maps each index to itself.
+    ///
+    /// ```
+    /// # use rle::HashTable;
+    /// use image::{Rgba, RgbaImage};
+    ///
+    /// let mut sprite = RgbaImage::new(16, 16);
+    /// for y in 4..12 {
+    ///     for x in 4..12 {
+    ///         sprite.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+    ///     }
+    /// }
+    ///
+    /// let table = HashTable::from_image(&sprite);
+    /// let bytes = table.encode_image(&sprite).unwrap();
+    /// assert_eq!(table.decode_image(&bytes).unwrap(), sprite);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_chunk](Table::encode_chunk).
+    pub fn encode_image<C>(&self, image: &ImageBuffer<P, C>) -> Result<Vec<u8>, Error>
+    where
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        let pixels: Vec<P> = image.pixels().copied().collect();
+        self.encode_chunk(image.width() as usize, image.height() as usize, &pixels)
+    }
+
+    /// Decodes an image encoded with [encode_image](Table::encode_image).
+    /// Enabled with the `image` feature.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [decode_chunk](Table::decode_chunk),
+    /// or with an [InvalidGridSize](Error::InvalidGridSize) error if the
+    /// dimensions are too large for an image.
+    pub fn decode_image(&self, bytes: &[u8]) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, Error> {
+        let (width, height, pixels) = self.decode_chunk(bytes)?;
+        if width > u32::MAX as usize || height > u32::MAX as usize {
+            return Err(Error::InvalidGridSize(pixels.len()));
+        }
+        Ok(ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+            pixels[y as usize * width + x as usize]
+        }))
+    }
+}
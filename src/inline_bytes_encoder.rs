@@ -0,0 +1,60 @@
+use crate::bulk::MAX_RUN;
+use crate::{EncoderMut, Error, SortedStore, TableItem, TableStore};
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// compressed byte format, adding elements to the table as it encounters
+/// them and embedding each new element in the byte stream the first time
+/// it appears. See [encode_bytes_inline](crate::Table::encode_bytes_inline).
+pub struct InlineBytesEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) rle: EncoderMut<'a, T, S>,
+    pub(crate) run: Option<(u8, usize)>,
+    pub(crate) packet: Vec<u8>,
+    pub(crate) pos: usize,
+}
+
+impl<'a, T, S> Iterator for InlineBytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone + TableItem,
+    S: TableStore<T>,
+{
+    type Item = Result<u8, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&byte) = self.packet.get(self.pos) {
+            self.pos += 1;
+            return Some(Ok(byte));
+        }
+        self.packet.clear();
+        self.pos = 1;
+
+        let (ind, len, is_new) = if let Some((ind, len)) = self.run.take() {
+            (ind, len, false)
+        } else {
+            let known = self.rle.table.len();
+            let (ind, len) = self.rle.next()?;
+            if ind >= 128 {
+                return Some(Err(Error::TableTooLarge(self.rle.table.len())));
+            }
+            (ind as u8, len, ind >= known)
+        };
+
+        let num = len.min(MAX_RUN);
+        if len > 1 {
+            self.packet.push((ind << 1) | 1);
+            self.packet.push(num as u8);
+        } else {
+            self.packet.push(ind << 1);
+        }
+        if is_new {
+            self.rle
+                .table
+                .item(ind as usize)
+                .unwrap()
+                .write_bytes(&mut self.packet);
+        }
+        if len > num {
+            self.run = Some((ind, len - num));
+        }
+        Some(Ok(self.packet[0]))
+    }
+}
@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::rc::Rc;
+
+/// A shared handle to a value stored in an [Interner].
+///
+/// Handles are compared, ordered and hashed by the id the interner gave
+/// their value, rather than by the value itself, so using them as table
+/// items makes finding runs and looking up items as cheap as comparing two
+/// integers, and adding an item to a table only clones an [Rc]. Handles from
+/// different interners should not be mixed, since their ids are unrelated.
+pub struct Interned<T> {
+    id: usize,
+    value: Rc<T>,
+}
+
+impl<T> Interned<T> {
+    /// The id of the value, which is the number of values that had been
+    /// interned before it.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The shared value.
+    pub fn rc(&self) -> &Rc<T> {
+        &self.value
+    }
+}
+
+impl<T> Clone for Interned<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            value: Rc::clone(&self.value),
+        }
+    }
+}
+
+impl<T> Deref for Interned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> PartialEq for Interned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Interned<T> {}
+
+impl<T> PartialOrd for Interned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Interned<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> Hash for Interned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T: Debug> Debug for Interned<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Interned")
+            .field(&self.id)
+            .field(&self.value)
+            .finish()
+    }
+}
+
+/// Stores each unique value once and hands out cheap [Interned] handles to
+/// them, for tables of large items (such as tile definitions with many
+/// properties) that are expensive to clone and compare.
+///
+/// The values only need to be compared once, when they are interned. After
+/// that, the handles can be encoded with any table, which will only compare
+/// their ids.
+///
+/// ```
+/// use rle::{Interner, Table};
+///
+/// #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Tile {
+///     name: String,
+///     solid: bool,
+/// }
+///
+/// let air = Tile { name: "air".into(), solid: false };
+/// let rock = Tile { name: "rock".into(), solid: true };
+/// let tiles = [air.clone(), air.clone(), air, rock.clone(), rock];
+///
+/// let mut interner = Interner::new();
+/// let handles = interner.intern_slice(&tiles);
+/// assert_eq!(interner.len(), 2);
+///
+/// let mut table = Table::default();
+/// let runs: Vec<_> = table.encode_mut(&handles).collect();
+/// assert_eq!(runs, [(0, 3), (1, 2)]);
+/// assert!(table.decode(&runs).map(|tile| tile.solid).eq([false, false, false, true, true]));
+/// ```
+#[derive(Debug)]
+pub struct Interner<T> {
+    lookup: BTreeMap<Rc<T>, usize>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            lookup: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Ord> Interner<T> {
+    /// Constructs a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of unique values that have been interned.
+    pub fn len(&self) -> usize {
+        self.lookup.len()
+    }
+
+    /// Returns `true` if no values have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.lookup.is_empty()
+    }
+
+    /// Returns the handle to the value, interning it first if it is new.
+    pub fn intern(&mut self, value: T) -> Interned<T> {
+        if let Some(handle) = self.get(&value) {
+            return handle;
+        }
+        let id = self.lookup.len();
+        let value = Rc::new(value);
+        self.lookup.insert(Rc::clone(&value), id);
+        Interned { id, value }
+    }
+
+    /// Returns the handle to the value, or `None` if it has not been interned.
+    pub fn get(&self, value: &T) -> Option<Interned<T>> {
+        self.lookup
+            .get_key_value(value)
+            .map(|(value, &id)| Interned {
+                id,
+                value: Rc::clone(value),
+            })
+    }
+
+    /// Interns every value of the slice, returning their handles in order.
+    /// Each value is only looked up when it differs from the one before it,
+    /// so runs of equal values only cost one lookup.
+    pub fn intern_slice(&mut self, values: &[T]) -> Vec<Interned<T>>
+    where
+        T: Clone,
+    {
+        let mut handles: Vec<Interned<T>> = Vec::with_capacity(values.len());
+        for (i, value) in values.iter().enumerate() {
+            let handle = match handles.last() {
+                Some(prev) if values[i - 1] == *value => prev.clone(),
+                _ => match self.get(value) {
+                    Some(handle) => handle,
+                    None => self.intern(value.clone()),
+                },
+            };
+            handles.push(handle);
+        }
+        handles
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{EncodeError, Error, Index, SortedStore, Table, TableStore};
+use std::borrow::Borrow;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+/// Reads the next run from an iterator of items, using `peeked` to hold the
+/// first item of the following run. Returns the first item, the length of the
+/// run, and the position of the run in the sequence.
+fn next_run<T, I>(
+    iter: &mut I,
+    peeked: &mut Option<I::Item>,
+    position: &mut usize,
+) -> Option<(I::Item, usize, usize)>
+where
+    T: PartialEq,
+    I: Iterator,
+    I::Item: Borrow<T>,
+{
+    let first = peeked.take().or_else(|| iter.next())?;
+    let mut len = 1;
+    for item in iter.by_ref() {
+        if item.borrow() == first.borrow() {
+            len += 1;
+        } else {
+            *peeked = Some(item);
+            break;
+        }
+    }
+    let start = *position;
+    *position += len;
+    Some((first, len, start))
+}
+
+/// An iterator that run-length encodes the items of another iterator into a
+/// series of runs. See [encode_iter](crate::Table::encode_iter).
+pub struct IterEncoder<'a, T, I, S = SortedStore<T>>
+where
+    I: Iterator,
+{
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) iter: I,
+    pub(crate) peeked: Option<I::Item>,
+    pub(crate) position: usize,
+}
+
+impl<'a, T, I, S> Iterator for IterEncoder<'a, T, I, S>
+where
+    T: PartialEq + Clone,
+    I: Iterator,
+    I::Item: Borrow<T>,
+    S: TableStore<T>,
+{
+    type Item = Result<(Index, usize), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, len, start) = next_run(&mut self.iter, &mut self.peeked, &mut self.position)?;
+        Some(
+            self.table
+                .get_index(item.borrow())
+                .map(|ind| (ind, len))
+                .ok_or(Error::TableMissingItems(start)),
+        )
+    }
+}
+
+/// An iterator that run-length encodes the items of another iterator into a
+/// series of runs, and also adds elements to the table as it encounters them.
+/// See [encode_iter_mut](crate::Table::encode_iter_mut).
+pub struct IterEncoderMut<'a, T, I, S = SortedStore<T>>
+where
+    I: Iterator,
+{
+    pub(crate) table: &'a mut Table<T, S>,
+    pub(crate) iter: I,
+    pub(crate) peeked: Option<I::Item>,
+    pub(crate) position: usize,
+    pub(crate) marker: PhantomData<T>,
+}
+
+impl<'a, T, I, S> Iterator for IterEncoderMut<'a, T, I, S>
+where
+    T: PartialEq + Clone,
+    I: Iterator,
+    I::Item: Borrow<T>,
+    S: TableStore<T>,
+{
+    type Item = (Index, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, len, _) = next_run(&mut self.iter, &mut self.peeked, &mut self.position)?;
+        Some((self.table.insert_or_get(item.borrow()), len))
+    }
+}
+
+/// An iterator that run-length encodes the items of a fallible source into a
+/// series of runs, stopping at the source's first error. See [encode_try_iter](crate::Table::encode_try_iter).
+pub struct TryIterEncoder<'a, T, I, B, E, S = SortedStore<T>>
+where
+    I: Iterator<Item = Result<B, E>>,
+{
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) iter: I,
+    pub(crate) peeked: Option<B>,
+    pub(crate) error: Option<E>,
+    pub(crate) done: bool,
+    pub(crate) position: usize,
+}
+
+impl<'a, T, I, B, E, S> Iterator for TryIterEncoder<'a, T, I, B, E, S>
+where
+    T: PartialEq + Clone,
+    I: Iterator<Item = Result<B, E>>,
+    B: Borrow<T>,
+    S: TableStore<T>,
+{
+    type Item = Result<(Index, usize), EncodeError<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Some(err) = self.error.take() {
+            self.done = true;
+            return Some(Err(EncodeError::Source(err)));
+        }
+        let first = match self.peeked.take().map(Ok).or_else(|| self.iter.next()) {
+            Some(Ok(first)) => first,
+            Some(Err(err)) => {
+                self.done = true;
+                return Some(Err(EncodeError::Source(err)));
+            }
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+
+        // If the source fails partway through a run, the run is yielded
+        // first, and the error is held until the next call
+        let mut len = 1;
+        for item in self.iter.by_ref() {
+            match item {
+                Ok(item) if item.borrow() == first.borrow() => len += 1,
+                Ok(item) => {
+                    self.peeked = Some(item);
+                    break;
+                }
+                Err(err) => {
+                    self.error = Some(err);
+                    break;
+                }
+            }
+        }
+        let start = self.position;
+        self.position += len;
+        Some(
+            self.table
+                .get_index(first.borrow())
+                .map(|ind| (ind, len))
+                .ok_or(EncodeError::Encode(Error::TableMissingItems(start))),
+        )
+    }
+}
+
+impl<'a, T, I, B, E, S> FusedIterator for TryIterEncoder<'a, T, I, B, E, S>
+where
+    T: PartialEq + Clone,
+    I: Iterator<Item = Result<B, E>>,
+    B: Borrow<T>,
+    S: TableStore<T>,
+{
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Returns an iterator to run-length encode the items of any iterator
+    /// (of either `T` or `&T`), using this table as a lookup.
+    ///
+    /// Unlike [encode](Table::encode), the items don't need to be collected
+    /// into a slice first. Since the items can't be checked in advance, each
+    /// run is a `Result`, and a run of items not found in the table will be a
+    /// [TableMissingItems](Error::TableMissingItems) error.
+    pub fn encode_iter<I>(&self, items: I) -> IterEncoder<'_, T, I::IntoIter, S>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        IterEncoder {
+            table: self,
+            iter: items.into_iter(),
+            peeked: None,
+            position: 0,
+        }
+    }
+
+    /// Returns an iterator to run-length encode the items of a fallible source,
+    /// such as a parser, without collecting and checking them first.
+    ///
+    /// Like [encode_iter](Table::encode_iter), each run is a `Result`, and a run
+    /// of items not found in the table is an [Encode](EncodeError::Encode) error.
+    /// If the source fails, the run it interrupted is yielded, followed by a
+    /// [Source](EncodeError::Source) error, after which the iterator ends.
+    ///
+    /// ```
+    /// # use rle::{EncodeError, Table};
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let source = "AAB!A".chars().map(|c| match c {
+    ///     '!' => Err("unexpected '!'"),
+    ///     c => Ok(c),
+    /// });
+    /// let mut runs = table.encode_try_iter(source);
+    /// assert_eq!(runs.next().unwrap().unwrap(), (0, 2));
+    /// assert_eq!(runs.next().unwrap().unwrap(), (1, 1));
+    /// assert!(matches!(runs.next(), Some(Err(EncodeError::Source(_)))));
+    /// assert!(runs.next().is_none());
+    /// ```
+    pub fn encode_try_iter<I, B, E>(&self, items: I) -> TryIterEncoder<'_, T, I::IntoIter, B, E, S>
+    where
+        I: IntoIterator<Item = Result<B, E>>,
+        B: Borrow<T>,
+    {
+        TryIterEncoder {
+            table: self,
+            iter: items.into_iter(),
+            peeked: None,
+            error: None,
+            done: false,
+            position: 0,
+        }
+    }
+
+    /// Returns an iterator to run-length encode the items of any iterator
+    /// (of either `T` or `&T`), adding items to the table as they are found.
+    /// See [encode_mut](Table::encode_mut).
+    pub fn encode_iter_mut<I>(&mut self, items: I) -> IterEncoderMut<'_, T, I::IntoIter, S>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        IterEncoderMut {
+            table: self,
+            iter: items.into_iter(),
+            peeked: None,
+            position: 0,
+            marker: PhantomData,
+        }
+    }
+}
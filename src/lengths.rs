@@ -0,0 +1,66 @@
+use crate::{Error, Index};
+use std::convert::TryFrom;
+
+/// An unsigned integer type that can hold the length of a run.
+///
+/// Encoding with a type smaller than `usize` (see [encode_as](crate::Table::encode_as))
+/// can make large buffers of runs much smaller, at the cost of splitting runs
+/// that are longer than the type can hold.
+pub trait RunLen: Copy {
+    /// The longest run that can be stored in this type.
+    const MAX: usize;
+
+    /// Converts the length, or returns `None` if it is longer than [MAX](RunLen::MAX).
+    fn from_usize(len: usize) -> Option<Self>;
+
+    /// Converts the length back to a `usize`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_run_len {
+    ($($t:ty),*) => {
+        $(
+            impl RunLen for $t {
+                const MAX: usize = if (<$t>::MAX as u128) < (usize::MAX as u128) {
+                    <$t>::MAX as usize
+                } else {
+                    usize::MAX
+                };
+
+                fn from_usize(len: usize) -> Option<Self> {
+                    Self::try_from(len).ok()
+                }
+
+                fn to_usize(self) -> usize {
+                    usize::try_from(self).unwrap_or(usize::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_run_len!(u8, u16, u32, u64, usize);
+
+/// Converts the lengths of the runs to a smaller type.
+///
+/// # Errors
+///
+/// Returns a [RunTooLong](Error::RunTooLong) error if a run is too long to
+/// fit in `L`.
+///
+/// ```
+/// # use rle::convert_runs;
+/// let runs: Vec<(usize, u8)> = convert_runs(&[(0, 3), (1, 200)]).unwrap();
+/// assert_eq!(runs, [(0, 3), (1, 200)]);
+/// assert!(convert_runs::<u8>(&[(0, 3), (1, 300)]).is_err());
+/// ```
+pub fn convert_runs<L: RunLen>(runs: &[(Index, usize)]) -> Result<Vec<(Index, L)>, Error> {
+    runs.iter()
+        .enumerate()
+        .map(|(i, &(ind, len))| {
+            L::from_usize(len)
+                .map(|len| (ind, len))
+                .ok_or(Error::RunTooLong(i, len))
+        })
+        .collect()
+}
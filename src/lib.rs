@@ -3,6 +3,8 @@
 //!
 //! [Run-length encoding on Wikipedia](https://en.wikipedia.org/wiki/Run-length_encoding)
 //!
+//! The minimum supported Rust version is 1.87.
+//!
 //! # Usage
 //!
 //! First, you create a [Table](crate::Table), which serves as a lookup table for
@@ -32,6 +34,18 @@
 //! // 5A 10B 3C 10A
 //! ```
 //!
+//! The same output can be produced with [display_runs](crate::Table::display_runs)...
+//!
+//! ```
+//! # use rle::Table;
+//! # let mut table = Table::default();
+//! # table.extend_from_slice(&['A', 'B', 'C']);
+//! # let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+//! #
+//! let runs: Vec<_> = table.encode(&str).unwrap().collect();
+//! assert_eq!(table.display_runs(&runs).to_string(), "5A 10B 3C 10A");
+//! ```
+//!
 //! Rather than a series of runs, you can also encode the values into
 //! a run-length encoded byte sequence. Here, we are also using [encode_bytes_mut](crate::Table::encode_bytes_mut),
 //! instead of [encode_bytes](crate::Table::encode_bytes), which will build the table as it encodes the sequence.
@@ -149,25 +163,196 @@
 //! // Number of chars in decoded string .... 4160
 //! ```
 
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod auto;
+#[cfg(feature = "base64")]
+mod base64;
+pub mod bitmap;
+mod bits;
+mod bitset;
+mod bmp;
+mod bounded;
+mod bulk;
+mod byte_scan;
+pub mod bytes;
 mod bytes_decoder;
+mod bytes_decoder_mut;
 mod bytes_encoder;
 mod bytes_encoder_mut;
+mod checked;
+mod chunk;
+mod chunks;
+mod compact;
+mod compare;
+mod concat;
+mod const_table;
+mod container;
+mod copied;
+mod crc;
 mod decoder;
+mod decoder_state;
+mod display_runs;
+mod encode_by;
+mod encode_options;
 mod encoder;
 mod encoder_mut;
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod framed;
+mod frozen;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod grid;
+mod hash_store;
+mod hex_str;
+#[cfg(feature = "image")]
+mod images;
+mod inline_bytes_encoder;
+mod interned;
+mod iter_encoder;
+mod lengths;
+mod lines;
+mod literal;
+mod metrics;
+mod migrate;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod optimize;
+mod owned;
+mod packbits;
+mod parse_runs;
+mod patch;
+mod persistent;
+pub mod planar;
+mod probe;
+mod quadtree;
+mod reader_encoder;
+mod remap;
+#[cfg(feature = "serde")]
+mod rle_seq;
+mod rle_vec;
+mod run;
+mod run_decoder;
+mod run_length;
+mod runs;
+mod scanlines;
+mod search;
+mod seekable;
+mod session;
+mod slice;
+mod split;
+mod stats;
+mod store;
+pub mod str;
+mod streaming;
+#[cfg(feature = "strings")]
+mod string_store;
 mod table;
+mod table_item;
+mod tga;
+mod total_float;
+mod transaction;
+mod validate;
+mod varint;
+mod visit;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod wide;
+mod zip;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 pub type Index = usize;
 
-pub use bytes_decoder::BytesDecoder;
+#[cfg(feature = "rkyv")]
+pub use archive::{ArchivedTable, TableResolver};
+#[cfg(feature = "arrow")]
+pub use arrow::ArrowItem;
+pub use auto::AutoBytesDecoder;
+pub use bitset::RleBitSet;
+pub use bmp::{decode_bmp_rle4, decode_bmp_rle8, encode_bmp_rle4, encode_bmp_rle8};
+pub use bounded::{BoundedBytesDecoder, BoundedBytesEncoder, BoundedTable, EvictionPolicy};
+pub use bytes_decoder::{BytesDecoder, EnumeratedDecoder};
+pub use bytes_decoder_mut::BytesDecoderMut;
 pub use bytes_encoder::BytesEncoder;
 pub use bytes_encoder_mut::BytesEncoderMut;
+pub use checked::{CheckedBytesDecoder, CheckedDecoder};
+pub use chunks::ChunkDecoder;
+pub use compact::{CompactBytesDecoder, CompactBytesEncoder};
+pub use compare::{bytes_eq, runs_eq};
+pub use concat::{append_bytes, append_runs};
+pub use const_table::ConstTable;
+pub use container::TABLE_FORMAT_VERSION;
 pub use decoder::Decoder;
-pub use encoder::Encoder;
+pub use decoder_state::DecoderState;
+pub use display_runs::DisplayRuns;
+pub use encode_options::{EncodeOptions, RunHeader};
+pub use encoder::{Encoder, OffsetEncoder, RunEncoder, TryEncoder};
 pub use encoder_mut::EncoderMut;
-pub use error::Error;
+pub use error::{EncodeError, Error};
+pub use framed::{framed_format, ByteFormat, BYTE_FORMAT_VERSION};
+pub use frozen::FrozenTable;
+#[cfg(feature = "arbitrary")]
+pub use fuzz::{arbitrary_bytes, EncodedBytes};
+pub use grid::{GridLayout, ScanOrder};
+pub use hash_store::{HashStore, HashTable};
+pub use hex_str::HexStrDecoder;
+pub use inline_bytes_encoder::InlineBytesEncoder;
+pub use interned::{Interned, Interner};
+pub use iter_encoder::{IterEncoder, IterEncoderMut, TryIterEncoder};
+pub use lengths::{convert_runs, RunLen};
+pub use lines::Lines;
+pub use literal::{LiteralBytesDecoder, LiteralBytesEncoder};
+pub use metrics::Metrics;
+pub use migrate::{migrate, Format};
+#[cfg(feature = "mmap")]
+pub use mmap::MappedBytes;
+pub use owned::{BytesDecoderOwned, DecoderOwned};
+pub use packbits::{decode_packbits, encode_packbits};
+pub use patch::{diff, Patch, PatchEdit};
+pub use persistent::{PersistentRle, PersistentRuns};
+pub use probe::AlphabetProbe;
+pub use quadtree::{QuadNode, Quadtree};
+pub use reader_encoder::ReaderEncoder;
+pub use remap::{remap_bytes, remap_runs};
+#[cfg(feature = "serde")]
+pub use rle_seq::RleSeq;
+pub use rle_vec::RleVec;
+#[cfg(feature = "rkyv")]
+pub use run::{ArchivedRun, RunResolver};
+pub use run::{IntoRun, Run};
+pub use run_decoder::{BytesRunDecoder, RunDecoder};
+pub use run_length::{runs_of, runs_of_slice, ByteRuns, RunLength, RunLengthIter, SliceRuns};
+pub use runs::{normalize_runs, normalized, Coverage, NormalizedRuns, Runs};
+pub use scanlines::{ScanlineDecoder, Scanlines};
+pub use search::{find_run_at, find_symbol};
+pub use seekable::chunked_len;
+pub use session::EncodeSession;
+pub use slice::{slice_bytes, slice_runs};
+pub use split::SplitDecoder;
+pub use stats::RleStats;
+pub use store::{SortedStore, StoreLookup, TableStore};
+pub use streaming::{StreamingDecoder, StreamingEncoder, StreamingItems};
+#[cfg(feature = "strings")]
+pub use string_store::StringStore;
 pub use table::Table;
+pub use table_item::TableItem;
+pub use tga::{decode_tga, encode_tga};
+pub use total_float::{TotalF32, TotalF64};
+pub use transaction::TableTransaction;
+pub use validate::DecodeSummary;
+pub use visit::Control;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmTable;
+pub use wide::{WideBytesDecoder, WideBytesEncoder, Width};
+pub use zip::{zip_decode, zip_runs, ZipDecoder, ZipLayers, ZipRuns};
 
 #[cfg(test)]
 mod tests {
@@ -190,6 +375,35 @@ mod tests {
         println!("HEX: {}", str);
     }
 
+    #[test]
+    fn decode_hex_str() {
+        let str: Vec<char> = "GGGGJJJJEEEEIIIIIIIAAAACCCCCCCCAAABBBBXXXXXRRRRRRRRR"
+            .chars()
+            .collect();
+        let table = Table::from_slice(&str);
+        let hex = table.encode_hex_str(&str).unwrap();
+
+        assert_eq!(table.decode_hex_str(&hex).unwrap(), str);
+        assert_eq!(table.decode_hex_str("").unwrap(), Vec::<char>::new());
+        assert_eq!(
+            table.decode_hex_str("1:2,0:0,0:1").unwrap(),
+            ['J', 'J', 'G']
+        );
+        assert!(matches!(
+            table.decode_hex_str("1:2,0;1,"),
+            Err(Error::InvalidHex(4))
+        ));
+        assert!(matches!(
+            table.decode_hex_str("1:2,FF:1,"),
+            Err(Error::UnknownIndex(255))
+        ));
+        let mut iter = table.decode_hex_str_iter("1:2,X");
+        assert_eq!(iter.next().unwrap().unwrap(), &'J');
+        assert_eq!(iter.next().unwrap().unwrap(), &'J');
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
     #[test]
     fn sorting() {
         let str: Vec<char> = "EEEEAAACCCCCCCBBBBBBDD".chars().collect();
@@ -300,4 +514,3885 @@ mod tests {
         println!("Time to encode ....................... {} μs", encode_time);
         println!("Time to decode ....................... {} μs", decode_time);
     }
+
+    #[test]
+    fn lines() {
+        let input = "....XX....\n...XXXX...\n\n..XXXXXX..\n";
+
+        let mut table = Table::default();
+        let lines = table.encode_lines(input).unwrap();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(table.decode_lines(&lines), input);
+
+        let row: String = table.decode_line(&lines, 1).unwrap().collect();
+        assert_eq!(row, "...XXXX...");
+        assert_eq!(table.decode_line(&lines, 2).unwrap().count(), 0);
+        assert!(table.decode_line(&lines, 5).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn lines_serde() {
+        let mut table = Table::default();
+        let lines = table.encode_lines("AAB\nBB").unwrap();
+        let json = serde_json::to_string(&lines).unwrap();
+        let loaded: Lines = serde_json::from_str(&json).unwrap();
+        assert_eq!(table.decode_lines(&loaded), "AAB\nBB");
+
+        let backwards = r#"{"bytes":[1,2,2,3,2],"ends":[3,1]}"#;
+        assert!(serde_json::from_str::<Lines>(backwards).is_err());
+        let past_end = r#"{"bytes":[1,2,2],"ends":[3,9]}"#;
+        assert!(serde_json::from_str::<Lines>(past_end).is_err());
+    }
+
+    #[test]
+    fn scanlines() {
+        let input: Vec<char> = "AAAABBBB\
+                                BBBBBBBB\
+                                CCAACCAA"
+            .chars()
+            .collect();
+
+        let mut table = Table::default();
+        let bytes = table.encode_scanlines_mut(&input, 8).unwrap();
+        assert_eq!(Scanlines::new(&bytes).count(), 3);
+        assert_eq!(&bytes[..2], &[4, 0]);
+
+        let row: String = table.decode_scanline(&bytes, 1).unwrap().collect();
+        assert_eq!(row, "BBBBBBBB");
+        assert!(table.decode_scanline(&bytes, 3).is_none());
+
+        let decoded: Vec<char> = table.decode_scanlines(&bytes).copied().collect();
+        assert_eq!(decoded, input);
+        assert_eq!(table.encode_scanlines(&input, 8).unwrap(), bytes);
+    }
+
+    #[test]
+    fn resume_decoding() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut table = Table::default();
+        let bytes: Vec<u8> = table.encode_bytes_mut(&input).unwrap().flatten().collect();
+
+        let mut decoder = table.decode_bytes(&bytes);
+        let first: String = decoder.by_ref().take(7).collect();
+        let state = decoder.state();
+        assert_eq!(state.element_offset, 7);
+        assert_eq!(state.byte_offset, 4);
+        assert_eq!(state.run, Some((1, 8)));
+
+        let rest: String = table.resume_bytes(&bytes, state).collect();
+        assert_eq!(first + &rest, input.iter().collect::<String>());
+    }
+
+    #[test]
+    fn inline_bytes() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut table = Table::default();
+        let bytes: Vec<u8> = table
+            .encode_bytes_inline(&input)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(&bytes[..6], &[1, 5, b'A', 0, 0, 0]);
+
+        let mut decoded_table = Table::default();
+        let decoded: Vec<char> = decoded_table.decode_bytes_mut(&bytes).collect();
+        assert_eq!(decoded, input);
+        assert_eq!(decoded_table.as_ref(), table.as_ref());
+    }
+
+    #[test]
+    fn literal_bytes() {
+        let input: Vec<char> = "AAAAABBBZZAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B']);
+        assert!(table.encode_bytes(&input).is_err());
+
+        let bytes: Vec<u8> = table.encode_bytes_literal(&input).unwrap().collect();
+        assert_eq!(&bytes[4..10], &[255, 2, b'Z', 0, 0, 0]);
+
+        let decoded: Vec<char> = table.decode_bytes_literal(&bytes).collect();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn bounded_table() {
+        let chunks = ["AAAABBBCCCC", "DDDDAAAAEEEE", "BBBBCCCCFFFFAAAA"];
+        for &policy in &[EvictionPolicy::Lru, EvictionPolicy::Reset] {
+            let mut encoder = BoundedTable::new(3, policy);
+            let mut decoder: BoundedTable<char> = BoundedTable::new(3, policy);
+            for chunk in chunks.iter() {
+                let chars: Vec<char> = chunk.chars().collect();
+                let bytes: Vec<u8> = encoder.encode_bytes(&chars).collect();
+                let decoded: String = decoder.decode_bytes(&bytes).collect();
+                assert_eq!(&decoded, chunk);
+                assert!(encoder.table().len() <= 3);
+                assert_eq!(encoder.table().as_ref(), decoder.table().as_ref());
+            }
+        }
+    }
+
+    #[test]
+    fn alphabet_probe() {
+        let probe = AlphabetProbe::probe("AAAABBBCCCCCCAAAAAAA".chars());
+        assert!(probe.is_exact());
+        assert_eq!(probe.distinct(), 3);
+        assert_eq!(probe.total(), 20);
+        assert_eq!(probe.most_common()[0], (&'A', 11));
+        assert!(probe.fits_in_bytes());
+
+        let mut probe = AlphabetProbe::with_limit(16);
+        probe.extend((0..100_000u32).map(|i| i % 10_000));
+        assert!(!probe.is_exact());
+        let distinct = probe.distinct();
+        assert!(distinct > 8_000 && distinct < 12_000, "{}", distinct);
+        assert!(!probe.fits_in_bytes());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn table_json() {
+        let table = Table::from_slice(&['X', '.', 'O']);
+        let json = table.to_json().unwrap();
+        let loaded = Table::<char>::from_json(&json).unwrap();
+        assert_eq!(loaded.as_ref(), &['X', '.', 'O']);
+        assert_eq!(loaded.iter_sorted().collect::<String>(), ".OX");
+
+        assert!(Table::<char>::from_json(r#"["A", "B", "A"]"#).is_err());
+    }
+
+    #[test]
+    fn migrate_formats() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut table = Table::default();
+        let inline: Vec<u8> = table
+            .encode_bytes_inline(&input)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let mut migrated = Table::<char>::default();
+        let bytes = migrate(Format::Inline, Format::Bytes, &mut migrated, &inline).unwrap();
+        assert_eq!(migrated.as_ref(), table.as_ref());
+        assert_eq!(
+            table.decode_bytes(&bytes).copied().collect::<Vec<_>>(),
+            input
+        );
+
+        let literal = migrate(Format::Bytes, Format::Literal, &mut table, &bytes).unwrap();
+        assert_eq!(literal, bytes);
+
+        let inline_again = migrate(Format::Literal, Format::Inline, &mut table, &literal).unwrap();
+        assert_eq!(inline_again, inline);
+
+        assert!(migrate(Format::Bytes, Format::Inline, &mut table, &[9]).is_err());
+    }
+
+    #[test]
+    fn generated_roundtrips() {
+        use crate::test_support::*;
+
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        assert_roundtrip(&table, &input);
+        assert_roundtrip(&table, &[]);
+
+        // The same seed always generates the same values
+        let (mut a, mut b) = (Generator::new(42), Generator::new(42));
+        assert_eq!(a.next_u64(), b.next_u64());
+        let (table, items) = a.pair(3, 12, RunLengths::Fixed(3));
+        assert_eq!(table.len(), 3);
+        assert!(items.chunks(3).all(|run| run.iter().all(|&x| x == run[0])));
+    }
+
+    #[test]
+    fn metrics() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&input);
+        let metrics = Metrics::new();
+
+        let bytes: Vec<u8> = table
+            .encode_bytes(&input)
+            .unwrap()
+            .metrics(&metrics)
+            .collect();
+        assert_eq!(metrics.encoded_items(), 28);
+        assert_eq!(metrics.encoded_runs(), 4);
+        assert_eq!(metrics.encoded_bytes(), 8);
+
+        let decoded = table.decode_bytes(&bytes).metrics(&metrics).count();
+        assert_eq!(decoded, 28);
+        assert_eq!(metrics.decoded_runs(), 4);
+        assert_eq!(metrics.decoded_bytes(), 8);
+        assert_eq!(metrics.decoded_items(), 28);
+
+        metrics.reset();
+        assert_eq!(metrics.encoded_items(), 0);
+    }
+
+    #[test]
+    fn output_budget() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&input);
+
+        let (bytes, consumed) = table.encode_bytes_bounded(&input, 100).unwrap();
+        assert_eq!(consumed, input.len());
+        assert_eq!(
+            bytes,
+            table.encode_bytes(&input).unwrap().collect::<Vec<u8>>()
+        );
+
+        let (bytes, consumed) = table.encode_bytes_bounded(&input, 5).unwrap();
+        assert_eq!(bytes.len(), 5);
+        assert_eq!(consumed, 16);
+        let decoded: Vec<char> = table.decode_bytes(&bytes).copied().collect();
+        assert_eq!(decoded, &input[..consumed]);
+    }
+
+    #[test]
+    fn auto_bytes() {
+        let mut table = Table::default();
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let bytes = table.encode_bytes_auto(&input);
+        assert_eq!(bytes[0], 0);
+        assert_eq!(table.len(), 3);
+
+        let mut decoded = Table::default();
+        let output: Vec<char> = decoded.decode_bytes_auto(&bytes).collect();
+        assert_eq!(output, input);
+
+        let mut table = Table::default();
+        let input: Vec<char> = "ABCDEFGH".chars().collect();
+        let bytes = table.encode_bytes_auto(&input);
+        assert_eq!(bytes[0], 1);
+        assert_eq!(bytes.len(), input.len() * 4 + 1);
+        assert!(table.is_empty());
+
+        let output: Vec<char> = table.decode_bytes_auto(&bytes).collect();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn split_streams() {
+        let mut input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        input.extend(std::iter::repeat_n('B', 300));
+        let table = Table::from_slice(&['A', 'B', 'C']);
+
+        let (indices, lengths) = table.encode_split(&input).unwrap();
+        assert_eq!(indices, [0, 1, 2, 0, 1, 1]);
+        assert_eq!(lengths, [5, 10, 3, 10, 255, 45]);
+
+        let decoded: Vec<char> = table.decode_split(&indices, &lengths).copied().collect();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn quadtree() {
+        let input: Vec<char> = "AAAABB\
+                                AAAABB\
+                                AAAACC\
+                                AAAACC\
+                                BBBBBB"
+            .chars()
+            .collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let bytes = table.encode_scanlines(&input, 6).unwrap();
+
+        let tree = Quadtree::from_scanlines(&table, &bytes, 6).unwrap();
+        assert_eq!((tree.width(), tree.height()), (6, 5));
+        assert_eq!(tree.get(5, 2), Some(2));
+        assert_eq!(tree.get(6, 0), None);
+        assert_eq!(tree.to_scanlines(&table).unwrap(), bytes);
+        assert_eq!(tree.to_bytes().unwrap().len(), tree.encoded_size());
+        assert_eq!(tree.compare_size(&bytes), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn quadtree_bytes() {
+        let indices = [0, 0, 1, 1, 0, 0, 1, 2, 2, 2, 2, 2];
+        let tree = Quadtree::from_indices(&indices, 4);
+        let bytes = tree.to_bytes().unwrap();
+        assert_eq!(&bytes[..8], &[4, 0, 0, 0, 3, 0, 0, 0]);
+        let loaded = Quadtree::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded, tree);
+        assert_eq!(loaded.to_indices(), indices);
+
+        assert!(matches!(
+            Quadtree::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::TruncatedStream)
+        ));
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(matches!(
+            Quadtree::from_bytes(&extra),
+            Err(Error::InvalidBytes(offset)) if offset == bytes.len()
+        ));
+        assert!(matches!(
+            Quadtree::from_bytes(&[0, 0, 0, 0, 1, 0, 0, 0, 0]),
+            Err(Error::InvalidGridSize(_))
+        ));
+        // A single cell can't be split any further
+        assert!(matches!(
+            Quadtree::from_bytes(&[1, 0, 0, 0, 1, 0, 0, 0, 255]),
+            Err(Error::InvalidBytes(8))
+        ));
+    }
+
+    #[test]
+    fn runs_len() {
+        let runs = [(0, 5), (1, 10), (0, 3)];
+        assert_eq!(runs.total_len(), 18);
+        assert_eq!(runs[..0].total_len(), 0);
+
+        let table = Table::from_slice(&['~', '#', '.']);
+        let coverage = runs.coverage(&table);
+        assert_eq!(coverage.len(), 3);
+        assert_eq!((coverage[0].item, coverage[0].count), (&'~', 8));
+        assert_eq!(coverage[1].count, 10);
+        assert_eq!(coverage[2].count, 0);
+        assert!((coverage[0].percent - 44.444).abs() < 0.001);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn runs_sample() {
+        use rand::SeedableRng;
+
+        let table = Table::from_slice(&['A', 'B']);
+        let runs = [(0, 1), (1, 999)];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let bs = (0..100)
+            .filter(|_| runs.sample(&table, &mut rng) == Some(&'B'))
+            .count();
+        assert!(bs > 90);
+
+        let all = runs.choose_multiple(&table, &mut rng, 5000);
+        assert_eq!(all.len(), 1000);
+        assert_eq!(all[0], &'A');
+        assert!(all[1..].iter().all(|&&c| c == 'B'));
+    }
+
+    #[test]
+    fn visit_runs() {
+        let mut input: Vec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+        input.extend(std::iter::repeat_n('A', 200));
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let bytes: Vec<u8> = table.encode_bytes(&input).unwrap().collect();
+
+        let mut runs = Vec::new();
+        let read = table.decode_bytes_visit(&bytes, |&item, len| {
+            runs.push((item, len));
+            Control::Continue
+        });
+        assert_eq!(read, bytes.len());
+        assert_eq!(runs, [('A', 5), ('B', 10), ('C', 3), ('A', 200)]);
+
+        let mut runs = 0;
+        let read = table.decode_bytes_visit(&bytes, |_, _| {
+            runs += 1;
+            if runs == 2 {
+                Control::Break
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!((runs, read), (2, 4));
+    }
+
+    #[test]
+    fn chunks() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let bytes: Vec<u8> = table.encode_bytes(&input).unwrap().collect();
+
+        let chunks: Vec<String> = table
+            .decode_chunks(&bytes, 8)
+            .map(|chunk| chunk.into_iter().collect())
+            .collect();
+        assert_eq!(chunks, ["AAAAABBB", "BBBBBBBC", "CCAAAAAA", "AAAA"]);
+
+        let mut decoder = table.decode_chunks(&bytes, 8);
+        let mut buf = ['-'; 12];
+        assert_eq!(decoder.fill(&mut buf), 12);
+        assert_eq!(decoder.fill(&mut buf), 12);
+        assert_eq!(decoder.fill(&mut buf), 4);
+        assert_eq!(&buf[..4], &['A'; 4]);
+    }
+
+    #[test]
+    fn enumerated() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let bytes: Vec<u8> = table.encode_bytes(&input).unwrap().collect();
+
+        let mut decoder = table.decode_enumerated(&bytes);
+        assert_eq!(decoder.next(), Some((0, &'A')));
+        assert_eq!(decoder.nth(15), Some((16, &'C')));
+        assert_eq!(decoder.next(), Some((17, &'C')));
+        assert_eq!(decoder.last(), Some((27, &'A')));
+
+        let positions: Vec<usize> = table
+            .decode_enumerated(&bytes)
+            .skip(3)
+            .step_by(10)
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(positions, [3, 13, 23]);
+    }
+
+    #[test]
+    fn enumerated_nth_checks_skipped_runs() {
+        let table = Table::from_slice(&['A', 'B']);
+        let metrics = Metrics::new();
+
+        // Index 2 is not in the table, so skipping over it ends decoding
+        let bytes = [0b001, 4, 0b101, 4, 0b011, 4];
+        let mut decoder = table.decode_bytes(&bytes);
+        assert_eq!(decoder.nth(6), None);
+        assert_eq!(decoder.next(), None);
+
+        let bytes = [0b001, 4, 0b011, 4];
+        let mut decoder = table.decode_bytes(&bytes).metrics(&metrics);
+        assert_eq!(decoder.nth(5), Some(&'B'));
+        assert_eq!(metrics.decoded_items(), 6);
+        assert_eq!(metrics.decoded_runs(), 2);
+    }
+
+    #[test]
+    fn custom_store() {
+        #[derive(Default)]
+        struct LinearStore(Vec<char>);
+
+        impl TableStore<char> for LinearStore {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+            fn get(&self, index: Index) -> Option<&char> {
+                self.0.get(index)
+            }
+            fn index_of(&self, item: &char) -> Option<Index> {
+                self.0.iter().position(|c| c == item)
+            }
+            fn insert_or_get(&mut self, item: &char) -> Index {
+                self.index_of(item).unwrap_or_else(|| {
+                    self.0.push(*item);
+                    self.0.len() - 1
+                })
+            }
+            fn clear(&mut self) {
+                self.0.clear();
+            }
+        }
+
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut table = Table::with_store(LinearStore::default());
+        let bytes: Vec<u8> = table
+            .encode_bytes_mut(&input)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(table.store().0, ['A', 'B', 'C']);
+
+        let decoded: Vec<char> = table.decode_bytes(&bytes).copied().collect();
+        assert_eq!(decoded, input);
+        assert_eq!(
+            table.encode_bytes(&input).unwrap().collect::<Vec<u8>>(),
+            bytes
+        );
+    }
+
+    #[cfg(feature = "strings")]
+    #[test]
+    fn string_store() {
+        let words: Vec<String> = "the cat the cat the the dog"
+            .split(' ')
+            .map(String::from)
+            .collect();
+        let mut table = Table::with_store(StringStore::with_capacity(4));
+        let runs: Vec<_> = table.encode_mut(&words).collect();
+        assert_eq!(runs, [(0, 1), (1, 1), (0, 1), (1, 1), (0, 2), (2, 1)]);
+        assert_eq!(table.store().as_slice(), ["the", "cat", "dog"]);
+
+        let decoded: Vec<&String> = table.decode(&runs).collect();
+        assert_eq!(decoded, words.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn encode_from_reader() {
+        let mut input = vec![b'.'; 20_000];
+        input.extend_from_slice(b"XX..X");
+        let table = Table::from_slice(b".X");
+
+        let bytes: Vec<u8> = table
+            .encode_bytes_from_reader(&input[..])
+            .unwrap()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            bytes,
+            table.encode_bytes(&input).unwrap().collect::<Vec<u8>>()
+        );
+
+        let mut encoder = table.encode_bytes_from_reader(&b"..Z"[..]).unwrap();
+        assert_eq!(encoder.next().unwrap().unwrap(), 1);
+        assert_eq!(encoder.next().unwrap().unwrap(), 2);
+        assert!(encoder.next().unwrap().is_err());
+        assert!(encoder.next().is_none());
+        assert!(encoder.next().is_none());
+
+        struct Failing;
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+        }
+        let mut encoder = table.encode_bytes_from_reader(Failing).unwrap();
+        assert!(encoder.next().unwrap().is_err());
+        assert!(encoder.next().is_none());
+    }
+
+    #[test]
+    fn encode_to_writer() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+
+        let mut out = Vec::new();
+        assert_eq!(table.encode_bytes_to(&input, &mut out).unwrap(), 8);
+        assert_eq!(
+            out,
+            table.encode_bytes(&input).unwrap().collect::<Vec<u8>>()
+        );
+
+        let err = table.encode_bytes_to(&['Z'], &mut out).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn encode_iterators() {
+        let str = "AAAAABBBBBBBBBBCCCAAAAAAAAAA";
+        let mut table = Table::default();
+        let runs: Vec<_> = table.encode_iter_mut(str.chars()).collect();
+        assert_eq!(runs, [(0, 5), (1, 10), (2, 3), (0, 10)]);
+
+        let chars: Vec<char> = str.chars().collect();
+        let by_ref: Vec<_> = table
+            .encode_iter(chars.iter())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(by_ref, runs);
+
+        let mut missing = table.encode_iter("AAZZ".chars());
+        assert_eq!(missing.next().unwrap().unwrap(), (0, 2));
+        assert!(matches!(
+            missing.next(),
+            Some(Err(Error::TableMissingItems(2)))
+        ));
+    }
+
+    #[test]
+    fn container() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['C', 'B', 'A']);
+        let bytes = table.encode_container(&input).unwrap();
+        assert_eq!(&bytes[..4], &[3, 0, 0, 0]);
+
+        let (decoded_table, decoded) = Table::<char>::decode_container(&bytes).unwrap();
+        assert_eq!(decoded_table.as_ref(), table.as_ref());
+        assert_eq!(decoded, input);
+
+        assert!(Table::<char>::decode_container(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn wide_indices() {
+        let table = Table::from_iter(0..1000u32);
+        let mut input: Vec<u32> = vec![999; 70_000];
+        input.extend_from_slice(&[5, 5, 300]);
+
+        assert!(table.encode_bytes(&input).is_err());
+        assert!(table.encode_bytes_wide(&input, Width::U8).is_err());
+
+        let bytes: Vec<u8> = table
+            .encode_bytes_wide(&input, Width::U16)
+            .unwrap()
+            .collect();
+        assert_eq!(&bytes[..4], &[0xCF, 0x07, 0xFF, 0xFF]);
+        assert_eq!(bytes.len(), 4 + 4 + 4 + 2);
+        let decoded: Vec<u32> = table
+            .decode_bytes_wide(&bytes, Width::U16)
+            .copied()
+            .collect();
+        assert_eq!(decoded, input);
+
+        let bytes: Vec<u8> = table
+            .encode_bytes_wide(&input, Width::U32)
+            .unwrap()
+            .collect();
+        let decoded: Vec<u32> = table
+            .decode_bytes_wide(&bytes, Width::U32)
+            .copied()
+            .collect();
+        assert_eq!(decoded, input);
+
+        let table = Table::from_slice(&['A', 'B']);
+        let input: Vec<char> = "AAAAABBBBBBBBBBAB".chars().collect();
+        assert_eq!(
+            table
+                .encode_bytes_wide(&input, Width::U8)
+                .unwrap()
+                .collect::<Vec<u8>>(),
+            table.encode_bytes(&input).unwrap().collect::<Vec<u8>>()
+        );
+    }
+
+    #[test]
+    fn checked_bytes() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let bytes: Vec<u8> = table.encode_bytes(&input).unwrap().collect();
+
+        let decoded: Vec<char> = table
+            .decode_bytes_checked(&bytes)
+            .map(|item| item.copied())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decoded, input);
+
+        let truncated = table.decode_bytes_checked(&bytes[..bytes.len() - 1]);
+        assert!(matches!(
+            truncated.last(),
+            Some(Err(Error::TruncatedStream))
+        ));
+
+        let mut unknown = table.decode_bytes_checked(&[0, 6, 2]);
+        assert_eq!(unknown.next().unwrap().unwrap(), &'A');
+        assert!(matches!(unknown.next(), Some(Err(Error::UnknownIndex(3)))));
+        assert!(unknown.next().is_none());
+    }
+
+    #[test]
+    fn try_decode() {
+        let table = Table::from_slice(&['A', 'B']);
+        let decoded: String = table
+            .try_decode(&[(0, 2), (1, 1)])
+            .map(|item| item.copied())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decoded, "AAB");
+
+        let mut decoder = table.try_decode(&[(0, 1), (1, 0), (5, 2), (0, 1)]);
+        assert_eq!(decoder.next().unwrap().unwrap(), &'A');
+        assert!(matches!(decoder.next(), Some(Err(Error::InvalidRun(2, 5)))));
+        assert!(decoder.next().is_none());
+    }
+
+    #[test]
+    fn zero_length_runs() {
+        let table = Table::from_slice(&['A', 'B']);
+        let runs = [(0, 2), (1, 0), (0, 0), (1, 1)];
+        assert_eq!(table.decode(&runs).collect::<String>(), "AAB");
+        assert_eq!(table.decode(&runs).rev().collect::<String>(), "BAA");
+        assert_eq!(
+            table
+                .try_decode(&runs)
+                .map(|item| item.copied())
+                .collect::<Result<String, _>>()
+                .unwrap(),
+            "AAB"
+        );
+        assert_eq!(
+            table.decode_runs(&runs).collect::<Vec<_>>(),
+            [(&'A', 2), (&'B', 1)]
+        );
+        let owned = DecoderOwned::new(std::sync::Arc::new(table.clone()), runs.to_vec());
+        assert_eq!(owned.collect::<String>(), "AAB");
+
+        // An empty run with an unknown index still ends decoding
+        let runs = [(0, 1), (7, 0), (1, 1)];
+        assert_eq!(table.decode(&runs).collect::<String>(), "A");
+        assert_eq!(table.decode(&runs).rev().collect::<String>(), "A");
+        let mut decoder = table.try_decode(&runs);
+        assert_eq!(decoder.next().unwrap().unwrap(), &'A');
+        assert!(matches!(decoder.next(), Some(Err(Error::InvalidRun(1, 7)))));
+        assert!(decoder.next().is_none());
+        assert_eq!(table.decode_runs(&runs).count(), 1);
+        let owned = DecoderOwned::new(std::sync::Arc::new(table.clone()), runs.to_vec());
+        assert_eq!(owned.collect::<String>(), "A");
+
+        // Bytes have no empty runs, and a length byte of 0 reads as 1
+        let mut decoder = table.decode_bytes_checked(&[0x01, 0x00, 0x06]);
+        assert_eq!(decoder.next().unwrap().unwrap(), &'A');
+        assert!(matches!(decoder.next(), Some(Err(Error::UnknownIndex(3)))));
+        assert!(decoder.next().is_none());
+    }
+
+    #[test]
+    fn rle_vec() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut vec = RleVec::from_slice(&input);
+        assert_eq!(vec.len(), 28);
+        assert_eq!(vec.runs(), &[(0, 5), (1, 10), (2, 3), (0, 10)]);
+        assert_eq!(vec.table().len(), 3);
+        for (i, c) in input.iter().enumerate() {
+            assert_eq!(vec.get(i), Some(c));
+        }
+        assert_eq!(vec.get(28), None);
+
+        vec.push('A');
+        vec.push('D');
+        assert_eq!(vec.runs().len(), 5);
+        assert_eq!(vec.last(), Some(&'D'));
+        assert_eq!(vec.to_vec().len(), 30);
+        assert_eq!((&vec).into_iter().filter(|&&c| c == 'A').count(), 16);
+    }
+
+    #[test]
+    fn rle_vec_editing() {
+        let mut expected: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let mut vec = RleVec::from_slice(&expected);
+
+        assert_eq!(vec.set(7, 'C'), 'B');
+        expected[7] = 'C';
+        assert_eq!(vec.to_vec(), expected);
+        assert_eq!(vec.runs().len(), 6);
+
+        assert_eq!(vec.set(7, 'B'), 'C');
+        expected[7] = 'B';
+        assert_eq!(vec.runs(), &[(0, 5), (1, 10), (2, 3), (0, 10)]);
+
+        vec.insert(0, 'A');
+        expected.insert(0, 'A');
+        vec.insert(vec.len(), 'D');
+        expected.push('D');
+        assert_eq!(vec.remove(6), 'B');
+        expected.remove(6);
+        assert_eq!(vec.to_vec(), expected);
+
+        let removed = vec.splice(5..18, "AAA".chars());
+        let expected_removed: Vec<char> = expected.splice(5..18, "AAA".chars()).collect();
+        assert_eq!(removed, expected_removed);
+        assert_eq!(vec.to_vec(), expected);
+        assert_eq!(vec.runs(), &[(0, 18), (3, 1)]);
+        for (i, c) in expected.iter().enumerate() {
+            assert_eq!(vec.get(i), Some(c));
+        }
+    }
+
+    #[test]
+    fn table_remove() {
+        let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAADD".chars().collect();
+        let mut table = Table::default();
+        let runs: Vec<_> = table.encode_mut(&str).collect();
+
+        assert_eq!(table.remove(&'X'), None);
+        let remap = table.remove(&'B').unwrap();
+        assert_eq!(remap, vec![Some(0), None, Some(1), Some(2)]);
+        assert_eq!(table.iter().copied().collect::<String>(), "ACD");
+        assert_eq!(table.get_index(&'D'), Some(2));
+
+        let runs: Vec<_> = runs
+            .iter()
+            .filter_map(|&(ind, len)| remap[ind].map(|ind| (ind, len)))
+            .collect();
+        let decoded: String = table.decode(&runs).collect();
+        assert_eq!(decoded, "AAAAACCCAAAAAAAAAADD");
+
+        let remap = table.retain(|&c| c != 'A');
+        assert_eq!(remap, vec![None, Some(0), Some(1)]);
+        assert_eq!(table.get_index(&'C'), Some(0));
+        assert_eq!(table.get_index(&'A'), None);
+        table.insert('A');
+        assert_eq!(table.get_index(&'A'), Some(2));
+    }
+
+    #[test]
+    fn hash_table() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Color(f32, f32, f32);
+        impl Eq for Color {}
+        impl std::hash::Hash for Color {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+                self.1.to_bits().hash(state);
+                self.2.to_bits().hash(state);
+            }
+        }
+
+        let red = Color(1.0, 0.0, 0.0);
+        let blue = Color(0.0, 0.0, 1.0);
+        let items = vec![red.clone(), red.clone(), blue.clone(), red.clone()];
+
+        let mut table = HashTable::with_store(HashStore::with_capacity(2));
+        let bytes: Vec<u8> = table.encode_bytes_mut(&items).unwrap().flatten().collect();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.store().as_slice(), &[red, blue]);
+
+        let decoded: Vec<Color> = table.decode_bytes(&bytes).cloned().collect();
+        assert_eq!(decoded, items);
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        assert_eq!(runs, [(0, 2), (1, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn encode_u8() {
+        let bytes = b"AAAAAAAAAAAAAAAAAAAABCCCCCCCCCDAAAAAAAAA";
+        let table = Table::from_slice(b"ABCD");
+        let slow: Vec<_> = table.encode(bytes).unwrap().collect();
+        let fast: Vec<_> = table.encode_u8(bytes).unwrap().collect();
+        assert_eq!(slow, [(0, 20), (1, 1), (2, 9), (3, 1), (0, 9)]);
+        assert_eq!(slow, fast);
+        let slow: Vec<_> = table.encode_bytes(bytes).unwrap().collect();
+        let fast: Vec<_> = table.encode_bytes_u8(bytes).unwrap().collect();
+        assert_eq!(slow, fast);
+
+        let table = Table::from_slice(&[0u8]);
+        assert!(matches!(
+            table.encode_u8(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5]),
+            Err(Error::TableMissingItems(10))
+        ));
+    }
+
+    #[test]
+    fn size_hints() {
+        let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+
+        let mut encoder = table.encode(&str).unwrap();
+        assert_eq!(encoder.size_hint(), (1, Some(28)));
+        encoder.next();
+        assert_eq!(encoder.size_hint(), (1, Some(23)));
+        let runs: Vec<_> = encoder.by_ref().collect();
+        assert_eq!(encoder.size_hint(), (0, Some(0)));
+        assert_eq!(encoder.next(), None);
+
+        let mut bytes = table.encode_bytes(&str).unwrap();
+        for rest in (0..=8).rev() {
+            let (min, max) = bytes.size_hint();
+            assert!(min <= rest && rest <= max.unwrap());
+            assert_eq!(bytes.next().is_some(), rest > 0);
+        }
+
+        let mut decoder = table.decode(&runs);
+        assert_eq!(decoder.len(), 23);
+        decoder.next();
+        assert_eq!(decoder.len(), 22);
+        decoder.next_back();
+        assert_eq!(decoder.size_hint(), (21, Some(21)));
+
+        // Overflowing run lengths don't panic
+        let decoder = table.decode(&[(0, usize::MAX), (0, 1)]);
+        assert_eq!(decoder.len(), usize::MAX);
+        assert_eq!(decoder.take(3).count(), 3);
+
+        let bytes: Vec<u8> = table.encode_bytes(&str).unwrap().collect();
+        let mut decoder = table.decode_bytes(&bytes);
+        decoder.next();
+        let (min, max) = decoder.size_hint();
+        assert_eq!(min, 4);
+        assert!(max.unwrap() >= 27);
+
+        // Decoding stops for good at the first invalid index
+        let mut decoder = table.decode(&[(0, 1), (9, 1), (1, 1)]);
+        assert_eq!(decoder.len(), 1);
+        assert_eq!(decoder.next(), Some(&'A'));
+        assert_eq!(decoder.next(), None);
+        let mut decoder = table.decode(&[(0, 1), (1, 0), (9, 0), (1, 1)]);
+        assert_eq!(decoder.len(), 1);
+        assert_eq!(decoder.next_back(), Some(&'A'));
+        assert_eq!(decoder.next_back(), None);
+        assert_eq!(decoder.next(), None);
+        let mut decoder = table.decode_bytes(&[0, 18, 2]);
+        assert_eq!(decoder.next(), Some(&'A'));
+        assert_eq!(decoder.next(), None);
+        assert_eq!(decoder.next(), None);
+    }
+
+    #[test]
+    fn decode_backwards() {
+        let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAAB".chars().collect();
+        let mut table = Table::default();
+        let runs: Vec<_> = table.encode_mut(&str).collect();
+        let bytes: Vec<u8> = table.encode_bytes(&str).unwrap().collect();
+        let reversed: String = str.iter().rev().collect();
+
+        assert_eq!(table.decode(&runs).rev().collect::<String>(), reversed);
+        assert_eq!(
+            table.decode_bytes(&bytes).rev().collect::<String>(),
+            reversed
+        );
+
+        // Take from both ends, meeting in the middle of a run
+        let mut decoder = table.decode(&runs);
+        let mut bytes_decoder = table.decode_bytes(&bytes);
+        let mut front = String::new();
+        let mut back = String::new();
+        for _ in 0..7 {
+            front.push(*decoder.next().unwrap());
+            back.push(*decoder.next_back().unwrap());
+            assert_eq!(bytes_decoder.next(), front.chars().last().as_ref());
+            assert_eq!(bytes_decoder.next_back(), back.chars().last().as_ref());
+        }
+        assert_eq!(decoder.len(), 15);
+        let rest: String = decoder.collect();
+        assert_eq!(bytes_decoder.collect::<String>(), rest);
+        assert_eq!(
+            front + &rest + &back.chars().rev().collect::<String>(),
+            str.iter().collect::<String>()
+        );
+    }
+
+    #[test]
+    fn bulk_encode_decode() {
+        let items: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+
+        let mut bytes = vec![0xAA];
+        table.encode_bytes_into(&items, &mut bytes).unwrap();
+        assert_eq!(
+            bytes,
+            [0xAA, 0x01, 0x05, 0x03, 0x0A, 0x05, 0x03, 0x01, 0x0A]
+        );
+
+        let mut decoded = vec!['X'];
+        table.decode_bytes_into(&bytes[1..], &mut decoded).unwrap();
+        assert_eq!(decoded[1..], items[..]);
+
+        let table = Table::from_slice(&['A']);
+        let mut bytes = vec![1, 2];
+        assert!(matches!(
+            table.encode_bytes_into(&['A', 'B'], &mut bytes),
+            Err(Error::TableMissingItems(1))
+        ));
+        assert_eq!(bytes, [1, 2]);
+
+        let mut decoded = vec!['X'];
+        assert!(matches!(
+            table.decode_bytes_into(&[1, 3, 4], &mut decoded),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_into(&[1, 3, 1], &mut decoded),
+            Err(Error::TruncatedStream)
+        ));
+        assert_eq!(decoded, ['X']);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rle_seq_serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Save {
+            name: String,
+            tiles: RleSeq<char>,
+        }
+
+        let mut tiles = RleSeq::from(vec!['.'; 10_000]);
+        tiles[5000] = 'X';
+        let save = Save {
+            name: "level".to_string(),
+            tiles,
+        };
+        let json = serde_json::to_string(&save).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"level","tiles":[[".",5000],["X",1],[".",4999]]}"#
+        );
+        let loaded: Save = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, save);
+
+        let empty: RleSeq<char> = serde_json::from_str("[]").unwrap();
+        assert!(empty.is_empty());
+
+        assert!(serde_json::from_str::<RleSeq<u8>>("[[1,18446744073709551615],[2,5]]").is_err());
+        assert!(serde_json::from_str::<RleSeq<u8>>("[[1,18446744073709551615]]").is_err());
+        assert!(serde_json::from_str::<RleSeq<u8>>("[[1,-1]]").is_err());
+        let zero: RleSeq<u8> = serde_json::from_str("[[1,0],[2,2]]").unwrap();
+        assert_eq!(*zero, vec![2, 2]);
+    }
+
+    #[test]
+    fn packbits() {
+        let mut bytes = b"ABCABCAAAAAAAAAAXYZZ".to_vec();
+        bytes.extend_from_slice(&[b'Q'; 300]);
+        bytes.extend_from_slice(b"RS");
+        let packed = encode_packbits(&bytes);
+        assert_eq!(decode_packbits(&packed).unwrap(), bytes);
+
+        assert!(encode_packbits(&[]).is_empty());
+        assert_eq!(encode_packbits(&[7; 130]), [0x81, 7, 0x01, 7, 7]);
+        assert_eq!(decode_packbits(&[0x80, 0x00, 5]).unwrap(), [5]);
+        assert!(matches!(
+            decode_packbits(&[0x02, 1, 2]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_packbits(&[0xFE]),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn tga_packets() {
+        let rows = "AAAB\
+                    CCCC\
+                    DBDB";
+        for bpp in 1..=4 {
+            let pixels: Vec<u8> = rows
+                .bytes()
+                .flat_map(|pixel| std::iter::repeat_n(pixel, bpp))
+                .collect();
+            let packed = encode_tga(&pixels, bpp, 4);
+            assert_eq!(decode_tga(&packed, bpp, 12).unwrap(), pixels);
+        }
+
+        // Packets never cross rows
+        let packed = encode_tga(&[9; 6], 1, 3);
+        assert_eq!(packed, [0x82, 9, 0x82, 9]);
+
+        assert!(matches!(
+            decode_tga(&[0x82, 9], 1, 4),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_tga(&[0x00, 9, 0x82, 9], 1, 2),
+            Err(Error::InvalidBytes(2))
+        ));
+
+        // A pixel count from a crafted header is never allocated up front
+        assert!(matches!(
+            decode_tga(&[0x82, 9], 4, usize::MAX),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            decode_tga(&[0x82, 9], 1, usize::MAX),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn bmp_rle() {
+        let pixels = [
+            1, 1, 1, 1, 9, 9, //
+            9, 9, 2, 3, 4, 5, //
+            15, 15, 15, 15, 15, 15,
+        ];
+        let data = encode_bmp_rle8(&pixels, 6);
+        assert_eq!(decode_bmp_rle8(&data, 6, 3).unwrap(), pixels);
+        let data = encode_bmp_rle4(&pixels, 6);
+        assert_eq!(decode_bmp_rle4(&data, 6, 3).unwrap(), pixels);
+
+        // Escapes that skip pixels leave them as zero
+        let data = [2, 7, 0, 0, 1, 8, 0, 2, 1, 1, 1, 9, 0, 1];
+        assert_eq!(
+            decode_bmp_rle8(&data, 3, 3).unwrap(),
+            [7, 7, 0, 8, 0, 0, 0, 0, 9]
+        );
+        let data = [3, 0x12, 0, 0, 0, 3, 0x45, 0x60, 0, 1];
+        assert_eq!(decode_bmp_rle4(&data, 3, 2).unwrap(), [1, 2, 1, 4, 5, 6]);
+
+        assert!(matches!(
+            decode_bmp_rle8(&[4, 1], 3, 1),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            decode_bmp_rle8(&[0, 4, 1, 2], 4, 1),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64_armor() {
+        let table = Table::from_slice(&['A', 'B']);
+        for str in ["", "A", "AB", "AAB", "AABBB", "ABABABABAB"].iter() {
+            let items: Vec<char> = str.chars().collect();
+            let text = table.encode_base64(&items).unwrap();
+            assert!(text.len().is_multiple_of(4));
+            assert_eq!(table.decode_base64(&text).unwrap(), items);
+        }
+
+        assert!(matches!(
+            table.decode_base64("AQ?D"),
+            Err(Error::InvalidBase64(2))
+        ));
+        assert!(matches!(
+            table.decode_base64("AQUDC"),
+            Err(Error::InvalidBase64(4))
+        ));
+    }
+
+    #[test]
+    fn bits_format() {
+        let table = Table::from_slice(&['.', 'X']);
+        for str in ["", ".", "..XX", "....XXXXXXXX..X", "X.X.X.X"].iter() {
+            let items: Vec<char> = str.chars().collect();
+            let bits = table.encode_bits(&items).unwrap();
+            assert_eq!(table.decode_bits(&bits).unwrap(), items);
+
+            let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+            assert!(bits.len() <= bytes.len());
+        }
+
+        let table = Table::from_slice(&['.']);
+        assert_eq!(
+            table
+                .decode_bits(&table.encode_bits(&['.'; 300]).unwrap())
+                .unwrap(),
+            ['.'; 300]
+        );
+        assert!(matches!(
+            table.decode_bits(&[0xC0]),
+            Err(Error::UnknownIndex(1))
+        ));
+        assert!(matches!(
+            table.decode_bits(&[0x01]),
+            Err(Error::TruncatedStream)
+        ));
+
+        let table = Table::from_slice(&['.', 'X', 'O']);
+        assert!(matches!(
+            table.encode_bits(&['.']),
+            Err(Error::TableTooLarge(3))
+        ));
+    }
+
+    #[test]
+    fn compact_bytes() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 600];
+        items.extend("BCB".chars());
+        items.extend(vec!['C'; 130]);
+        let compact: Vec<u8> = table.encode_bytes_compact(&items).unwrap().collect();
+        let decoded: Vec<char> = table.decode_bytes_compact(&compact).copied().collect();
+        assert_eq!(decoded, items);
+
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert!(compact.len() < bytes.len());
+        assert_eq!(table.encode_bytes_compact(&[]).unwrap().count(), 0);
+
+        let table = Table::from_iter(0..17);
+        assert!(matches!(
+            table.encode_bytes_compact(&[0]),
+            Err(Error::TableTooLarge(17))
+        ));
+    }
+
+    #[test]
+    fn grid_layouts() {
+        let grid: Vec<char> = "AAABB\
+                               ABBBC\
+                               CCCCC"
+            .chars()
+            .collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+
+        for layout in [
+            GridLayout::new(5, 3),
+            GridLayout::new(5, 3).break_lines(),
+            GridLayout::new(5, 3).column_major(),
+            GridLayout::new(5, 3).column_major().break_lines(),
+        ] {
+            let bytes = table.encode_grid(layout, &grid).unwrap();
+            assert_eq!(table.decode_grid(layout, &bytes).unwrap(), grid);
+        }
+
+        // Breaking lines is the same as encoding each line on its own
+        let layout = GridLayout::new(5, 3).break_lines();
+        let bytes = table.encode_grid(layout, &grid).unwrap();
+        let mut rows = Vec::new();
+        for row in grid.chunks(5) {
+            table.encode_bytes_into(row, &mut rows).unwrap();
+        }
+        assert_eq!(bytes, rows);
+
+        assert!(matches!(
+            table.encode_grid(GridLayout::new(4, 4), &grid),
+            Err(Error::InvalidGridSize(15))
+        ));
+        assert!(matches!(
+            table.decode_grid(GridLayout::new(4, 4), &bytes),
+            Err(Error::InvalidGridSize(15))
+        ));
+
+        // Grids too large to count don't overflow
+        let huge = GridLayout::new(usize::MAX, 2);
+        assert_eq!(huge.len(), usize::MAX);
+        assert!(matches!(
+            table.encode_grid(huge, &grid),
+            Err(Error::InvalidGridSize(15))
+        ));
+    }
+
+    #[test]
+    fn tilemap_chunks() {
+        let tiles: Vec<char> = "AAAABBBB\
+                                AAAABBBB\
+                                CCCCDDDD\
+                                CCCCDDDD"
+            .chars()
+            .collect();
+        let table = Table::from_slice(&['A', 'B', 'C', 'D']);
+        let chunk = table.encode_chunk(8, 4, &tiles).unwrap();
+        assert_eq!(table.decode_chunk(&chunk).unwrap(), (8, 4, tiles.clone()));
+
+        assert!(matches!(
+            table.decode_chunk(&chunk[..10]),
+            Err(Error::TruncatedStream)
+        ));
+        let mut bad = chunk.clone();
+        bad[0] = b'X';
+        assert!(matches!(
+            table.decode_chunk(&bad),
+            Err(Error::InvalidBytes(0))
+        ));
+
+        let smaller = Table::from_slice(&table[..3]);
+        assert!(matches!(
+            smaller.decode_chunk(&chunk),
+            Err(Error::InvalidBytes(12))
+        ));
+        assert!(matches!(
+            table.encode_chunk(8, 3, &tiles),
+            Err(Error::InvalidGridSize(32))
+        ));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn image_roundtrip() {
+        use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+        let sheet = RgbaImage::from_fn(64, 32, |x, y| match (x / 16 + y / 16) % 3 {
+            0 => Rgba([0, 0, 0, 0]),
+            1 => Rgba([255, 255, 255, 255]),
+            _ => Rgba([40, 80, 120, 255]),
+        });
+        let table = HashTable::from_image(&sheet);
+        assert_eq!(table.len(), 3);
+        let bytes = table.encode_image(&sheet).unwrap();
+        assert!(bytes.len() < 300);
+        assert_eq!(table.decode_image(&bytes).unwrap(), sheet);
+
+        let indexed = GrayImage::from_fn(10, 10, |x, _| Luma([(x / 5) as u8]));
+        let table = HashTable::from_image(&indexed);
+        let bytes = table.encode_image(&indexed).unwrap();
+        assert_eq!(table.decode_image(&bytes).unwrap(), indexed);
+
+        let mut huge = bytes[..4].to_vec();
+        huge.extend_from_slice(&[0xFF; 8]);
+        huge.extend_from_slice(&bytes[12..]);
+        assert!(matches!(
+            table.decode_image(&huge),
+            Err(Error::InvalidGridSize(100))
+        ));
+    }
+
+    #[test]
+    fn run_normalization() {
+        let mut runs = vec![(0, 2), (0, 0), (1, 3), (1, 1), (0, 0), (2, 1), (2, 2)];
+        let merged: Vec<_> = normalized(runs.iter().copied()).collect();
+        normalize_runs(&mut runs);
+        assert_eq!(runs, [(0, 2), (1, 4), (2, 3)]);
+        assert_eq!(runs, merged);
+
+        // Dropping an empty run can leave two runs of the same index to merge
+        let mut runs = vec![(0, 1), (1, 0), (0, 1)];
+        normalize_runs(&mut runs);
+        assert_eq!(runs, [(0, 2)]);
+
+        let table = Table::from_slice(&['A', 'B']);
+        let mut runs = vec![(0, 2), (0, 3), (1, 1), (1, 1)];
+        let decoded: String = table.decode(&runs).collect();
+        normalize_runs(&mut runs);
+        assert_eq!(table.decode(&runs).collect::<String>(), decoded);
+    }
+
+    #[test]
+    fn concatenation() {
+        let left: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let right: Vec<char> = "AAAAAAAAAADDDDDBBBCC".chars().collect();
+        let joined: Vec<char> = left.iter().chain(&right).copied().collect();
+
+        // Same table
+        let mut table = Table::default();
+        let mut runs: Vec<_> = table.encode_mut(&left).collect();
+        let right_runs: Vec<_> = table.encode_mut(&right).collect();
+        let mut bytes: Vec<u8> = table.encode_bytes(&left).unwrap().collect();
+        let right_bytes: Vec<u8> = table.encode_bytes(&right).unwrap().collect();
+        append_runs(&mut runs, &right_runs);
+        append_bytes(&mut bytes, &right_bytes).unwrap();
+        let expected: Vec<u8> = table.encode_bytes(&joined).unwrap().collect();
+        assert_eq!(runs, table.encode(&joined).unwrap().collect::<Vec<_>>());
+        assert_eq!(bytes, expected);
+        assert!(matches!(
+            append_bytes(&mut bytes, &[0x00, 0x03]),
+            Err(Error::TruncatedStream)
+        ));
+        assert_eq!(bytes, expected);
+
+        // Different tables
+        let mut left_table = Table::default();
+        let mut runs: Vec<_> = left_table.encode_mut(&left).collect();
+        let mut bytes: Vec<u8> = left_table.encode_bytes(&left).unwrap().collect();
+        let right_table = Table::from_slice(&['D', 'C', 'B', 'A']);
+        let right_runs: Vec<_> = right_table.encode(&right).unwrap().collect();
+        let right_bytes: Vec<u8> = right_table.encode_bytes(&right).unwrap().collect();
+
+        let mut table = left_table.clone();
+        table
+            .append_runs_from(&mut runs, &right_table, &right_runs)
+            .unwrap();
+        assert_eq!(table.decode(&runs).copied().collect::<Vec<_>>(), joined);
+        assert_eq!(runs.len(), 7);
+
+        let mut table = left_table.clone();
+        table
+            .append_bytes_from(&mut bytes, &right_table, &right_bytes)
+            .unwrap();
+        assert_eq!(
+            table.decode_bytes(&bytes).copied().collect::<Vec<_>>(),
+            joined
+        );
+        assert_eq!(bytes, expected);
+
+        assert!(matches!(
+            table.append_runs_from(&mut runs, &right_table, &[(9, 1)]),
+            Err(Error::UnknownIndex(9))
+        ));
+    }
+
+    #[test]
+    fn slicing() {
+        let items: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        for &(start, end) in &[(0, 28), (3, 7), (5, 15), (10, 11), (20, 40), (30, 35)] {
+            let expected = &items[start.min(28)..end.min(28)];
+            let sliced = slice_runs(&runs, start..end);
+            assert!(table.decode(&sliced).eq(expected));
+            let sliced = slice_bytes(&bytes, start..end).unwrap();
+            assert!(table.decode_bytes(&sliced).eq(expected));
+        }
+
+        assert_eq!(slice_runs(&runs, ..), runs);
+        assert!(slice_runs(&runs, 5..5).is_empty());
+        assert!(matches!(
+            slice_bytes(&[0, 1], 0..5),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn diff_patch() {
+        let old: Vec<char> = "AAAABBBBCCCCAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C', 'X']);
+        let old_runs: Vec<_> = table.encode(&old).unwrap().collect();
+
+        for new in [
+            "AAAABBBBCCCCAAAA",
+            "AAAABXXBCCCCAAAA",
+            "AAAABBBBCCCCAAAAXXX",
+            "AAAABBBB",
+            "XAAABBBBCCCCAAAX",
+        ]
+        .iter()
+        {
+            let new: Vec<char> = new.chars().collect();
+            let new_runs: Vec<_> = table.encode(&new).unwrap().collect();
+            let patch = diff(&old_runs, &new_runs);
+            assert_eq!(patch.apply(&old_runs), new_runs);
+            assert_eq!(patch.is_empty(), old == new);
+        }
+
+        let patch = diff(&old_runs, &[]);
+        assert_eq!(
+            patch.edits,
+            [PatchEdit {
+                start: 0,
+                remove: 16,
+                runs: vec![]
+            }]
+        );
+        assert!(patch.apply(&old_runs).is_empty());
+    }
+
+    #[test]
+    fn run_length_adapter() {
+        let str = "AAAAABBBBBBBBBBCCCAAAAAAAAAA";
+        let runs: Vec<_> = str.chars().runs().collect();
+        assert_eq!(runs, [('A', 5), ('B', 10), ('C', 3), ('A', 10)]);
+
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let chars: Vec<char> = str.chars().collect();
+        let indexed: Vec<_> = runs
+            .iter()
+            .map(|&(c, len)| (table.get_index(&c).unwrap(), len))
+            .collect();
+        assert_eq!(indexed, table.encode(&chars).unwrap().collect::<Vec<_>>());
+
+        assert_eq!(std::iter::empty::<u8>().runs().next(), None);
+        let mut iter = [1, 1, 2].iter().runs();
+        assert_eq!(iter.size_hint(), (1, Some(3)));
+        assert_eq!(iter.next(), Some((&1, 2)));
+        assert_eq!(iter.next(), Some((&2, 1)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn decode_into_buffers() {
+        let table = Table::from_slice(&['A', 'B']);
+        let mut items = vec!['-'];
+        table.decode_into(&[(1, 2), (0, 1)], &mut items).unwrap();
+        assert_eq!(items, ['-', 'B', 'B', 'A']);
+        assert!(matches!(
+            table.decode_into(&[(0, 1), (2, 1)], &mut items),
+            Err(Error::InvalidRun(1, 2))
+        ));
+
+        let bytes: Vec<u8> = table.encode_bytes(&['A', 'A', 'B', 'A']).unwrap().collect();
+        let mut buf = ['-'; 4];
+        assert_eq!(table.decode_bytes_to_slice(&bytes, &mut buf).unwrap(), 4);
+        assert_eq!(buf, ['A', 'A', 'B', 'A']);
+        let mut small = ['-'; 3];
+        assert!(matches!(
+            table.decode_bytes_to_slice(&bytes, &mut small),
+            Err(Error::BufferTooSmall(4))
+        ));
+        assert_eq!(small, ['-'; 3]);
+        assert!(matches!(
+            table.decode_bytes_to_slice(&[0x08], &mut buf),
+            Err(Error::UnknownIndex(4))
+        ));
+    }
+
+    #[test]
+    fn table_to_bytes() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut bytes = table.to_bytes().unwrap();
+        assert_eq!(
+            Table::<char>::from_bytes(&bytes).unwrap().as_ref(),
+            table.as_ref()
+        );
+        assert!(matches!(
+            Table::<char>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::InvalidBytes(_))
+        ));
+        bytes[4] = TABLE_FORMAT_VERSION + 1;
+        assert!(matches!(
+            Table::<char>::from_bytes(&bytes),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            Table::<char>::from_bytes(b"RLEX"),
+            Err(Error::InvalidBytes(0))
+        ));
+    }
+
+    #[test]
+    fn optimize_for_frequency() {
+        let input: Vec<char> = "ABABCCCCCCABAB".chars().collect();
+        let mut table = Table::from_slice(&['C', 'D', 'B', 'A']);
+        let runs: Vec<_> = table.encode(&input).unwrap().collect();
+        let remap = table.optimize_for(&input);
+        assert_eq!(table.as_ref(), ['B', 'A', 'C', 'D']);
+        assert_eq!(remap, [2, 3, 0, 1]);
+
+        let remapped: Vec<_> = runs.iter().map(|&(ind, len)| (remap[ind], len)).collect();
+        assert_eq!(
+            table.decode(&remapped).collect::<String>(),
+            "ABABCCCCCCABAB"
+        );
+
+        let mut hashed = HashTable::with_store(HashStore::new());
+        assert!(hashed.optimize_for(&[3, 1, 1, 3]).is_empty());
+        assert_eq!(hashed.store().as_slice(), [3, 1]);
+    }
+
+    #[test]
+    fn literal_block_options() {
+        let table = Table::from_slice(&['A', 'B', 'C', 'D']);
+        let options = EncodeOptions::new().literal_blocks();
+        for str in ["", "A", "ABCD", "AAAAABCDCBAAAAA", "ABABABABCCCCCCCCD"].iter() {
+            let items: Vec<char> = str.chars().collect();
+            let bytes = table.encode_bytes_with(&items, options).unwrap();
+            assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), items);
+        }
+
+        let small = Table::from_slice(&['A', 'B']);
+        let items: Vec<char> = "AAAAABABBBBBBB".chars().collect();
+        let plain = small
+            .encode_bytes_with(&items, EncodeOptions::new())
+            .unwrap();
+        assert_eq!(
+            plain,
+            small.encode_bytes(&items).unwrap().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            small
+                .decode_bytes_with(&plain, EncodeOptions::new())
+                .unwrap(),
+            items
+        );
+        assert!(matches!(
+            small.decode_bytes_with(&[0x00, 0x05], options),
+            Err(Error::UnknownIndex(5))
+        ));
+        assert!(matches!(
+            small.encode_bytes_with(&['C'], options),
+            Err(Error::TableMissingItems(0))
+        ));
+    }
+
+    #[test]
+    fn narrow_run_lengths() {
+        let input: Vec<u8> = std::iter::repeat_n(b'A', 70000)
+            .chain(std::iter::repeat_n(b'B', 3))
+            .collect();
+        let table = Table::from_slice(b"AB");
+        let runs: Vec<(Index, u16)> = table.encode_as(&input).unwrap().collect();
+        assert_eq!(runs, [(0, 65535), (0, 4465), (1, 3)]);
+        assert_eq!(table.decode_as(&runs).len(), input.len());
+        assert!(table.decode_as(&runs).eq(input.iter()));
+
+        let wide: Vec<_> = table.encode(&input).unwrap().collect();
+        assert!(matches!(
+            convert_runs::<u16>(&wide),
+            Err(Error::RunTooLong(0, 70000))
+        ));
+        let narrow = convert_runs::<u32>(&wide).unwrap();
+        assert_eq!(narrow, [(0, 70000), (1, 3)]);
+    }
+
+    #[test]
+    fn varint_lengths() {
+        let table = Table::from_slice(&['A', 'B']);
+        let mut items = vec!['A'; 300];
+        items.extend(vec!['B'; 5]);
+        items.extend(vec!['A'; 20000]);
+        let bytes = table.encode_bytes_varint(&items).unwrap();
+        assert_eq!(
+            bytes,
+            [0x01, 0xAC, 0x02, 0x03, 0x05, 0x01, 0xA0, 0x9C, 0x01]
+        );
+        assert_eq!(table.decode_bytes_varint(&bytes).unwrap(), items);
+
+        let plain: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert!(bytes.len() < plain.len());
+        assert_eq!(table.decode_bytes_varint(&plain).unwrap(), items);
+
+        assert!(matches!(
+            table.decode_bytes_varint(&[0x01, 0x80]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_bytes_varint(&[
+                0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F
+            ]),
+            Err(Error::InvalidBytes(1))
+        ));
+    }
+
+    #[test]
+    fn by_value_decoding() {
+        let input: Vec<u16> = vec![4, 4, 9, 9, 9, 4];
+        let table = Table::from_slice(&[4, 9]);
+        let runs: Vec<_> = table.encode(&input).unwrap().collect();
+        let reversed: Vec<u16> = table.decode_copied(&runs).rev().collect();
+        assert_eq!(reversed, [4, 9, 9, 9, 4, 4]);
+        assert_eq!(table.decode_cloned(&runs).len(), 6);
+
+        let bytes: Vec<u8> = table.encode_bytes(&input).unwrap().collect();
+        assert_eq!(table.decode_bytes_copied(&bytes).collect::<Vec<_>>(), input);
+        assert_eq!(table.decode_bytes_cloned(&bytes).collect::<Vec<_>>(), input);
+    }
+
+    #[test]
+    fn owned_decoders() {
+        use std::sync::Arc;
+
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Arc::new(Table::from_slice(&['A', 'B', 'C']));
+        let runs: Vec<_> = table.encode(&input).unwrap().collect();
+        let bytes: Arc<[u8]> = table.encode_bytes(&input).unwrap().collect();
+
+        let runs = DecoderOwned::new(table.clone(), runs);
+        let bytes = BytesDecoderOwned::new(table.clone(), bytes.clone());
+        let handle =
+            std::thread::spawn(move || (runs.collect::<Vec<_>>(), bytes.collect::<Vec<_>>()));
+        let (from_runs, from_bytes) = handle.join().unwrap();
+        assert_eq!(from_runs, input);
+        assert_eq!(from_bytes, input);
+
+        let mut decoder = BytesDecoderOwned::new(table, vec![0x00, 0x06, 0x02]);
+        assert_eq!(decoder.next(), Some('A'));
+        assert_eq!(decoder.next(), None);
+        assert_eq!(decoder.next(), None);
+    }
+
+    #[test]
+    fn streaming_encoder() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 200];
+        items.extend("BBBBBCAAAAAAAAAAACCCCCCCC".chars());
+        let expected: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        let mut encoder = table.encode_streaming();
+        let mut bytes = Vec::new();
+        for packet in items.chunks(7) {
+            encoder.push_slice(packet).unwrap();
+            encoder.write_bytes(&mut bytes).unwrap();
+        }
+        assert_eq!(encoder.position(), items.len());
+        encoder.finish_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, expected);
+
+        let mut encoder = table.encode_streaming();
+        encoder.push('B').unwrap();
+        assert!(matches!(
+            encoder.push('X'),
+            Err(Error::TableMissingItems(1))
+        ));
+        assert_eq!(encoder.finish(), [(1, 1)]);
+    }
+
+    #[test]
+    fn streaming_decoder() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 200];
+        items.extend("BBBBBCAAAAAAAAAAACCCCCCCC".chars());
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        let mut decoder = table.decode_streaming();
+        let mut decoded = Vec::new();
+        for packet in bytes.chunks(3) {
+            // Only take some of the items, leaving the rest for the next packet
+            let mut iter = decoder.feed(packet);
+            decoded.extend(iter.by_ref().take(2).map(|item| *item.unwrap()));
+        }
+        decoded.extend(decoder.feed(&[]).map(|item| *item.unwrap()));
+        decoder.finish().unwrap();
+        assert_eq!(decoded, items);
+
+        let mut decoder = table.decode_streaming();
+        assert_eq!(decoder.feed(&[0x01]).count(), 0);
+        assert!(matches!(decoder.finish(), Err(Error::TruncatedStream)));
+
+        let mut decoder = table.decode_streaming();
+        let mut iter = decoder.feed(&[0x00, 0x7E, 0x00]);
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(Error::UnknownIndex(63)))));
+        assert!(iter.next().is_none());
+        drop(iter);
+        assert!(matches!(decoder.finish(), Err(Error::UnknownIndex(63))));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn async_encode_decode() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 300];
+        items.extend("BBBCAAAAACCCCCCCCCC".chars());
+
+        runtime.block_on(async {
+            let mut bytes = Vec::new();
+            let written = table.encode_bytes_async(&items, &mut bytes).await.unwrap();
+            assert_eq!(written, bytes.len());
+            assert_eq!(
+                bytes,
+                table.encode_bytes(&items).unwrap().collect::<Vec<_>>()
+            );
+
+            let mut decoded = vec!['X'];
+            let read = table
+                .decode_bytes_async(&bytes[..], &mut decoded)
+                .await
+                .unwrap();
+            assert_eq!(read, items.len());
+            assert_eq!(decoded[1..], items[..]);
+
+            let err = table
+                .decode_bytes_async(&[0x01][..], &mut decoded)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(decoded.len(), items.len() + 1);
+
+            let err = table
+                .encode_bytes_async(&['X'], &mut bytes)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        });
+    }
+
+    #[test]
+    fn analyze_sizes() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut long = vec!['A'; 600];
+        long.extend(vec!['B'; 3]);
+        for items in [long, "ABCABCAABBCC".chars().collect::<Vec<_>>()].iter() {
+            let stats = table.analyze(items);
+            assert_eq!(stats.items, items.len());
+            assert_eq!(stats.runs, table.encode(items).unwrap().count());
+            assert_eq!(stats.histogram.values().sum::<usize>(), stats.runs);
+            assert_eq!(stats.missing, 0);
+            assert_eq!(stats.bytes_len, table.encode_bytes(items).unwrap().count());
+            assert_eq!(
+                stats.varint_len,
+                table.encode_bytes_varint(items).unwrap().len()
+            );
+            let options = EncodeOptions::new().literal_blocks();
+            assert_eq!(
+                stats.literal_blocks_len,
+                table.encode_bytes_with(items, options).unwrap().len()
+            );
+            assert_eq!(
+                stats.compact_len,
+                table.encode_bytes_compact(items).unwrap().count()
+            );
+        }
+
+        let table = Table::from_slice(&['A', 'B']);
+        let stats = table.analyze(&['A', 'A', 'C', 'C', 'C']);
+        assert_eq!(stats.symbols, 1);
+        assert_eq!(stats.missing, 3);
+    }
+
+    #[test]
+    fn validate_untrusted_bytes() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 300];
+        items.extend("BBBCAAAAA".chars());
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        let summary = table.validate_bytes(&bytes).unwrap();
+        assert_eq!(summary.items, 309);
+        assert_eq!(summary.runs, 6);
+        assert_eq!(summary.longest_run, 127);
+
+        assert!(matches!(
+            table.validate_bytes(&[0x01, 0x05, 0x03]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.validate_bytes(&[0x00, 0x40]),
+            Err(Error::UnknownIndex(32))
+        ));
+        assert_eq!(table.validate_bytes(&[]).unwrap(), DecodeSummary::default());
+    }
+
+    #[test]
+    fn public_index_lookup() {
+        let mut table = HashTable::with_store(HashStore::new());
+        table.extend_from_slice(&["grass", "water"]);
+        assert_eq!(table.index_of(&"water"), Some(1));
+        assert_eq!(table.index_of(&"lava"), None);
+        assert_eq!(table.index_of_or_insert(&"lava"), 2);
+        assert_eq!(table.index_of(&"lava"), Some(2));
+        assert_eq!(table.item(2), Some(&"lava"));
+    }
+
+    #[test]
+    fn merge_chunk_tables() {
+        let chunks = ["AAABBA", "CCAADD", "BBDDEE"];
+        let mut global = Table::default();
+        let mut merged = Vec::new();
+        for chunk in &chunks {
+            let items: Vec<char> = chunk.chars().collect();
+            let mut local = Table::default();
+            let runs: Vec<_> = local.encode_mut(&items).collect();
+            let remap = global.merge(&local);
+            assert_eq!(remap.len(), local.len());
+            merged.extend(runs.iter().map(|&(ind, len)| (remap[ind], len)));
+        }
+        assert_eq!(global.as_ref(), ['A', 'B', 'C', 'D', 'E']);
+        assert_eq!(global.decode(&merged).collect::<String>(), chunks.concat());
+
+        let mut hashed = HashTable::with_store(HashStore::new());
+        assert_eq!(hashed.merge(&global), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn remap_encoded_data() {
+        let mut table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "CCBBCCCCABC".chars().collect();
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        let mapping = table.optimize_for(&items);
+        assert_eq!(table.as_ref(), ['C', 'B', 'A']);
+        let new_runs = remap_runs(&runs, &mapping).unwrap();
+        assert_eq!(new_runs, [(0, 2), (1, 2), (0, 4), (2, 1), (1, 1), (0, 1)]);
+        let new_bytes = remap_bytes(&bytes, &mapping).unwrap();
+        assert_eq!(
+            new_bytes,
+            table.encode_bytes(&items).unwrap().collect::<Vec<_>>()
+        );
+
+        let mapping = table.remove(&'C').unwrap();
+        assert!(matches!(
+            remap_runs(&[(1, 1), (0, 2)], &mapping),
+            Err(Error::InvalidRun(1, 0))
+        ));
+        assert!(matches!(
+            remap_bytes(&[0x02, 0x00], &mapping),
+            Err(Error::UnknownIndex(0))
+        ));
+        assert!(matches!(
+            remap_bytes(&[0x00], &[200]),
+            Err(Error::TableTooLarge(201))
+        ));
+    }
+
+    #[test]
+    fn run_struct() {
+        let input: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let runs: Vec<Run> = table.encode_runs(&input).unwrap().collect();
+        let tuples: Vec<(Index, usize)> = runs.iter().map(|&run| run.into()).collect();
+        assert_eq!(tuples, table.encode(&input).unwrap().collect::<Vec<_>>());
+        assert!(table.decode_as(&runs).eq(input.iter()));
+        assert!(table.decode_as(&runs).rev().eq(input.iter().rev()));
+
+        let run = Run::new(1, 10u8);
+        assert_eq!(run.len(), 10);
+        assert!(!run.is_empty());
+        assert_eq!(run.split_at(10), (run, Run::new(1, 0)));
+        assert!(run.split_at(10).1.is_empty());
+        assert_eq!(Run::from((3, 4)).to_string(), "3:4");
+    }
+
+    #[test]
+    fn table_collection_traits() {
+        let table: Table<char> = "ABRACADABRA".chars().collect();
+        assert_eq!(table.as_ref(), ['A', 'B', 'R', 'C', 'D']);
+
+        let mut hashed: HashTable<char> = table.iter().copied().collect();
+        Extend::extend(&mut hashed, &['Z', 'A']);
+        Extend::extend(&mut hashed, vec!['Y']);
+        assert_eq!(
+            hashed.store().as_slice(),
+            ['A', 'B', 'R', 'C', 'D', 'Z', 'Y']
+        );
+
+        let mut count = 0;
+        for item in &table {
+            assert_eq!(table.index_of(item), Some(count));
+            count += 1;
+        }
+        assert_eq!(count, table.len());
+        assert_eq!(table.into_iter().collect::<String>(), "ABRCD");
+    }
+
+    #[test]
+    fn sorted_accessors() {
+        let table = Table::from_slice(&[30, 10, 20, 40]);
+        assert!(table.contains(&20));
+        assert!(!table.contains(&25));
+        for (i, item) in table.iter_sorted().enumerate() {
+            assert_eq!(table.get_sorted(i), Some(item));
+            assert_eq!(table.sorted_position(item), Some(i));
+        }
+        assert_eq!(table.get_sorted(4), None);
+        assert_eq!(table.sorted_position(&25), None);
+    }
+
+    #[test]
+    fn str_helpers() {
+        use crate::str::*;
+
+        let text = "ééé  naïve ☃☃☃☃ ok";
+        let mut table = Table::default();
+        let runs = encode_str(&mut table, text);
+        let chars: Vec<char> = text.chars().collect();
+        assert_eq!(runs, table.encode(&chars).unwrap().collect::<Vec<_>>());
+        assert_eq!(decode_to_string(&table, &runs), text);
+
+        let bytes = encode_str_bytes(&mut table, text).unwrap();
+        assert_eq!(
+            bytes,
+            table.encode_bytes(&chars).unwrap().collect::<Vec<_>>()
+        );
+        assert_eq!(decode_bytes_to_string(&table, &bytes).unwrap(), text);
+        assert!(decode_bytes_to_string(&table, &[0xFE]).is_err());
+        assert_eq!(encode_str(&mut table, ""), []);
+    }
+
+    #[test]
+    fn table_free_bytes() {
+        use crate::bytes::*;
+
+        let mut data = vec![0u8; 1000];
+        data[500..600].fill(3);
+        data.extend(0..=255);
+        let packed = encode_u8(&data);
+        assert_eq!(&packed[..8], [255, 0, 245, 0, 100, 3, 255, 0]);
+        assert_eq!(decode_u8(&packed).unwrap(), data);
+        assert!(decode_u8(&[]).unwrap().is_empty());
+
+        let mut out = vec![9];
+        assert!(matches!(
+            decode_u8_into(&[2, 1, 5], &mut out),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_u8_into(&[2, 1, 0, 5], &mut out),
+            Err(Error::InvalidBytes(2))
+        ));
+        assert_eq!(out, [9]);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn wasm_table() {
+        let level = [0u8, 0, 0, 0, 7, 7, 3, 0, 0];
+        let table = WasmTable::from_data(&level);
+        assert_eq!(table.symbols(), [0, 7, 3]);
+        let packed = table.encode(&level).ok().unwrap();
+        assert_eq!(table.decode(&packed).ok().unwrap(), level);
+
+        let loaded = WasmTable::from_bytes(&table.to_bytes().ok().unwrap())
+            .ok()
+            .unwrap();
+        assert_eq!(loaded.length(), 3);
+        let container = loaded.encode_container(&level).ok().unwrap();
+        assert_eq!(WasmTable::decode_container(&container).ok().unwrap(), level);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_round_trip() {
+        use crate::ffi::*;
+        let symbols = [10u32, 20, 30];
+        let items = [10u32, 10, 10, 30, 20, 20];
+        unsafe {
+            let table = rle_table_new(symbols.as_ptr(), symbols.len());
+            assert_eq!(rle_table_len(table), 3);
+
+            let mut len = 0;
+            let status = rle_encode(
+                table,
+                items.as_ptr(),
+                items.len(),
+                std::ptr::null_mut(),
+                0,
+                &mut len,
+            );
+            assert_eq!(status, RleStatus::BufferTooSmall);
+            let mut bytes = vec![0u8; len];
+            let status = rle_encode(
+                table,
+                items.as_ptr(),
+                items.len(),
+                bytes.as_mut_ptr(),
+                bytes.len(),
+                &mut len,
+            );
+            assert_eq!(status, RleStatus::Ok);
+
+            let mut out = [0u32; 4];
+            let status = rle_decode(
+                table,
+                bytes.as_ptr(),
+                len,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut len,
+            );
+            assert_eq!((status, len), (RleStatus::BufferTooSmall, 6));
+            let mut out = [0u32; 6];
+            let status = rle_decode(
+                table,
+                bytes.as_ptr(),
+                bytes.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut len,
+            );
+            assert_eq!(status, RleStatus::Ok);
+            assert_eq!(out, items);
+
+            let status = rle_decode(
+                table,
+                [0x07].as_ptr(),
+                1,
+                out.as_mut_ptr(),
+                out.len(),
+                &mut len,
+            );
+            assert_eq!(status, RleStatus::Failed);
+            assert!(!rle_last_error().is_null());
+            rle_table_free(table);
+        }
+    }
+
+    #[test]
+    fn try_encode_matches_encode() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AAABCCCCBBAAAC".chars().collect();
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        let tried: Result<Vec<_>, _> = table.try_encode(&items).collect();
+        assert_eq!(tried.unwrap(), runs);
+
+        let mut items = items;
+        items[6] = 'X';
+        let tried: Vec<_> = table.try_encode(&items).collect();
+        assert!(tried[..tried.len() - 1].iter().all(|run| run.is_ok()));
+        assert!(matches!(
+            tried.last(),
+            Some(Err(Error::TableMissingItems(6)))
+        ));
+    }
+
+    #[test]
+    fn large_table_insert_or_get() {
+        let items: Vec<u32> = (0..5000).map(|i| (i * 7919) % 5003).collect();
+        let mut table = Table::default();
+        let runs: Vec<_> = table.encode_mut(&items).collect();
+        assert_eq!(table.len(), 5000);
+        assert_eq!(table.decode(&runs).copied().collect::<Vec<_>>(), items);
+
+        // Every item keeps the index it was first given
+        assert!(items
+            .iter()
+            .enumerate()
+            .all(|(i, x)| table.index_of(x) == Some(i)));
+        assert!(items
+            .iter()
+            .enumerate()
+            .all(|(i, x)| table.index_of_or_insert(x) == i));
+        assert_eq!(table.len(), 5000);
+
+        let mut sorted = items.clone();
+        sorted.sort();
+        assert!(table.iter_sorted().eq(sorted.iter()));
+        assert_eq!(table.sorted_position(&sorted[100]), Some(100));
+
+        let mut tx = table.transaction();
+        let added: Vec<u32> = (10_000..12_000).collect();
+        tx.extend_from_slice(&added);
+        assert_eq!(tx.index_of(&added[0]), Some(5000));
+        tx.rollback();
+        assert_eq!(table.len(), 5000);
+        assert!(added.iter().all(|x| !table.contains(x)));
+        assert!(table.iter_sorted().eq(sorted.iter()));
+
+        table.retain(|&x| x % 2 == 0);
+        assert!(table
+            .iter_sorted()
+            .eq(sorted.iter().filter(|&&x| x % 2 == 0)));
+        assert!(table
+            .iter()
+            .enumerate()
+            .all(|(i, x)| table.index_of(x) == Some(i)));
+
+        // Evicting items replaces them in place
+        let mut bounded = BoundedTable::new(100, EvictionPolicy::Lru);
+        assert!(bounded.encode_bytes(&added).count() > added.len());
+        let table = bounded.table();
+        assert_eq!(table.len(), 100);
+        assert!(table
+            .iter()
+            .enumerate()
+            .all(|(i, x)| table.index_of(x) == Some(i)));
+        assert!(table.iter_sorted().eq(added[1900..].iter()));
+    }
+
+    #[test]
+    fn bitmap_words() {
+        let mut bools = vec![false; 3];
+        bools.extend(vec![true; 70]);
+        bools.extend([false, true, true, false]);
+        let mut words = vec![0u64; 2];
+        for (i, _) in bools.iter().enumerate().filter(|(_, &b)| b) {
+            words[i / 64] |= 1 << (i % 64);
+        }
+        assert_eq!(words, [!0b111, 0b1101_1111_1111]);
+
+        let expected = [(false, 3), (true, 70), (false, 1), (true, 2), (false, 1)];
+        assert_eq!(bitmap::encode_bools(&bools), expected);
+        assert_eq!(bitmap::encode_words(&words, bools.len()), expected);
+        assert_eq!(bitmap::decode_bools(&expected), bools);
+        assert_eq!(bitmap::decode_words(&expected), words);
+
+        // Bits past `len` are ignored
+        assert_eq!(bitmap::encode_words(&[!0, !0], 70), [(true, 70)]);
+        assert_eq!(bitmap::encode_words(&[0], 0), []);
+    }
+
+    #[test]
+    fn rle_bitset_ops() {
+        let bools = |str: &str| str.chars().map(|c| c == '1').collect::<Vec<bool>>();
+        let a = bools("0011110000111111110001");
+        let b = bools("0101010111100011");
+        let (sa, sb) = (RleBitSet::from_bools(&a), RleBitSet::from_bools(&b));
+        assert_eq!(sa.to_bools(), a);
+        assert_eq!(sa.count_ones(), 13);
+        assert!((0..30).all(|i| sa.contains(i) == a.get(i).copied().unwrap_or(false)));
+
+        let bit = |v: &[bool], i: usize| v.get(i).copied().unwrap_or(false);
+        let check = |set: RleBitSet, op: fn(bool, bool) -> bool| {
+            let expected: Vec<bool> = (0..a.len()).map(|i| op(bit(&a, i), bit(&b, i))).collect();
+            assert_eq!(set, RleBitSet::from_bools(&expected));
+        };
+        check(sa.union(&sb), |x, y| x | y);
+        check(sa.intersection(&sb), |x, y| x & y);
+        check(sa.difference(&sb), |x, y| x & !y);
+        check(sa.xor(&sb), |x, y| x ^ y);
+        assert_eq!(
+            sa.intersection(&sb),
+            RleBitSet::from_bools(&bools("0001010000100011000000"))
+        );
+
+        assert_eq!(RleBitSet::from_words(&sa.to_words(), a.len()), sa);
+        assert_eq!(sa.xor(&sa), RleBitSet::zeros(a.len()));
+        assert_eq!(RleBitSet::ones(3).runs().collect::<Vec<_>>(), [(true, 3)]);
+    }
+
+    #[test]
+    fn rle_vec_range_queries() {
+        let items: Vec<char> = "AAAABBBCCCCCCAADDDDB".chars().collect();
+        let vec = RleVec::from_slice(&items);
+        assert_eq!(vec.count_in_range(&'A', 0..20), 6);
+        assert_eq!(vec.count_in_range(&'C', 5..10), 3);
+        assert_eq!(vec.count_in_range(&'E', 0..20), 0);
+        for &(start, end) in &[(0, 20), (2, 15), (7, 8), (10, 10), (19, 20)] {
+            for symbol in "ABCDE".chars() {
+                let expected = items[start..end].iter().filter(|&&x| x == symbol).count();
+                assert_eq!(vec.count_in_range(&symbol, start..end), expected);
+            }
+            let decoded: Vec<char> = vec
+                .runs_in_range(start..end)
+                .flat_map(|(ind, len)| std::iter::repeat_n(vec.table()[ind], len))
+                .collect();
+            assert_eq!(decoded, &items[start..end]);
+        }
+        let runs: Vec<_> = vec.runs_in_range(2..9).collect();
+        assert_eq!(runs, [(0, 2), (1, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn rle_vec_rank_select() {
+        let items: Vec<char> = "AABBBACCAB".chars().collect();
+        let mut vec = RleVec::from_slice(&items);
+        assert_eq!(vec.rank(&'A', 6), 3);
+        assert_eq!(vec.rank(&'B', 10), 4);
+        assert_eq!(vec.select(&'A', 3), Some(8));
+        assert_eq!(vec.select(&'C', 2), None);
+        for symbol in "ABCD".chars() {
+            let positions: Vec<usize> = (0..items.len()).filter(|&i| items[i] == symbol).collect();
+            for i in 0..=items.len() {
+                assert_eq!(vec.rank(&symbol, i), positions.partition_point(|&p| p < i));
+            }
+            for k in 0..=positions.len() {
+                assert_eq!(vec.select(&symbol, k), positions.get(k).copied());
+            }
+        }
+
+        // The index is rebuilt after the vector changes
+        vec.insert(0, 'C');
+        assert_eq!(vec.select(&'C', 0), Some(0));
+        assert_eq!(vec.rank(&'C', 1), 1);
+    }
+
+    #[test]
+    fn chunked_decode_range() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 10];
+        items.extend("BBBBBBCCCAB".chars());
+        items.extend(vec!['C'; 9]);
+        let bytes = table.encode_chunked(&items, 8).unwrap();
+        assert_eq!(chunked_len(&bytes).unwrap(), 30);
+        for &(start, end) in &[(0, 30), (0, 1), (7, 9), (8, 16), (12, 27), (29, 30), (5, 5)] {
+            assert_eq!(
+                table.decode_range(&bytes, start..end).unwrap(),
+                &items[start..end]
+            );
+        }
+        assert_eq!(table.decode_range(&bytes, ..).unwrap(), items);
+
+        // A chunk that decodes to the wrong number of items is rejected
+        let mut broken = bytes.clone();
+        broken.pop();
+        assert!(table.decode_range(&broken, 25..).is_err());
+        assert!(matches!(
+            chunked_len(&bytes[..6]),
+            Err(Error::InvalidBytes(4))
+        ));
+    }
+
+    #[test]
+    fn container_crc() {
+        let table = Table::from_slice(&[10u32, 20, 30]);
+        let items = [10, 10, 10, 30, 20, 20, 10];
+        let bytes = table.encode_container_crc(&items).unwrap();
+        let (decoded_table, decoded) = Table::<u32>::decode_container_crc(&bytes).unwrap();
+        assert_eq!(decoded_table.as_ref(), table.as_ref());
+        assert_eq!(decoded, items);
+
+        // Flipping any single bit is detected
+        for i in 0..bytes.len() {
+            let mut corrupt = bytes.clone();
+            corrupt[i] ^= 0x10;
+            assert!(matches!(
+                Table::<u32>::decode_container_crc(&corrupt),
+                Err(Error::ChecksumMismatch(..))
+            ));
+        }
+        assert!(matches!(
+            Table::<u32>::decode_container_crc(&bytes[..3]),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert_eq!(crc::crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn framed_formats() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 300];
+        items.extend("BCBCCCCCAAB".chars());
+        let formats = [
+            ByteFormat::Bytes,
+            ByteFormat::LiteralBlocks,
+            ByteFormat::Varint,
+            ByteFormat::Wide(Width::U8),
+            ByteFormat::Wide(Width::U16),
+            ByteFormat::Wide(Width::U32),
+        ];
+        for &format in &formats {
+            let bytes = table.encode_framed(&items, format).unwrap();
+            assert_eq!(table.decode_framed(&bytes).unwrap(), items);
+            let expected = match format {
+                ByteFormat::Wide(Width::U8) => ByteFormat::Bytes,
+                _ => format,
+            };
+            assert_eq!(framed_format(&bytes).unwrap(), expected);
+        }
+
+        let bytes = table
+            .encode_framed(&items, ByteFormat::Wide(Width::U16))
+            .unwrap();
+        assert!(matches!(
+            table.decode_framed(&bytes[..bytes.len() - 1]),
+            Err(Error::TruncatedStream)
+        ));
+        let mut newer = bytes.clone();
+        newer[4] = BYTE_FORMAT_VERSION + 1;
+        assert!(matches!(
+            table.decode_framed(&newer),
+            Err(Error::UnsupportedVersion(_))
+        ));
+        newer[4] = BYTE_FORMAT_VERSION;
+        newer[5] = 0x80;
+        assert!(matches!(
+            table.decode_framed(&newer),
+            Err(Error::InvalidBytes(5))
+        ));
+        assert!(matches!(
+            table.decode_framed(&bytes[6..]),
+            Err(Error::InvalidBytes(0))
+        ));
+    }
+
+    #[test]
+    fn framed_wide_malformed() {
+        let table: Table<u8> = vec![0, 1].into_iter().collect();
+        let frame = |payload: &[u8]| {
+            let mut bytes = b"RLEB".to_vec();
+            bytes.extend_from_slice(&[BYTE_FORMAT_VERSION, 3]);
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+        assert_eq!(
+            table
+                .decode_framed(&frame(&[0x01, 0x00, 0x00, 0x00, 0x02, 0x00]))
+                .unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            table
+                .decode_framed(&frame(&[0x03, 0x00, 0x03, 0x00]))
+                .unwrap(),
+            vec![1, 1, 1]
+        );
+        assert!(matches!(
+            table.decode_framed(&frame(&[0x04, 0x00])),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_framed(&frame(&[0x01, 0x00, 0xFF])),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_framed(&frame(&[0x00])),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let names: Vec<String> = ["grass", "water", "sand"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let table = Table::from_slice(&names);
+        assert_eq!(table.index_of_by_key("water"), Some(1));
+        assert_eq!(table.index_of(&names[2]), Some(2));
+        assert!(table.contains_by_key("sand"));
+        assert!(!table.contains_by_key("lava"));
+        assert!(table.contains(&names[0]));
+
+        let mut hashed = HashTable::with_store(HashStore::new());
+        hashed.extend_from_slice(&names);
+        assert_eq!(hashed.index_of_by_key("sand"), Some(2));
+
+        let level = ["sand", "sand", "water", "grass", "grass"];
+        let runs = table.encode_borrowed(&level).unwrap();
+        let owned: Vec<String> = level.iter().map(|s| s.to_string()).collect();
+        assert_eq!(runs, table.encode(&owned).unwrap().collect::<Vec<_>>());
+        assert!(matches!(
+            table.encode_borrowed(&["grass", "lava"]),
+            Err(Error::TableMissingItems(1))
+        ));
+
+        #[cfg(feature = "strings")]
+        {
+            let mut strings = Table::with_store(StringStore::<String>::new());
+            strings.extend_from_slice(&names);
+            assert_eq!(strings.index_of_by_key("grass"), Some(0));
+            assert_eq!(strings.index_of_by_key(&Box::<str>::from("water")), Some(1));
+        }
+    }
+
+    #[test]
+    fn interned_items() {
+        let values: Vec<String> = ["grass", "grass", "water", "sand", "sand", "sand", "grass"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let mut interner = Interner::new();
+        let handles = interner.intern_slice(&values);
+        assert_eq!(interner.len(), 3);
+        assert!(handles.iter().zip(&values).all(|(h, v)| **h == *v));
+        assert_eq!(interner.get(&values[0]), Some(handles[0].clone()));
+        assert_eq!(interner.intern(values[5].clone()).id(), handles[3].id());
+        assert_eq!(handles[0].id(), handles[6].id());
+
+        let mut table = Table::default();
+        let runs: Vec<_> = table.encode_mut(&handles).collect();
+        let plain: Vec<_> = Table::default().encode_mut(&values).collect();
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs.len(), plain.len());
+        let decoded: Vec<String> = table.decode(&runs).map(|h| (**h).clone()).collect();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn table_free_runs() {
+        let bytes = b"AAAABCCCCCCAAZ";
+        let expected = [(b'A', 4), (b'B', 1), (b'C', 6), (b'A', 2), (b'Z', 1)];
+        assert_eq!(runs_of(bytes).collect::<Vec<_>>(), expected);
+        assert_eq!(bytes.iter().copied().runs().collect::<Vec<_>>(), expected);
+        let items: Vec<u32> = bytes.iter().map(|&x| x as u32).collect();
+        assert!(runs_of_slice(&items)
+            .map(|(&x, len)| (x as u8, len))
+            .eq(expected.iter().copied()));
+        assert_eq!(runs_of(&[]).next(), None);
+    }
+
+    #[test]
+    fn encode_option_profiles() {
+        let table: Table<u32> = (0..100).collect();
+        let mut items = vec![99; 300];
+        items.extend([0, 1, 2, 2, 2, 50]);
+        items.extend(vec![7; 129]);
+        let plain = table
+            .encode_bytes_with(&items, EncodeOptions::new())
+            .unwrap();
+        assert_eq!(
+            plain,
+            table.encode_bytes(&items).unwrap().collect::<Vec<u8>>()
+        );
+
+        for &max_run in &[1, 2, 63, 127, 128, 255] {
+            for &header in &[RunHeader::Flagged, RunHeader::Pair] {
+                let options = EncodeOptions::new().max_run(max_run).header(header);
+                let bytes = table.encode_bytes_with(&items, options).unwrap();
+                assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), items);
+                if header == RunHeader::Pair {
+                    assert!(bytes.chunks(2).all(|pair| pair[1] as usize <= max_run));
+                }
+            }
+        }
+
+        let big: Table<u32> = (0..200).collect();
+        let pair = EncodeOptions::new().header(RunHeader::Pair);
+        assert!(big.encode_bytes_with(&[199, 199], pair).is_ok());
+        assert!(matches!(
+            big.encode_bytes_with(&[199], EncodeOptions::new()),
+            Err(Error::TableTooLarge(200))
+        ));
+        assert!(matches!(
+            big.decode_bytes_with(&[0, 0], pair),
+            Err(Error::InvalidBytes(0))
+        ));
+    }
+
+    #[test]
+    fn encode_fallible_source() {
+        let table = Table::from_slice(&[1, 2, 3]);
+        let source = vec![Ok(1), Ok(1), Ok(9), Ok(2), Ok(2), Err("bad"), Ok(3)];
+        let runs: Vec<_> = table.encode_try_iter(source).collect();
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[0].as_ref().unwrap(), &(0, 2));
+        assert!(matches!(
+            runs[1],
+            Err(EncodeError::Encode(Error::TableMissingItems(2)))
+        ));
+        assert_eq!(runs[2].as_ref().unwrap(), &(1, 2));
+        assert!(matches!(runs[3], Err(EncodeError::Source("bad"))));
+
+        let ok: Vec<Result<u32, ()>> = vec![Ok(3), Ok(3), Ok(1)];
+        let runs: Result<Vec<_>, _> = table.encode_try_iter(ok).collect();
+        assert_eq!(runs.unwrap(), [(2, 2), (0, 1)]);
+
+        let first_err: Vec<Result<&u32, &str>> = vec![Err("empty")];
+        let mut runs = table.encode_try_iter(first_err);
+        assert!(matches!(
+            runs.next(),
+            Some(Err(EncodeError::Source("empty")))
+        ));
+        assert!(runs.next().is_none());
+    }
+
+    // The sorted-order cache inside the store never affects hashing.
+    #[allow(clippy::mutable_key_type)]
+    #[test]
+    fn table_equality() {
+        use std::collections::HashSet;
+        let a = Table::from_slice(&['A', 'B', 'C']);
+        let b: Table<char> = "ABC".chars().collect();
+        let c = Table::from_slice(&['C', 'B', 'A']);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, Table::from_slice(&['A', 'B']));
+
+        let mut hashed = HashTable::with_store(HashStore::new());
+        hashed.extend_from_slice(&['A', 'B', 'C']);
+        assert!(a == hashed);
+
+        let set: HashSet<Table<char>> = vec![a.clone(), b, c].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+    }
+
+    #[test]
+    fn parse_runs() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let runs = [(0, 5), (1, 10), (2, 3), (0, 10)];
+        let str = table.display_runs(&runs).to_string();
+        assert_eq!(table.parse_runs(&str).unwrap(), runs);
+        assert_eq!(table.parse_runs("  \n").unwrap(), vec![]);
+        assert!(matches!(
+            table.parse_runs("5A B"),
+            Err(Error::InvalidRunText(3))
+        ));
+        assert!(matches!(
+            table.parse_runs("5A 3"),
+            Err(Error::InvalidRunText(3))
+        ));
+        assert!(matches!(
+            table.parse_runs("5A 3BB"),
+            Err(Error::InvalidRunText(3))
+        ));
+        assert!(matches!(
+            table.parse_runs("5A 3D"),
+            Err(Error::TableMissingItems(1))
+        ));
+
+        let mut table: Table<String> = Table::default();
+        let runs = table.parse_runs_mut("2grass 1water 3grass").unwrap();
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.display_runs(&runs).to_string(),
+            "2grass 1water 3grass"
+        );
+    }
+
+    #[test]
+    fn planar_channels() {
+        let red = [1, 1, 1, 2, 2, 3];
+        let alpha = [9, 9, 9, 9, 0, 0];
+        let pixels: Vec<u32> = red
+            .iter()
+            .zip(&alpha)
+            .flat_map(|(&r, &a)| [r, 0, 0, a])
+            .collect();
+
+        let mut tables = vec![Table::default(); 4];
+        let planes = planar::encode(&mut tables, &pixels);
+        assert_eq!(planes[0], [(0, 3), (1, 2), (2, 1)]);
+        assert_eq!(planes[1], [(0, 6)]);
+        assert_eq!(planes[3], [(0, 4), (1, 2)]);
+        assert_eq!(planar::decode(&tables, &planes).unwrap(), pixels);
+
+        let mut byte_tables = vec![Table::default(); 4];
+        let bytes = planar::encode_bytes(&mut byte_tables, &pixels).unwrap();
+        assert_eq!(planar::decode_bytes(&byte_tables, &bytes).unwrap(), pixels);
+
+        let mut short = planes.clone();
+        short[2] = vec![(0, 5)];
+        assert!(matches!(
+            planar::decode(&tables, &short),
+            Err(Error::PlaneLengthMismatch(2, 5))
+        ));
+        assert!(matches!(
+            planar::decode(&tables, &planes[..3]),
+            Err(Error::PlaneCountMismatch(3, 4))
+        ));
+        assert!(matches!(
+            planar::decode_bytes(&byte_tables, &bytes[..3]),
+            Err(Error::PlaneCountMismatch(3, 4))
+        ));
+
+        // A channel with too many items leaves every table untouched
+        let mut tables = vec![Table::default(); 2];
+        let items: Vec<u32> = (0..128).flat_map(|i| [0, i]).collect();
+        assert!(matches!(
+            planar::encode_bytes(&mut tables, &items),
+            Err(Error::TableTooLarge(128))
+        ));
+        assert!(tables.iter().all(Table::is_empty));
+    }
+
+    #[test]
+    fn persistent_rle_versions() {
+        let chars = |str: &str| str.chars().collect::<Vec<char>>();
+        let v0 = PersistentRle::from_slice(&chars("AABBBC"));
+        let v1 = v0.set(0, 'B');
+        let v2 = v1.insert(6, 'C');
+        let v3 = v2.remove(1);
+        let v4 = v3.splice(2..4, "AA".chars());
+        let v5 = v4.splice(.., std::iter::empty());
+
+        // Every earlier version is left unchanged
+        for (rle, str, runs) in [
+            (&v0, "AABBBC", 3),
+            (&v1, "BABBBC", 4),
+            (&v2, "BABBBCC", 4),
+            (&v3, "BBBBCC", 2),
+            (&v4, "BBAACC", 3),
+            (&v5, "", 0),
+        ] {
+            assert_eq!(rle.to_vec(), chars(str));
+            assert_eq!(rle.len(), str.len());
+            assert_eq!(rle.run_count(), runs);
+            assert_eq!(rle.runs().count(), runs);
+            assert_eq!(rle.get(2), str.chars().nth(2).as_ref());
+        }
+        let runs: Vec<_> = v4.runs().collect();
+        assert_eq!(runs, [(&'B', 2), (&'A', 2), (&'C', 2)]);
+        assert_eq!(v3, PersistentRle::from_slice(&chars("BBBBCC")));
+    }
+
+    #[test]
+    fn fill_range() {
+        let items: Vec<char> = "AAAABBBBCCCC".chars().collect();
+        let mut vec = RleVec::from_slice(&items);
+        let mut persistent = PersistentRle::from_slice(&items);
+        for &(start, end, item, expected, runs) in &[
+            (2, 6, 'C', "AACCCCBBCCCC", 4),
+            (6, 8, 'C', "AACCCCCCCCCC", 2),
+            (3, 3, 'D', "AACCCCCCCCCC", 2),
+            (10, 12, 'D', "AACCCCCCCCDD", 3),
+            (0, 12, 'B', "BBBBBBBBBBBB", 1),
+        ] {
+            let expected: Vec<char> = expected.chars().collect();
+            vec.fill_range(start..end, item);
+            persistent = persistent.fill_range(start..end, item);
+            assert_eq!(vec.to_vec(), expected);
+            assert_eq!(vec.runs().len(), runs);
+            assert_eq!(persistent.to_vec(), expected);
+            assert_eq!(persistent.run_count(), runs);
+        }
+    }
+
+    #[test]
+    fn decode_runs() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AAAABBBCCA".chars().collect();
+        let expected = [(&'A', 4), (&'B', 3), (&'C', 2), (&'A', 1)];
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        assert_eq!(table.decode_runs(&runs).collect::<Vec<_>>(), expected);
+
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert_eq!(
+            table.decode_bytes_runs(&bytes).collect::<Vec<_>>(),
+            expected
+        );
+
+        // Runs longer than a byte can hold come back split
+        let bytes: Vec<u8> = table.encode_bytes(&['B'; 130]).unwrap().collect();
+        assert_eq!(
+            table.decode_bytes_runs(&bytes).collect::<Vec<_>>(),
+            [(&'B', 127), (&'B', 3)]
+        );
+
+        assert_eq!(
+            table.decode_runs(&[(0, 2usize), (99, 1), (0, 1)]).count(),
+            1
+        );
+        assert_eq!(table.decode_bytes_runs(&[0x00, 0x01]).count(), 1);
+    }
+
+    #[test]
+    fn encode_session() {
+        let chunks = [vec![1u32, 1, 2, 2, 1], vec![3, 3, 3, 1], vec![4, 4, 4, 4]];
+        let mut session = EncodeSession::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let id = session.encode(chunk).unwrap();
+            assert_eq!(id, i);
+            assert_eq!(session.decode(id).as_ref(), Some(chunk));
+        }
+        assert_eq!(session.len(), 3);
+        assert_eq!(session.table().as_ref(), [1, 2, 3, 4]);
+
+        let bytes = session.to_bytes();
+        let loaded = EncodeSession::<u32>::from_bytes(&bytes).unwrap();
+        for (id, chunk) in chunks.iter().enumerate() {
+            assert_eq!(loaded.decode(id).as_ref(), Some(chunk));
+            assert_eq!(loaded.used(id), session.used(id));
+        }
+        assert!(EncodeSession::<u32>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let too_many: Vec<u32> = (0..200).collect();
+        assert!(matches!(
+            session.encode(&too_many),
+            Err(Error::TableTooLarge(128))
+        ));
+        assert_eq!(session.table().len(), 4);
+        assert_eq!(session.len(), 3);
+    }
+
+    #[test]
+    fn encode_fallback() {
+        let old = Table::from_slice(&[0u32, 1, 2]);
+        let items = [0, 0, 3, 1, 1, 4, 4, 2, 0];
+        let (runs, substituted) = old.encode_fallback(&items, 0).unwrap();
+        let decoded: Vec<u32> = old.decode(&runs).copied().collect();
+        assert_eq!(decoded, [0, 0, 0, 1, 1, 0, 0, 2, 0]);
+        assert_eq!(substituted, [2..3, 5..7]);
+        assert_eq!(runs, [(0, 3), (1, 2), (0, 2), (2, 1), (0, 1)]);
+
+        let options = EncodeOptions::new().fallback(0);
+        for options in [
+            options,
+            options.literal_blocks(),
+            options.header(RunHeader::Pair),
+        ] {
+            let bytes = old.encode_bytes_with(&items, options).unwrap();
+            assert_eq!(old.decode_bytes_with(&bytes, options).unwrap(), decoded);
+        }
+        assert!(matches!(
+            old.encode_bytes_with(&items, EncodeOptions::new()),
+            Err(Error::TableMissingItems(2))
+        ));
+        assert!(matches!(
+            old.encode_fallback(&items, 3),
+            Err(Error::UnknownIndex(3))
+        ));
+    }
+
+    #[test]
+    fn encode_by_tolerance() {
+        let colors: Vec<u8> = (0..300u32).map(|i| ((i / 50) * 40 + i % 3) as u8).collect();
+        let mut table = Table::default();
+        let runs = table.encode_mut_by(&colors, |a, b| a.abs_diff(*b) <= 2);
+        assert_eq!(runs.len(), 6);
+        assert!(runs.iter().all(|&(_, len)| len == 50));
+        assert_eq!(table.len(), 6);
+        assert_eq!(
+            table
+                .encode_by(&colors, |a, b| a.abs_diff(*b) <= 2)
+                .unwrap(),
+            runs
+        );
+        assert!(matches!(
+            table.encode_by(&[255], |a, b| a == b),
+            Err(Error::TableMissingItems(0))
+        ));
+        assert_eq!(table.encode_by(&[] as &[u8], |a, b| a == b).unwrap(), []);
+    }
+
+    #[test]
+    fn total_floats() {
+        let heights = [0.5f32, 0.5, 0.5, -0.0, 0.0, f32::NAN, f32::NAN, 1.25];
+        let items = TotalF32::from_slice(&heights);
+        let table: Table<TotalF32> = items.iter().copied().collect();
+        assert_eq!(table.len(), 5);
+        let runs: Vec<_> = table.encode(items).unwrap().collect();
+        assert_eq!(
+            runs.iter().map(|r| r.1).collect::<Vec<_>>(),
+            [3, 1, 1, 2, 1]
+        );
+        let decoded: Vec<TotalF32> = table.decode(&runs).copied().collect();
+        let decoded = TotalF32::as_floats(&decoded);
+        assert!(decoded
+            .iter()
+            .zip(&heights)
+            .all(|(a, b)| a.to_bits() == b.to_bits()));
+        assert_eq!(table.get_sorted(0), Some(&TotalF32(-0.0)));
+        assert!(table.get_sorted(4).unwrap().0.is_nan());
+
+        let samples: Vec<TotalF64> = [1.0, 1.0, 2.5].iter().map(|&x| TotalF64(x)).collect();
+        let table = Table::from_slice(&samples);
+        let bytes = table.encode_container(&samples).unwrap();
+        assert_eq!(
+            Table::<TotalF64>::decode_container(&bytes).unwrap().1,
+            samples
+        );
+    }
+
+    #[test]
+    fn encoded_size_matches_output() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let mut items = vec!['A'; 300];
+        items.extend("BCBCCAA".chars());
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert_eq!(table.encoded_size(&items).unwrap(), bytes.len());
+
+        let options = EncodeOptions::new();
+        for options in [
+            options,
+            options.max_run(1),
+            options.max_run(2),
+            options.max_run(255),
+            options.header(RunHeader::Pair),
+            options.header(RunHeader::Pair).max_run(9),
+            options.literal_blocks(),
+        ] {
+            let bytes = table.encode_bytes_with(&items, options).unwrap();
+            assert_eq!(
+                table.encoded_size_with(&items, options).unwrap(),
+                bytes.len()
+            );
+        }
+
+        let partial = Table::from_slice(&['A', 'B']);
+        assert!(matches!(
+            partial.encoded_size(&items),
+            Err(Error::TableMissingItems(301))
+        ));
+        let options = EncodeOptions::new().fallback(0);
+        let bytes = partial.encode_bytes_with(&items, options).unwrap();
+        assert_eq!(
+            partial.encoded_size_with(&items, options).unwrap(),
+            bytes.len()
+        );
+    }
+
+    #[test]
+    fn frozen_table_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenTable<u32>>();
+        assert_send_sync::<FrozenTable<u32, HashStore<u32>>>();
+
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AAAABBBBCCCCAAAA".chars().collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        let frozen = table.freeze();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let frozen = frozen.clone();
+                let (bytes, items) = (&bytes, &items);
+                scope.spawn(move || {
+                    let decoded: Vec<char> = frozen.decode_bytes(bytes).copied().collect();
+                    assert_eq!(&decoded, items);
+                });
+            }
+        });
+
+        let shared = frozen.clone();
+        assert!(shared.ptr_eq(&frozen));
+        let mut thawed = shared.thaw();
+        thawed.insert('D');
+        assert_eq!(frozen.len(), 3);
+        assert_eq!(frozen.thaw().len(), 3);
+    }
+
+    #[test]
+    fn search_bytes() {
+        let table = Table::from_slice(&['A', 'B', 'C', 'D']);
+        let items: Vec<char> = "AAABBBBBCCAAB".chars().collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert_eq!(find_symbol(&bytes, 0).unwrap(), Some(0));
+        assert_eq!(find_symbol(&bytes, 1).unwrap(), Some(3));
+        assert_eq!(find_symbol(&bytes, 2).unwrap(), Some(8));
+        assert_eq!(find_symbol(&bytes, 3).unwrap(), None);
+        assert_eq!(find_symbol(&bytes, 9).unwrap(), None);
+
+        assert_eq!(find_run_at(&bytes, 0).unwrap(), Some((0, 0..3)));
+        assert_eq!(find_run_at(&bytes, 4).unwrap(), Some((1, 3..8)));
+        assert_eq!(find_run_at(&bytes, 10).unwrap(), Some((0, 10..12)));
+        assert_eq!(find_run_at(&bytes, 12).unwrap(), Some((1, 12..13)));
+        assert_eq!(find_run_at(&bytes, items.len()).unwrap(), None);
+        assert!(matches!(
+            find_symbol(&[0x01], 5),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn encoded_equality() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AABBBCAAAC".chars().collect();
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        let mut reversed = Table::from_slice(&['C', 'B', 'A']);
+        let other_runs: Vec<_> = reversed
+            .encode_as::<u8>(&items)
+            .unwrap()
+            .map(|(i, n)| (i, n as usize))
+            .collect();
+        let other_bytes: Vec<u8> = reversed.encode_bytes(&items).unwrap().collect();
+        assert_ne!(runs, other_runs);
+        assert!(runs_eq(&runs, &table, &other_runs, &reversed));
+        assert!(bytes_eq(&bytes, &table, &other_bytes, &reversed));
+
+        let mut changed = items.clone();
+        changed[5] = 'B';
+        let changed_runs: Vec<_> = table.encode(&changed).unwrap().collect();
+        assert!(!runs_eq(&runs, &table, &changed_runs, &table));
+        assert!(!runs_eq(&runs, &table, &runs[..runs.len() - 1], &table));
+        assert!(!bytes_eq(&bytes, &table, &bytes[..bytes.len() - 1], &table));
+
+        reversed.insert('D');
+        let invalid = [(reversed.len() + 1, 1)];
+        assert!(!runs_eq(&invalid, &reversed, &invalid, &reversed));
+        assert!(runs_eq(&[(0, 0)], &table, &[], &reversed));
+    }
+
+    #[test]
+    fn grid_transforms() {
+        let (width, height) = (4, 3);
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AABB\
+                                ABBC\
+                                CCCC"
+            .chars()
+            .collect();
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+        let layout = GridLayout::new(width, height);
+        let decode = |runs: &[(Index, usize)]| table.decode(runs).collect::<String>();
+
+        let flipped = decode(&layout.flip_horizontal(&runs).unwrap());
+        assert_eq!(flipped, "BBAACBBACCCC");
+        let flipped = decode(&layout.flip_vertical(&runs).unwrap());
+        assert_eq!(flipped, "CCCCABBCAABB");
+
+        let rotated_runs = layout.rotate90(&runs).unwrap();
+        assert!(rotated_runs.windows(2).all(|w| w[0].0 != w[1].0));
+        assert_eq!(decode(&rotated_runs), "CAACBACBBCCB");
+
+        let mut turned = runs.clone();
+        let mut turn_layout = layout;
+        for _ in 0..4 {
+            turned = turn_layout.rotate90(&turned).unwrap();
+            turn_layout = GridLayout::new(turn_layout.height, turn_layout.width);
+        }
+        assert_eq!(turned, runs);
+        assert!(matches!(
+            layout.rotate90(&runs[1..]),
+            Err(Error::InvalidGridSize(_))
+        ));
+        assert_eq!(GridLayout::new(0, 0).rotate90(&[]).unwrap(), []);
+    }
+
+    #[test]
+    fn grid_transforms_layout() {
+        // AAB
+        // CCC
+        let columns = GridLayout::new(3, 2).column_major();
+        let runs = [(0, 1), (2, 1), (0, 1), (2, 1), (1, 1), (2, 1)];
+        assert_eq!(
+            columns.flip_horizontal(&runs).unwrap(),
+            [(1, 1), (2, 1), (0, 1), (2, 1), (0, 1), (2, 1)]
+        );
+        assert_eq!(
+            columns.flip_vertical(&runs).unwrap(),
+            [(2, 1), (0, 1), (2, 1), (0, 1), (2, 1), (1, 1)]
+        );
+        assert_eq!(columns.rotate90(&runs).unwrap(), [(2, 3), (0, 2), (1, 1)]);
+
+        // AAA
+        // AAB
+        let lines = GridLayout::new(3, 2).break_lines();
+        let runs = [(0, 3), (0, 2), (1, 1)];
+        let flipped = lines.flip_vertical(&runs).unwrap();
+        assert_eq!(flipped, [(0, 2), (1, 1), (0, 3)]);
+        assert_eq!(
+            lines.flip_horizontal(&flipped).unwrap(),
+            [(1, 1), (0, 2), (0, 3)]
+        );
+        assert_eq!(
+            GridLayout::new(3, 2).flip_horizontal(&flipped).unwrap(),
+            [(1, 1), (0, 5)]
+        );
+        assert_eq!(
+            lines.rotate90(&runs).unwrap(),
+            [(0, 2), (0, 2), (1, 1), (0, 1)]
+        );
+
+        assert!(matches!(
+            lines.flip_vertical(&[(0, usize::MAX), (0, 7)]),
+            Err(Error::InvalidGridSize(usize::MAX))
+        ));
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_encoded_bytes() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let mut seed = 41u32;
+        let data: Vec<u8> = (0..8192)
+            .map(|_| {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        let mut u = Unstructured::new(&data);
+        let mut tables = 0;
+        while !u.is_empty() {
+            let table = Table::<u16>::arbitrary(&mut u).unwrap();
+            tables += !table.is_empty() as usize;
+            let encoded = EncodedBytes::<u16>::arbitrary(&mut u).unwrap();
+            assert!(encoded.table.len() <= 127);
+            let mut items = Vec::new();
+            encoded
+                .table
+                .decode_bytes_into(&encoded.bytes, &mut items)
+                .unwrap();
+            let (table, decoded) = Table::<u16>::decode_container(&encoded.to_container()).unwrap();
+            assert_eq!(table, encoded.table);
+            assert_eq!(decoded, items);
+        }
+        assert!(tables > 0);
+    }
+
+    #[test]
+    fn const_table_matches_table() {
+        let table = Table::from_slice(&[5u32, 9, 2]);
+        let mut items = vec![5, 5, 5, 2, 9, 9, 5];
+        items.extend(vec![9; 200]);
+        let mut fixed: ConstTable<u32, 127> = ConstTable::new();
+        for &item in table.iter() {
+            fixed.insert(item).unwrap();
+        }
+        assert_eq!(fixed.as_slice(), table.as_ref());
+        assert!(table
+            .iter()
+            .enumerate()
+            .all(|(i, x)| fixed.index_of(x) == Some(i)));
+
+        let expected: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        let mut bytes = vec![0; expected.len()];
+        assert_eq!(
+            fixed.encode_bytes_to_slice(&items, &mut bytes).unwrap(),
+            expected.len()
+        );
+        assert_eq!(bytes, expected);
+        assert!(matches!(
+            fixed.encode_bytes_to_slice(&items, &mut bytes[1..]),
+            Err(Error::BufferTooSmall(n)) if n == expected.len()
+        ));
+
+        let mut decoded = vec![0; items.len()];
+        assert_eq!(
+            fixed.decode_bytes_to_slice(&bytes, &mut decoded).unwrap(),
+            items.len()
+        );
+        assert_eq!(decoded, items);
+
+        let mut small: ConstTable<u32, 2> = ConstTable::from_slice(&[5, 5, 1]).unwrap();
+        assert!(matches!(small.insert(9), Err(Error::TableTooLarge(3))));
+        assert!(matches!(
+            small.encode_bytes_to_slice(&[9], &mut []),
+            Err(Error::TableMissingItems(0))
+        ));
+        assert!(matches!(
+            small.decode_bytes_to_slice(&[0x04], &mut [0]),
+            Err(Error::UnknownIndex(2))
+        ));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mapped_bytes_decode() {
+        use std::sync::Arc;
+
+        let table = Table::from_slice(&[1u32, 2, 3]);
+        let mut items = vec![1; 5000];
+        items.extend([2, 3, 3]);
+        items.extend(vec![2; 5000]);
+        let path = std::env::temp_dir().join(format!("rle-mmap-test-{}.rle", std::process::id()));
+        std::fs::write(&path, table.encode_chunked(&items, 256).unwrap()).unwrap();
+
+        // SAFETY: the file isn't written to again until the map is dropped
+        let bytes = unsafe { MappedBytes::open(&path) }.unwrap();
+        assert_eq!(chunked_len(&bytes).unwrap(), items.len());
+        assert_eq!(table.decode_range(&bytes, 5000..5003).unwrap(), [2, 3, 3]);
+        drop(bytes);
+
+        std::fs::write(
+            &path,
+            table.encode_bytes(&items).unwrap().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        // SAFETY: the file isn't written to while the decoder is alive
+        let decoder = unsafe { BytesDecoderOwned::open(Arc::new(table), &path) }.unwrap();
+        assert!(decoder.eq(items.iter().copied()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn encode_with_offsets() {
+        let table = Table::from_slice(&['A', 'B', 'C']);
+        let items: Vec<char> = "AAABCCCCB".chars().collect();
+        let runs: Vec<_> = table.encode_with_offsets(&items).unwrap().collect();
+        assert_eq!(runs, [(0, 3, 0), (1, 1, 3), (2, 4, 4), (1, 1, 8)]);
+        assert!(runs
+            .iter()
+            .map(|&(ind, len, _)| (ind, len))
+            .eq(table.encode(&items).unwrap()));
+        assert_eq!(table.encode_with_offsets(&[]).unwrap().count(), 0);
+        assert!(table.encode_with_offsets(&['X']).is_err());
+    }
+
+    #[test]
+    fn encode_sparse() {
+        let table: Table<u32> = (0..6).collect();
+        let mut items = vec![0; 2000];
+        items[..3].copy_from_slice(&[1, 1, 2]);
+        items[1000..1004].copy_from_slice(&[3, 3, 3, 5]);
+        items[1999] = 4;
+        for options in [
+            EncodeOptions::new().sparse(0),
+            EncodeOptions::new()
+                .sparse(0)
+                .header(RunHeader::Pair)
+                .max_run(10),
+        ] {
+            let bytes = table.encode_bytes_with(&items, options).unwrap();
+            assert!(
+                bytes.len()
+                    < table
+                        .encode_bytes_with(
+                            &items,
+                            EncodeOptions {
+                                sparse: None,
+                                ..options
+                            }
+                        )
+                        .unwrap()
+                        .len()
+            );
+            assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), items);
+            assert!(matches!(
+                table.decode_bytes_with(&bytes[..bytes.len() - 1], options),
+                Err(Error::TruncatedStream)
+            ));
+        }
+
+        let options = EncodeOptions::new().sparse(0);
+        assert!(table.encode_bytes_with(&[], options).unwrap().is_empty());
+        let empty = vec![0; 300];
+        let bytes = table.encode_bytes_with(&empty, options).unwrap();
+        assert_eq!(bytes, [0xAC, 0x02, 0x00]);
+        assert_eq!(table.decode_bytes_with(&bytes, options).unwrap(), empty);
+        assert!(matches!(
+            table.encode_bytes_with(&empty, EncodeOptions::new().sparse(6)),
+            Err(Error::UnknownIndex(6))
+        ));
+    }
+
+    #[test]
+    fn sparse_malformed() {
+        let table: Table<u8> = vec![0, 1].into_iter().collect();
+        let options = EncodeOptions::new().sparse(0);
+        let mut huge_gap = vec![0xFF; 8];
+        huge_gap.extend_from_slice(&[0x7F, 0x00]);
+        assert!(matches!(
+            table.decode_bytes_with(&huge_gap, options),
+            Err(Error::InvalidBytes(0))
+        ));
+        let mut huge_span = vec![0x00];
+        huge_span.extend_from_slice(&[0xFF; 9]);
+        huge_span.push(0x01);
+        assert!(matches!(
+            table.decode_bytes_with(&huge_span, options),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x01, 0x05, 0x02], options),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x01, 0x01, 0x04], options),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x00], options),
+            Err(Error::TruncatedStream)
+        ));
+        assert_eq!(
+            table
+                .decode_bytes_with(&[0x01, 0x03, 0x01, 0x00, 0x02], options)
+                .unwrap(),
+            vec![0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn table_transaction() {
+        let items: Vec<char> = "AABBCCDDA".chars().collect();
+        let mut table = Table::from_slice(&['A', 'B']);
+        let before = table.clone();
+
+        let mut tx = table.transaction();
+        assert!(tx
+            .encode_bytes_mut(&items)
+            .unwrap()
+            .all(|byte| byte.is_ok()));
+        assert_eq!(tx.added(), ['C', 'D']);
+        tx.rollback();
+        assert_eq!(table, before);
+        assert!(!table.contains(&'C'));
+
+        let mut tx = table.transaction();
+        let bytes: Vec<u8> = tx
+            .encode_bytes_mut(&items)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        tx.commit();
+        assert_eq!(table.as_ref(), ['A', 'B', 'C', 'D']);
+        assert_eq!(
+            table.decode_bytes(&bytes).copied().collect::<Vec<_>>(),
+            items
+        );
+    }
+
+    #[test]
+    fn zip_decode_layers() {
+        let chars = |str: &str| str.chars().collect::<Vec<char>>();
+        let (tile_items, solid_items, zone_items) =
+            (chars("AAABBBBCCD"), chars("XXXXXXOOOO"), chars("112222223"));
+        let tiles = Table::from_slice(&['A', 'B', 'C', 'D']);
+        let solid = Table::from_slice(&['X', 'O']);
+        let zones = Table::from_slice(&['1', '2', '3']);
+        let tile_bytes: Vec<u8> = tiles.encode_bytes(&tile_items).unwrap().collect();
+        let solid_runs: Vec<_> = solid.encode(&solid_items).unwrap().collect();
+        let zone_runs: Vec<_> = zones.encode(&zone_items).unwrap().collect();
+        let layers = || {
+            (
+                tiles.decode_bytes_runs(&tile_bytes),
+                solid.decode_runs(&solid_runs),
+                zones.decode_runs(&zone_runs),
+            )
+        };
+
+        // Decoding stops at the end of the shortest layer
+        let expected: Vec<_> = tile_items
+            .iter()
+            .zip(&solid_items)
+            .zip(&zone_items)
+            .map(|((a, b), c)| (a, b, c))
+            .collect();
+        assert_eq!(zip_decode(layers()).collect::<Vec<_>>(), expected);
+        assert!(zip_decode(layers())
+            .step_by(4)
+            .eq(expected.iter().copied().step_by(4)));
+
+        let mut decoder = zip_decode(layers());
+        assert_eq!(decoder.nth(8), Some((&'C', &'O', &'3')));
+        assert_eq!(decoder.next(), None);
+
+        let runs: Vec<_> = zip_runs(layers()).collect();
+        assert_eq!(
+            runs.iter().map(|&(_, len)| len).collect::<Vec<_>>(),
+            [2, 1, 3, 1, 1, 1]
+        );
+        let mut start = 0;
+        for (items, len) in runs {
+            assert!(expected[start..start + len].iter().all(|x| *x == items));
+            start += len;
+        }
+        assert_eq!(start, 9);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv_archived_table() {
+        use rkyv::rancor::Error as RkyvError;
+        use rkyv::Archived;
+
+        let table = Table::from_slice(&[10u32, 20, 30]);
+        let mut items = vec![10; 200];
+        items.extend([30, 20, 20, 30, 30, 30]);
+        let runs: Vec<Run> = table.encode_runs(&items).unwrap().collect();
+        let short_runs: Vec<Run<u8>> = table
+            .encode_as::<u8>(&items)
+            .unwrap()
+            .map(Run::from)
+            .collect();
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+
+        let table_bytes = rkyv::to_bytes::<RkyvError>(&table).unwrap();
+        let archived = rkyv::access::<ArchivedTable<u32>, RkyvError>(&table_bytes).unwrap();
+        assert_eq!(archived.len(), table.len());
+        for (i, item) in table.iter().enumerate() {
+            assert_eq!(archived.index_of(&(*item).into()), Some(i));
+        }
+        assert_eq!(archived.index_of(&u32::MAX.into()), None);
+        let decoded: Vec<u32> = archived
+            .decode_bytes(&bytes)
+            .map(|x| x.to_native())
+            .collect();
+        assert_eq!(decoded, items);
+
+        let run_bytes = rkyv::to_bytes::<RkyvError>(&runs).unwrap();
+        let archived_runs = rkyv::access::<Archived<Vec<Run>>, RkyvError>(&run_bytes).unwrap();
+        assert!(archived
+            .decode(archived_runs)
+            .map(|x| x.to_native())
+            .eq(items.iter().copied()));
+        let short_bytes = rkyv::to_bytes::<RkyvError>(&short_runs).unwrap();
+        let archived_short =
+            rkyv::access::<Archived<Vec<Run<u8>>>, RkyvError>(&short_bytes).unwrap();
+        assert!(table.decode_as(archived_short).eq(items.iter()));
+
+        let restored: Table<u32> = rkyv::deserialize::<_, RkyvError>(archived).unwrap();
+        assert_eq!(restored, table);
+        assert!(rkyv::access::<ArchivedTable<u32>, RkyvError>(&table_bytes[1..]).is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_run_arrays() {
+        use arrow_array::types::{Int16Type, Int32Type, Int64Type};
+        use arrow_array::{Array, Int32Array, RunArray, StringArray};
+
+        let table = Table::from_slice(&[10u32, 20, 30, 40]);
+        let mut items = vec![10; 40_000];
+        items.extend([20, 30, 30]);
+        items.extend(vec![40; 100]);
+        let runs: Vec<_> = table.encode(&items).unwrap().collect();
+
+        let array: RunArray<Int32Type> = table.to_run_array(&runs).unwrap();
+        assert_eq!(array.len(), items.len());
+        let (imported, imported_runs) = Table::<u32>::from_run_array(&array).unwrap();
+        assert!(imported.decode(&imported_runs).eq(items.iter()));
+
+        let sliced = array.slice(1234, 20_000);
+        let sliced = sliced
+            .as_any()
+            .downcast_ref::<RunArray<Int32Type>>()
+            .unwrap();
+        let sliced_runs = imported.clone().import_run_array(sliced).unwrap();
+        assert!(imported.decode(&sliced_runs).eq(&items[1234..21_234]));
+
+        assert!(matches!(
+            table.to_run_array::<Int16Type, _>(&runs),
+            Err(Error::RunTooLong(_, end)) if end > i16::MAX as usize
+        ));
+        assert!(matches!(
+            table.to_run_array::<Int64Type, _>(&[(4, 1usize)]),
+            Err(Error::UnknownIndex(4))
+        ));
+        assert!(matches!(
+            table.to_run_array::<Int64Type, _>(&[(0, usize::MAX), (0, 1)]),
+            Err(Error::RunTooLong(_, _))
+        ));
+        assert!(matches!(
+            table.to_run_array::<Int64Type, _>(&[(0, 1), (0, usize::MAX)]),
+            Err(Error::RunTooLong(1, usize::MAX))
+        ));
+        assert!(matches!(
+            Table::<String>::from_run_array(&array),
+            Err(Error::InvalidArray)
+        ));
+
+        let names = StringArray::from(vec![Some("grass"), None]);
+        let ends = Int32Array::from(vec![3, 5]);
+        let array = RunArray::<Int32Type>::try_new(&ends, &names).unwrap();
+        assert!(matches!(
+            Table::<String>::from_run_array(&array),
+            Err(Error::InvalidArray)
+        ));
+
+        let names = Table::from_slice(&["grass".to_string(), "water".to_string()]);
+        let array: RunArray<Int64Type> =
+            names.to_run_array(&[(1, 2usize), (0, 0), (0, 4)]).unwrap();
+        assert_eq!(array.run_ends().values(), [2, 6]);
+        let (table, runs) = Table::<String>::from_run_array(&array).unwrap();
+        assert_eq!(table.as_ref(), ["water", "grass"]);
+        assert_eq!(runs, [(0, 2), (1, 4)]);
+    }
+
+    #[test]
+    fn zero_length_byte() {
+        let table: Table<char> = "AB".chars().collect();
+        let bytes = [0x01, 0x00, 0x02];
+        let expected = vec!['A', 'B'];
+
+        assert_eq!(
+            table.decode_bytes(&bytes).copied().collect::<Vec<_>>(),
+            expected
+        );
+        let mut items = Vec::new();
+        table.decode_bytes_into(&bytes, &mut items).unwrap();
+        assert_eq!(items, expected);
+        let mut slice = ['C'; 4];
+        assert_eq!(table.decode_bytes_to_slice(&bytes, &mut slice).unwrap(), 2);
+        assert_eq!(&slice[..2], &expected[..]);
+
+        let summary = table.validate_bytes(&bytes).unwrap();
+        assert_eq!((summary.items, summary.runs), (2, 2));
+        assert_eq!(slice_bytes(&bytes, 0..2).unwrap(), vec![0x00, 0x02]);
+
+        let mut joined = vec![0x00];
+        append_bytes(&mut joined, &bytes).unwrap();
+        assert_eq!(joined, vec![0x01, 0x02, 0x02]);
+    }
+
+    #[test]
+    fn append_from_leaves_table_on_error() {
+        let mut table: Table<char> = "A".chars().collect();
+        let other: Table<char> = "BC".chars().collect();
+        let mut runs = vec![(0, 2)];
+        assert!(matches!(
+            table.append_runs_from(&mut runs, &other, &[(0, 1), (5, 1)]),
+            Err(Error::UnknownIndex(5))
+        ));
+        assert_eq!(table.len(), 1);
+        assert_eq!(runs, vec![(0, 2)]);
+
+        let mut bytes = vec![0x00];
+        assert!(matches!(
+            table.append_bytes_from(&mut bytes, &other, &[0x02, 0x0A]),
+            Err(Error::UnknownIndex(5))
+        ));
+        assert_eq!(table.len(), 1);
+        assert_eq!(bytes, vec![0x00]);
+
+        let mut full: Table<u8> = (0..127).collect();
+        let other: Table<u8> = std::iter::once(200).collect();
+        assert!(matches!(
+            full.append_bytes_from(&mut bytes, &other, &[0x00]),
+            Err(Error::TableTooLarge(128))
+        ));
+        assert_eq!(full.len(), 127);
+    }
+
+    #[test]
+    fn zero_length_byte_readers() {
+        let table: Table<char> = "AB".chars().collect();
+        let bytes = [0x01, 0x00, 0x02];
+
+        let runs: Vec<_> = table.decode_bytes_runs(&bytes).collect();
+        assert_eq!(runs, vec![(&'A', 1), (&'B', 1)]);
+        assert_eq!(find_symbol(&bytes, 0).unwrap(), Some(0));
+        assert_eq!(find_symbol(&bytes, 1).unwrap(), Some(1));
+        assert_eq!(find_run_at(&bytes, 0).unwrap(), Some((0, 0..1)));
+        assert!(bytes_eq(&bytes, &table, &[0x00, 0x02], &table));
+        assert!(!bytes_eq(&bytes, &table, &[0x02], &table));
+
+        let fixed: ConstTable<char, 2> = ConstTable::from_slice(&['A', 'B']).unwrap();
+        let mut items = ['C'; 2];
+        assert_eq!(fixed.decode_bytes_to_slice(&bytes, &mut items).unwrap(), 2);
+        assert_eq!(items, ['A', 'B']);
+
+        let mut session = EncodeSession::with_table(table.clone());
+        session.encode(&['A', 'B']).unwrap();
+        let mut session_bytes = session.to_bytes();
+        let len = session_bytes.len();
+        session_bytes[len - 6] = 3;
+        session_bytes.truncate(len - 2);
+        session_bytes.extend_from_slice(&bytes);
+        let session = EncodeSession::<char>::from_bytes(&session_bytes).unwrap();
+        assert_eq!(session.used(0).unwrap(), &[0, 1]);
+        assert_eq!(session.decode(0).unwrap(), vec!['A', 'B']);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn zero_length_byte_archived() {
+        use rkyv::rancor::Error as RkyvError;
+
+        let table: Table<u32> = vec![10, 20].into_iter().collect();
+        let table_bytes = rkyv::to_bytes::<RkyvError>(&table).unwrap();
+        let archived = rkyv::access::<ArchivedTable<u32>, RkyvError>(&table_bytes).unwrap();
+        let items: Vec<u32> = archived
+            .decode_bytes(&[0x01, 0x00, 0x02])
+            .map(|item| item.to_native())
+            .collect();
+        assert_eq!(items, vec![10, 20]);
+    }
+
+    #[test]
+    fn chunk_malformed_header() {
+        let table: Table<u8> = vec![0, 1].into_iter().collect();
+        let mut bytes = b"RLEC".to_vec();
+        bytes.extend_from_slice(&[0xFF; 8]);
+        bytes.push(2);
+        assert!(matches!(
+            table.decode_chunk(&bytes),
+            Err(Error::InvalidGridSize(0))
+        ));
+        bytes.extend_from_slice(&[0x00, 0x02]);
+        assert!(matches!(
+            table.decode_chunk(&bytes),
+            Err(Error::InvalidGridSize(2))
+        ));
+        assert!(matches!(
+            table.decode_grid(GridLayout::new(usize::MAX, 2), &[0x00, 0x02]),
+            Err(Error::InvalidGridSize(2))
+        ));
+        assert!(matches!(
+            table.decode_chunk(b"RLEC\x01\x00"),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_chunk(b"RLED\x01\x00\x00\x00\x01\x00\x00\x00\x02\x00"),
+            Err(Error::InvalidBytes(0))
+        ));
+    }
+
+    #[test]
+    fn chunked_malformed_header() {
+        let table: Table<u8> = vec![0, 1].into_iter().collect();
+        let chunked = |chunk_len: u32, len: u32, offsets: &[u32], payload: &[u8]| {
+            let mut bytes = Vec::new();
+            for value in [chunk_len, len].iter().chain(offsets) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+
+        // A chunk length far larger than the payload could hold
+        let bytes = chunked(u32::MAX, 1000, &[0], &[0x00, 0x00]);
+        assert_eq!(bytes.len(), 14);
+        assert!(matches!(
+            table.decode_range(&bytes, 0..1),
+            Err(Error::InvalidBytes(8))
+        ));
+
+        // Offsets past the end of the payload, or going backwards
+        let bytes = chunked(1, 2, &[0, 50], &[0x00, 0x02]);
+        assert!(matches!(chunked_len(&bytes), Err(Error::InvalidBytes(8))));
+        let bytes = chunked(1, 2, &[1, 0], &[0x00, 0x02]);
+        assert!(matches!(chunked_len(&bytes), Err(Error::InvalidBytes(8))));
+        let bytes = chunked(0, 2, &[], &[]);
+        assert!(matches!(chunked_len(&bytes), Err(Error::InvalidBytes(0))));
+
+        let bytes = chunked(1, 2, &[0, 1], &[0x00, 0x02]);
+        assert_eq!(table.decode_range(&bytes, 1..2).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn bits_malformed() {
+        let table = Table::from_slice(&['.', 'X']);
+        let mut bytes = vec![0; 9];
+        bytes.push(0x80);
+        bytes.extend_from_slice(&[0xFF; 9]);
+        assert!(matches!(
+            table.decode_bits(&bytes),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            table.decode_bits(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xFF]),
+            Err(Error::TruncatedStream)
+        ));
+        let one = Table::from_slice(&['.']);
+        assert!(matches!(
+            one.decode_bits(&[0b1100_0000]),
+            Err(Error::UnknownIndex(1))
+        ));
+        assert_eq!(table.decode_bits(&[]).unwrap(), Vec::<char>::new());
+    }
+
+    #[test]
+    fn varint_malformed() {
+        let table: Table<u8> = vec![0, 1].into_iter().collect();
+        let mut bytes = vec![0x01];
+        bytes.extend_from_slice(&[0xFF; 8]);
+        bytes.push(0x7F);
+        assert!(matches!(
+            table.decode_bytes_varint(&bytes),
+            Err(Error::InvalidBytes(0))
+        ));
+        let mut too_wide = vec![0x00, 0x01];
+        too_wide.extend_from_slice(&[0xFF; 10]);
+        too_wide.push(0x01);
+        assert!(matches!(
+            table.decode_bytes_varint(&too_wide),
+            Err(Error::InvalidBytes(1))
+        ));
+        assert!(matches!(
+            table.decode_bytes_varint(&[0x01, 0x80]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_bytes_varint(&[0x04]),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert_eq!(
+            table.decode_bytes_varint(&[0x01, 0x00, 0x02]).unwrap(),
+            vec![0, 1]
+        );
+
+        let runs: Vec<_> = table
+            .decode_bytes_varint_runs(&[0x00, 0x04, 0x00])
+            .collect();
+        assert_eq!(runs.len(), 2);
+        assert!(matches!(runs[1], Err(Error::UnknownIndex(2))));
+    }
+
+    #[test]
+    fn custom_store_text_and_grids() {
+        let mut table = HashTable::with_store(HashStore::new());
+        let lines = table.encode_lines("AAB\nBCC").unwrap();
+        assert_eq!(table.decode_lines(&lines), "AAB\nBCC");
+
+        let input: Vec<char> = "AAAABBCCCC".chars().collect();
+        let bytes = table.encode_scanlines(&input, 5).unwrap();
+        let decoded: String = table.decode_scanlines(&bytes).collect();
+        assert_eq!(decoded, "AAAABBCCCC");
+        let tree = Quadtree::from_scanlines(&table, &bytes, 5).unwrap();
+        assert_eq!(tree.to_scanlines(&table).unwrap(), bytes);
+
+        let mut sender = HashTable::with_store(HashStore::new());
+        let mut receiver = HashTable::with_store(HashStore::new());
+        let inline: Vec<u8> = sender
+            .encode_bytes_inline(&input)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let decoded: Vec<char> = receiver.decode_bytes_mut(&inline).collect();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn runs_follow_store_equality() {
+        // Ordered by the key alone, but equal only if the names match too
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        struct Keyed(u8, &'static str);
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Keyed {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let items = [Keyed(1, "a"), Keyed(1, "b"), Keyed(2, "c")];
+        let table = Table::from_slice(&items);
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.encode(&items).unwrap().collect::<Vec<_>>(),
+            [(0, 2), (1, 1)]
+        );
+        let bytes: Vec<u8> = table.encode_bytes(&items).unwrap().collect();
+        assert_eq!(bytes, [0b001, 2, 0b010]);
+    }
+
+    #[test]
+    fn run_bytes_malformed() {
+        let table: Table<char> = "AB".chars().collect();
+        let unknown = [0x00, 0x04];
+        let truncated = [0x00, 0x03];
+
+        let mut items = Vec::new();
+        assert!(matches!(
+            table.decode_bytes_into(&unknown, &mut items),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_into(&truncated, &mut items),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(items.is_empty());
+
+        let mut slice = ['C'; 4];
+        assert!(matches!(
+            table.decode_bytes_to_slice(&unknown, &mut slice),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_to_slice(&truncated, &mut slice),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            table.decode_bytes_to_slice(&[0x01, 0xFF], &mut slice),
+            Err(Error::BufferTooSmall(255))
+        ));
+        let fixed: ConstTable<char, 2> = ConstTable::from_slice(&['A', 'B']).unwrap();
+        assert!(matches!(
+            fixed.decode_bytes_to_slice(&unknown, &mut slice),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            fixed.decode_bytes_to_slice(&truncated, &mut slice),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            fixed.decode_bytes_to_slice(&[0x01, 0xFF], &mut slice),
+            Err(Error::BufferTooSmall(255))
+        ));
+
+        assert!(matches!(
+            table.validate_bytes(&unknown),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.validate_bytes(&truncated),
+            Err(Error::TruncatedStream)
+        ));
+        let checked: Vec<_> = table.decode_bytes_checked(&unknown).collect();
+        assert!(matches!(
+            checked[..],
+            [Ok('A'), Err(Error::UnknownIndex(2))]
+        ));
+        let checked: Vec<_> = table.decode_bytes_checked(&truncated).collect();
+        assert!(matches!(
+            checked[..],
+            [Ok('A'), Err(Error::TruncatedStream)]
+        ));
+
+        assert!(matches!(
+            table.decode_grid(GridLayout::new(2, 1), &unknown),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            planar::decode_bytes(std::slice::from_ref(&table), &[truncated.to_vec()]),
+            Err(Error::TruncatedStream)
+        ));
+
+        // The iterators stop at the first run they can't decode
+        assert_eq!(table.decode_bytes(&unknown).count(), 1);
+        assert_eq!(table.decode_bytes(&truncated).count(), 1);
+        assert_eq!(table.decode_bytes_runs(&unknown).count(), 1);
+        assert_eq!(table.decode_bytes_compact(&[0x01, 0x21]).count(), 1);
+        assert_eq!(table.decode_bytes_compact(&[0x01, 0x00]).count(), 1);
+        assert_eq!(
+            table.decode_chunks(&unknown, 4).collect::<Vec<_>>(),
+            [['A']]
+        );
+        assert_eq!(table.decode_scanlines(&[0x05, 0x00, 0x00]).count(), 0);
+
+        // A bad run only ends its own scanline
+        let rows = [0x02, 0x00, 0x00, 0x04, 0x01, 0x00, 0x02];
+        assert_eq!(table.decode_scanlines(&rows).collect::<String>(), "AB");
+
+        // Functions that only read the run headers
+        assert!(matches!(
+            slice_bytes(&truncated, ..),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            remap_bytes(&unknown, &[0, 1]),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            remap_bytes(&truncated, &[0, 1]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            find_symbol(&truncated, 1),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            find_run_at(&truncated, 5),
+            Err(Error::TruncatedStream)
+        ));
+        let mut bytes = vec![0x02];
+        assert!(matches!(
+            append_bytes(&mut bytes, &truncated),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            append_bytes(&mut vec![0x01], &[0x00]),
+            Err(Error::TruncatedStream)
+        ));
+        assert_eq!(bytes, [0x02]);
+    }
+
+    #[test]
+    fn encode_options_malformed() {
+        let table: Table<char> = "AB".chars().collect();
+        let plain = EncodeOptions::new();
+        assert!(matches!(
+            table.decode_bytes_with(&[0x00, 0x04], plain),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x01], plain),
+            Err(Error::TruncatedStream)
+        ));
+        assert_eq!(
+            table.decode_bytes_with(&[0x01, 0x00, 0x02], plain).unwrap(),
+            ['A', 'B']
+        );
+
+        let pair = plain.header(RunHeader::Pair);
+        assert!(matches!(
+            table.decode_bytes_with(&[0x00, 0x00], pair),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x02, 0x01], pair),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x00, 0x01, 0x01], pair),
+            Err(Error::TruncatedStream)
+        ));
+
+        let literal = plain.literal_blocks();
+        assert!(matches!(
+            table.decode_bytes_with(&[0x01, 0x00, 0x02], literal),
+            Err(Error::UnknownIndex(2))
+        ));
+        assert!(matches!(
+            table.decode_bytes_with(&[0x7F, 0x00], literal),
+            Err(Error::TruncatedStream)
+        ));
+    }
+
+    #[test]
+    fn containers_malformed() {
+        let table = Table::from_slice(&[10u32, 20]);
+        let bytes = table.encode_container(&[10, 10, 20]).unwrap();
+        assert_eq!(&bytes[12..], [0x01, 0x02, 0x02]);
+        let with_payload = |payload: &[u8]| {
+            let mut blob = bytes[..12].to_vec();
+            blob.extend_from_slice(payload);
+            blob
+        };
+
+        let decode = Table::<u32>::decode_container;
+        assert_eq!(
+            decode(&with_payload(&[0x01, 0x00, 0x02])).unwrap().1,
+            [10, 20]
+        );
+        assert!(matches!(
+            decode(&with_payload(&[0x00, 0x04])),
+            Err(Error::InvalidBytes(13))
+        ));
+        assert!(matches!(
+            decode(&with_payload(&[0x01])),
+            Err(Error::InvalidBytes(12))
+        ));
+        let mut huge = bytes.clone();
+        huge[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(decode(&huge), Err(Error::InvalidBytes(12))));
+        let mut twice = bytes.clone();
+        twice[8..12].copy_from_slice(&10u32.to_le_bytes());
+        assert!(matches!(decode(&twice), Err(Error::InvalidBytes(8))));
+        assert!(matches!(decode(&bytes[..2]), Err(Error::InvalidBytes(0))));
+
+        // A correct checksum doesn't make a malformed blob valid
+        let mut blob = with_payload(&[0x00, 0x04]);
+        crc::crc32(&blob).write_bytes(&mut blob);
+        assert!(matches!(
+            Table::<u32>::decode_container_crc(&blob),
+            Err(Error::InvalidBytes(13))
+        ));
+
+        let saved = table.to_bytes().unwrap();
+        let load = Table::<u32>::from_bytes;
+        assert_eq!(load(&saved).unwrap(), table);
+        assert!(matches!(load(&saved[..4]), Err(Error::InvalidBytes(4))));
+        assert!(matches!(load(&saved[1..]), Err(Error::InvalidBytes(0))));
+        let mut newer = saved.clone();
+        newer[4] += 1;
+        assert!(matches!(load(&newer), Err(Error::UnsupportedVersion(_))));
+        let mut huge = saved.clone();
+        huge[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(load(&huge), Err(Error::InvalidBytes(17))));
+        let mut twice = saved.clone();
+        twice[13..17].copy_from_slice(&10u32.to_le_bytes());
+        assert!(matches!(load(&twice), Err(Error::InvalidBytes(13))));
+        let mut extra = saved.clone();
+        extra.push(0);
+        assert!(matches!(load(&extra), Err(Error::InvalidBytes(17))));
+
+        let mut session = EncodeSession::with_table(table.clone());
+        session.encode(&[10, 10, 20]).unwrap();
+        let saved = session.to_bytes();
+        assert_eq!(&saved[16..], [3, 0, 0, 0, 0x01, 0x02, 0x02]);
+        let load = EncodeSession::<u32>::from_bytes;
+        let mut unknown = saved.clone();
+        unknown[22] = 0x04;
+        assert!(matches!(load(&unknown), Err(Error::InvalidBytes(22))));
+        let mut truncated = saved.clone();
+        truncated[16] = 1;
+        assert!(matches!(load(&truncated), Err(Error::InvalidBytes(20))));
+        let mut huge = saved.clone();
+        huge[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(load(&huge), Err(Error::InvalidBytes(16))));
+        let mut count = saved.clone();
+        count[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(load(&count), Err(Error::InvalidBytes(23))));
+    }
+
+    #[test]
+    fn image_formats_malformed() {
+        assert!(matches!(
+            decode_packbits(&[0x00]),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_tga(&[0x01, 9], 1, 2),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_tga(&[0xFF, 9], 1, 4),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            decode_bmp_rle8(&[0, 2, 5, 0], 3, 1),
+            Err(Error::InvalidBytes(0))
+        ));
+        assert!(matches!(
+            decode_bmp_rle8(&[0, 2, 0], 3, 1),
+            Err(Error::TruncatedStream)
+        ));
+        assert!(matches!(
+            decode_bmp_rle4(&[0, 5, 0x12, 0x34], 5, 1),
+            Err(Error::TruncatedStream)
+        ));
+
+        // The tree doesn't know the table, so unknown leaves are caught when
+        // it's converted back to scanlines
+        let tree = Quadtree::from_bytes(&[1, 0, 0, 0, 1, 0, 0, 0, 7]).unwrap();
+        let table: Table<char> = "AB".chars().collect();
+        assert!(matches!(
+            tree.to_scanlines(&table),
+            Err(Error::TableMissingItems(_))
+        ));
+        assert!(matches!(
+            Quadtree::from_bytes(&[2, 0, 0, 0, 2, 0, 0, 0, 255, 0, 1]),
+            Err(Error::TruncatedStream)
+        ));
+    }
 }
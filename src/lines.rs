@@ -0,0 +1,120 @@
+use crate::{BytesDecoder, Error, Table, TableStore};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// A block of text that was run-length encoded one line at a time, so
+/// that its layout survives the round-trip and each line can be decoded
+/// on its own. See [encode_lines](crate::Table::encode_lines).
+///
+/// With the `serde` feature enabled, deserializing fails if the line ends
+/// are out of order or point past the end of the bytes.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Lines {
+    /// The encoded bytes of every line, stored back to back.
+    bytes: Vec<u8>,
+
+    /// The position in `bytes` where each line ends.
+    ends: Vec<usize>,
+}
+
+impl Lines {
+    /// The number of lines.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if there are no lines.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// The encoded bytes of all the lines, back to back.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The encoded bytes of a single line, or `None` if out of bounds.
+    pub fn line_bytes(&self, line: usize) -> Option<&[u8]> {
+        let end = *self.ends.get(line)?;
+        let start = if line > 0 { self.ends[line - 1] } else { 0 };
+        Some(&self.bytes[start..end])
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Lines {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            bytes: Vec<u8>,
+            ends: Vec<usize>,
+        }
+
+        let Raw { bytes, ends } = Raw::deserialize(deserializer)?;
+        let mut start = 0;
+        for (line, &end) in ends.iter().enumerate() {
+            if end < start || end > bytes.len() {
+                return Err(de::Error::custom(format!(
+                    "line [{}] ends at {}, outside of its bytes",
+                    line, end
+                )));
+            }
+            start = end;
+        }
+        Ok(Self { bytes, ends })
+    }
+}
+
+impl<S> Table<char, S>
+where
+    S: TableStore<char>,
+{
+    /// Run-length encodes the text line by line, adding characters to
+    /// the table as they are found.
+    ///
+    /// The text is split on `'\n'`, which is stored implicitly as the
+    /// boundary between lines rather than as a symbol in the table. Runs
+    /// never cross a line boundary, so every line can be decoded individually
+    /// with [decode_line](Table::decode_line).
+    ///
+    /// # Errors
+    ///
+    /// Like [encode_bytes_mut](Table::encode_bytes_mut), this will return a
+    /// [TableTooLarge](Error::TableTooLarge) error if the text contains too
+    /// many unique characters to be encoded as bytes.
+    pub fn encode_lines(&mut self, text: &str) -> Result<Lines, Error> {
+        let mut lines = Lines::default();
+        for line in text.split('\n') {
+            let chars: Vec<char> = line.chars().collect();
+            for byte in self.encode_bytes_mut(&chars)? {
+                lines.bytes.push(byte?);
+            }
+            lines.ends.push(lines.bytes.len());
+        }
+        Ok(lines)
+    }
+
+    /// Return an iterator that decodes a single line of the encoded text,
+    /// or `None` if the line is out of bounds.
+    pub fn decode_line<'a>(
+        &'a self,
+        lines: &'a Lines,
+        line: usize,
+    ) -> Option<BytesDecoder<'a, char, S>> {
+        lines.line_bytes(line).map(|bytes| self.decode_bytes(bytes))
+    }
+
+    /// Decodes all the lines back into a string, with the lines separated
+    /// by `'\n'` just like the original text.
+    pub fn decode_lines(&self, lines: &Lines) -> String {
+        let mut str = String::new();
+        for line in 0..lines.len() {
+            if line > 0 {
+                str.push('\n');
+            }
+            str.extend(self.decode_line(lines, line).unwrap());
+        }
+        str
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Error, Index, Table, TableItem};
+use std::cmp::Ordering;
+
+/// The index reserved in the byte format to mark a run of a literal item
+/// that follows inline, rather than an item stored in the table.
+pub(crate) const LITERAL_INDEX: u8 = 127;
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// compressed byte format, writing items that are missing from the table
+/// as inline literals. See [encode_bytes_literal](crate::Table::encode_bytes_literal).
+pub struct LiteralBytesEncoder<'a, T> {
+    pub(crate) table: &'a Table<T>,
+    pub(crate) items: &'a [T],
+    pub(crate) index: usize,
+    pub(crate) packet: Vec<u8>,
+    pub(crate) pos: usize,
+}
+
+impl<'a, T> Iterator for LiteralBytesEncoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&byte) = self.packet.get(self.pos) {
+            self.pos += 1;
+            return Some(byte);
+        }
+        if self.index >= self.items.len() {
+            return None;
+        }
+        self.packet.clear();
+        self.pos = 1;
+
+        let item = &self.items[self.index];
+        let mut len = 1;
+        while len < 127
+            && self.index + len < self.items.len()
+            && item.cmp(&self.items[self.index + len]) == Ordering::Equal
+        {
+            len += 1;
+        }
+        self.index += len;
+
+        let ind = self
+            .table
+            .get_index(item)
+            .map_or(LITERAL_INDEX, |ind| ind as u8);
+        if len > 1 {
+            self.packet.push((ind << 1) | 1);
+            self.packet.push(len as u8);
+        } else {
+            self.packet.push(ind << 1);
+        }
+        if ind == LITERAL_INDEX {
+            item.write_bytes(&mut self.packet);
+        }
+        Some(self.packet[0])
+    }
+}
+
+/// An iterator that decodes a run-length encoded sequence of bytes with
+/// inline literals into a series of `T` values. See [decode_bytes_literal](crate::Table::decode_bytes_literal).
+pub struct LiteralBytesDecoder<'a, T> {
+    pub(crate) table: &'a Table<T>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) literal: Option<T>,
+}
+
+impl<'a, T> LiteralBytesDecoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    fn read_run(&mut self) -> Option<(Index, usize)> {
+        let (&ind, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        let len = if (ind & 1) == 1 {
+            let (&len, rest) = self.bytes.split_first()?;
+            self.bytes = rest;
+            len as usize
+        } else {
+            1
+        };
+        let ind = ind >> 1;
+        if ind == LITERAL_INDEX {
+            let (item, size) = T::read_bytes(self.bytes)?;
+            self.bytes = &self.bytes[size..];
+            self.literal = Some(item);
+        }
+        Some((ind as usize, len))
+    }
+}
+
+impl<'a, T> Iterator for LiteralBytesDecoder<'a, T>
+where
+    T: Ord + Clone + TableItem,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.run.take().or_else(|| self.read_run())?;
+        if len > 1 {
+            self.run = Some((ind, len - 1));
+        }
+        if ind == LITERAL_INDEX as usize {
+            self.literal.clone()
+        } else {
+            self.table.get(ind).cloned()
+        }
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Returns an iterator to run-length encode the items as a sequence of bytes.
+    ///
+    /// Unlike [encode_bytes](Table::encode_bytes), this will not fail if `items`
+    /// contains elements that are not in the table. Instead, runs of those
+    /// elements are written with the reserved index `127`, followed by the bytes
+    /// of the element itself (see [TableItem]). Use [decode_bytes_literal](Table::decode_bytes_literal)
+    /// to decode the sequence.
+    ///
+    /// # Errors
+    ///
+    /// Because index `127` is reserved for literals, this will return a
+    /// [TableTooLarge](Error::TableTooLarge) error if the table contains
+    /// >= 127 items.
+    pub fn encode_bytes_literal<'a>(
+        &'a self,
+        items: &'a [T],
+    ) -> Result<LiteralBytesEncoder<'a, T>, Error> {
+        if self.len() < LITERAL_INDEX as usize {
+            Ok(LiteralBytesEncoder {
+                table: self,
+                items,
+                index: 0,
+                packet: Vec::new(),
+                pos: 0,
+            })
+        } else {
+            Err(Error::TableTooLarge(self.len()))
+        }
+    }
+
+    /// Return an iterator that decodes a sequence of bytes produced by
+    /// [encode_bytes_literal](Table::encode_bytes_literal), using this table
+    /// as the index lookup for all the elements that aren't literals.
+    pub fn decode_bytes_literal<'a>(&'a self, bytes: &'a [u8]) -> LiteralBytesDecoder<'a, T> {
+        LiteralBytesDecoder {
+            table: self,
+            bytes,
+            run: None,
+            literal: None,
+        }
+    }
+}
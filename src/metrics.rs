@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Counters that track how much data was encoded and decoded, and how long
+/// it took, so the effectiveness of compression can be monitored.
+///
+/// Supply a collector to an encoder or decoder with its `metrics` method,
+/// such as [BytesEncoder::metrics](crate::BytesEncoder::metrics). The counters
+/// are atomic, so one collector can be shared between many encoders on
+/// different threads, and read at any time to feed into a metrics exporter.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    encoded_items: AtomicU64,
+    encoded_runs: AtomicU64,
+    encoded_bytes: AtomicU64,
+    encode_nanos: AtomicU64,
+    decoded_bytes: AtomicU64,
+    decoded_runs: AtomicU64,
+    decoded_items: AtomicU64,
+    decode_nanos: AtomicU64,
+}
+
+impl Metrics {
+    /// Constructs a new collector with all counters set to zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of items that have been encoded.
+    pub fn encoded_items(&self) -> u64 {
+        self.encoded_items.load(Ordering::Relaxed)
+    }
+
+    /// The number of runs that have been encoded.
+    pub fn encoded_runs(&self) -> u64 {
+        self.encoded_runs.load(Ordering::Relaxed)
+    }
+
+    /// The number of bytes that encoding has output.
+    pub fn encoded_bytes(&self) -> u64 {
+        self.encoded_bytes.load(Ordering::Relaxed)
+    }
+
+    /// The total time spent encoding.
+    pub fn encode_time(&self) -> Duration {
+        Duration::from_nanos(self.encode_nanos.load(Ordering::Relaxed))
+    }
+
+    /// The number of bytes that have been decoded.
+    pub fn decoded_bytes(&self) -> u64 {
+        self.decoded_bytes.load(Ordering::Relaxed)
+    }
+
+    /// The number of runs that have been decoded.
+    pub fn decoded_runs(&self) -> u64 {
+        self.decoded_runs.load(Ordering::Relaxed)
+    }
+
+    /// The number of items that decoding has output.
+    pub fn decoded_items(&self) -> u64 {
+        self.decoded_items.load(Ordering::Relaxed)
+    }
+
+    /// The total time spent decoding.
+    pub fn decode_time(&self) -> Duration {
+        Duration::from_nanos(self.decode_nanos.load(Ordering::Relaxed))
+    }
+
+    /// The average number of bytes each encoded item took up, or `0.0`
+    /// if nothing has been encoded.
+    pub fn bytes_per_item(&self) -> f64 {
+        match self.encoded_items() {
+            0 => 0.0,
+            items => self.encoded_bytes() as f64 / items as f64,
+        }
+    }
+
+    /// Sets all the counters back to zero.
+    pub fn reset(&self) {
+        for counter in [
+            &self.encoded_items,
+            &self.encoded_runs,
+            &self.encoded_bytes,
+            &self.encode_nanos,
+            &self.decoded_bytes,
+            &self.decoded_runs,
+            &self.decoded_items,
+            &self.decode_nanos,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn add_encoded_run(&self, items: usize, start: Instant) {
+        self.encoded_runs.fetch_add(1, Ordering::Relaxed);
+        self.encoded_items
+            .fetch_add(items as u64, Ordering::Relaxed);
+        self.encode_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_encoded_bytes(&self, bytes: usize) {
+        self.encoded_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_decoded_run(&self, bytes: usize, start: Instant) {
+        self.decoded_runs.fetch_add(1, Ordering::Relaxed);
+        self.decoded_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.decode_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_decoded_items(&self, items: usize) {
+        self.decoded_items
+            .fetch_add(items as u64, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::bulk::read_run;
+use crate::literal::LITERAL_INDEX;
+use crate::{Error, Index, Table, TableItem};
+
+/// A byte format that encoded data can be migrated between with [migrate].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The format of [encode_bytes](crate::Table::encode_bytes).
+    Bytes,
+
+    /// The self-describing format of [encode_bytes_inline](crate::Table::encode_bytes_inline).
+    Inline,
+
+    /// The format of [encode_bytes_literal](crate::Table::encode_bytes_literal).
+    Literal,
+}
+
+/// Transcodes a sequence of bytes from one format to another, one run at a
+/// time, without ever decoding the runs into individual elements.
+///
+/// The `table` is used to look up the items of the original bytes, so it
+/// should be the table they were encoded with (or an empty table for the
+/// [Inline](Format::Inline) format). Items embedded in the original bytes
+/// are added to the table, so that afterwards it can be used to decode the
+/// migrated bytes. Bytes migrated to the [Inline](Format::Inline) format
+/// carry their own items and are always decoded starting with an empty table.
+///
+/// # Errors
+///
+/// Returns an [InvalidBytes](Error::InvalidBytes) error if the original
+/// bytes are malformed, or a [TableTooLarge](Error::TableTooLarge) error if
+/// the table grows too large for the new format.
+pub fn migrate<T>(
+    from: Format,
+    to: Format,
+    table: &mut Table<T>,
+    bytes: &[u8],
+) -> Result<Vec<u8>, Error>
+where
+    T: Ord + Clone + TableItem,
+{
+    let runs = read_runs(from, table, bytes)?;
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut inline = Table::default();
+    for (ind, len) in runs {
+        let item = &table[ind];
+        let (ind, is_new) = match to {
+            Format::Bytes => (ind, false),
+            Format::Inline => {
+                let known = inline.len();
+                let ind = inline.insert_or_get(item);
+                (ind, ind >= known)
+            }
+            Format::Literal if ind >= LITERAL_INDEX as usize => (LITERAL_INDEX as usize, true),
+            Format::Literal => (ind, false),
+        };
+        if ind > 127 || (ind == 127 && to != Format::Literal) {
+            return Err(Error::TableTooLarge(table.len().max(inline.len())));
+        }
+        let mut len = len;
+        let mut embed = is_new;
+        while len > 0 {
+            let num = len.min(127);
+            if num > 1 {
+                out.push(((ind as u8) << 1) | 1);
+                out.push(num as u8);
+            } else {
+                out.push((ind as u8) << 1);
+            }
+            if embed {
+                item.write_bytes(&mut out);
+                embed = to == Format::Literal;
+            }
+            len -= num;
+        }
+    }
+    Ok(out)
+}
+
+/// Reads all the runs from the bytes, merging neighbouring runs of the same item.
+fn read_runs<T>(
+    format: Format,
+    table: &mut Table<T>,
+    bytes: &[u8],
+) -> Result<Vec<(Index, usize)>, Error>
+where
+    T: Ord + Clone + TableItem,
+{
+    let mut runs: Vec<(Index, usize)> = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let start = offset;
+        let invalid = || Error::InvalidBytes(start);
+        let (mut ind, len, size) = read_run(bytes, offset).ok().flatten().ok_or_else(invalid)?;
+        offset += size;
+        match format {
+            Format::Inline if ind == table.len() => {
+                let (item, size) = T::read_bytes(&bytes[offset..]).ok_or_else(invalid)?;
+                offset += size;
+                table.insert(item);
+            }
+            Format::Literal if ind == LITERAL_INDEX as usize => {
+                let (item, size) = T::read_bytes(&bytes[offset..]).ok_or_else(invalid)?;
+                offset += size;
+                ind = table.insert_or_get(&item);
+            }
+            _ => {}
+        }
+        if ind >= table.len() {
+            return Err(invalid());
+        }
+        match runs.last_mut() {
+            Some(last) if last.0 == ind => last.1 += len,
+            _ => runs.push((ind, len)),
+        }
+    }
+    Ok(runs)
+}
@@ -0,0 +1,107 @@
+use crate::{BytesDecoderOwned, Table, TableStore};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The bytes of a file, mapped into memory so they are only read from disk
+/// as they are accessed.
+///
+/// This derefs to `[u8]`, so it can be passed to any of the byte decoders,
+/// such as [decode_bytes](Table::decode_bytes) or [decode_range](Table::decode_range),
+/// to lazily decode large encoded files without reading them into a `Vec`
+/// first. Only available with the `mmap` feature.
+///
+/// ```no_run
+/// # use rle::{MappedBytes, Table};
+/// let table = Table::from_slice(&[0u8, 1, 2]);
+/// // SAFETY: nothing else writes to the file while it is mapped
+/// let bytes = unsafe { MappedBytes::open("level.rle") }.unwrap();
+/// let first_row: Vec<u8> = table.decode_bytes(&bytes).take(256).copied().collect();
+/// ```
+///
+/// Mapping a file is `unsafe`, since the file must not be modified or
+/// truncated while it is mapped, by this process or any other. If it is,
+/// the mapped bytes can change underneath the decoder, or reading them can
+/// crash the process.
+#[derive(Debug)]
+pub struct MappedBytes {
+    map: Mmap,
+}
+
+impl MappedBytes {
+    /// Opens the file at `path` and maps its bytes into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any
+    /// other, until the returned bytes are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from opening or mapping the file.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        // SAFETY: upheld by the caller
+        unsafe { Self::from_file(&File::open(path)?) }
+    }
+
+    /// Maps the bytes of an open file into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any
+    /// other, until the returned bytes are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from mapping the file.
+    pub unsafe fn from_file(file: &File) -> io::Result<Self> {
+        // SAFETY: upheld by the caller
+        let map = unsafe { Mmap::map(file)? };
+        Ok(Self { map })
+    }
+
+    /// The mapped bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl Deref for MappedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl AsRef<[u8]> for MappedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl<T, S> BytesDecoderOwned<T, S, MappedBytes>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Maps the file at `path` into memory and creates a decoder for its
+    /// bytes, using the table as the index lookup. Only available with the
+    /// `mmap` feature.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any
+    /// other, until the decoder is dropped (see [MappedBytes::open]).
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from opening or mapping the file.
+    pub unsafe fn open<P: AsRef<Path>>(table: Arc<Table<T, S>>, path: P) -> io::Result<Self> {
+        // SAFETY: upheld by the caller
+        Ok(Self::new(table, unsafe { MappedBytes::open(path)? }))
+    }
+}
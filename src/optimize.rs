@@ -0,0 +1,61 @@
+use crate::{Index, Table, TableStore};
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Reorders the table so that the items most frequent in `items` get the
+    /// smallest indices, adding any items that were not already in the table.
+    ///
+    /// Frequency is counted per run rather than per item, since each run
+    /// stores its index once no matter how long it is. Items with the same
+    /// frequency keep their relative order, and items that do not appear in
+    /// `items` are moved after all the ones that do.
+    ///
+    /// Returns a remap from each item's old index to its new one, which can be
+    /// used to update runs that were encoded with the table before it changed.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table = Table::from_slice(&['A', 'B', 'C']);
+    /// let remap = table.optimize_for(&['C', 'C', 'B', 'C', 'B', 'C', 'D']);
+    /// assert_eq!(table.as_ref(), ['C', 'B', 'D', 'A']);
+    /// assert_eq!(remap, [3, 1, 0]);
+    /// ```
+    pub fn optimize_for(&mut self, items: &[T]) -> Vec<Index> {
+        let old_len = self.len();
+        let mut counts = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let ind = self.insert_or_get(&items[i]);
+            if ind >= counts.len() {
+                counts.resize(ind + 1, 0);
+            }
+            counts[ind] += 1;
+            i += self.run_len(&items[i..]);
+        }
+        counts.resize(self.len(), 0);
+
+        // Stable sort, so ties keep their old order
+        let mut order: Vec<Index> = (0..self.len()).collect();
+        order.sort_by(|&a, &b| counts[b].cmp(&counts[a]));
+
+        let sorted: Vec<T> = order
+            .iter()
+            .filter_map(|&ind| self.item(ind).cloned())
+            .collect();
+        self.clear();
+        for item in &sorted {
+            self.insert_or_get(item);
+        }
+
+        let mut remap = vec![0; old_len];
+        for (new, &old) in order.iter().enumerate() {
+            if old < old_len {
+                remap[old] = new;
+            }
+        }
+        remap
+    }
+}
@@ -0,0 +1,187 @@
+use crate::bulk::read_run;
+use crate::{Index, SortedStore, Table, TableStore};
+use std::iter::FusedIterator;
+use std::sync::Arc;
+
+/// An iterator that decodes a sequence of runs into a series of `T` values,
+/// owning both the runs and a shared handle to the table. Unlike [Decoder](crate::Decoder),
+/// it has no borrow lifetimes, so it can be returned from functions or sent
+/// to other threads.
+///
+/// ```
+/// # use rle::{DecoderOwned, Table};
+/// use std::sync::Arc;
+///
+/// let table = Arc::new(Table::from_slice(&['A', 'B']));
+/// let decoder = DecoderOwned::new(table, vec![(0, 2), (1, 1)]);
+/// let str = std::thread::spawn(move || decoder.collect::<String>());
+/// assert_eq!(str.join().unwrap(), "AAB");
+/// ```
+pub struct DecoderOwned<T, S = SortedStore<T>> {
+    pub(crate) table: Arc<Table<T, S>>,
+    pub(crate) runs: Vec<(Index, usize)>,
+    pub(crate) index: usize,
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<T, S> DecoderOwned<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Creates a decoder for the runs using the table as the index lookup.
+    pub fn new(table: Arc<Table<T, S>>, runs: Vec<(Index, usize)>) -> Self {
+        Self {
+            table,
+            runs,
+            index: 0,
+            run: None,
+        }
+    }
+
+    /// The table this decoder looks items up in.
+    pub fn table(&self) -> &Arc<Table<T, S>> {
+        &self.table
+    }
+}
+
+impl<T, S> Iterator for DecoderOwned<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (ind, len) = match self.run.take() {
+                Some(run) => run,
+                None => {
+                    let run = *self.runs.get(self.index)?;
+                    self.index += 1;
+                    run
+                }
+            };
+            match self.table.item(ind) {
+                Some(_) if len == 0 => {}
+                Some(item) => {
+                    if len > 1 {
+                        self.run = Some((ind, len - 1));
+                    }
+                    return Some(item.clone());
+                }
+                None => {
+                    // Stop decoding at the first index that isn't in the table
+                    self.index = self.runs.len();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<T, S> FusedIterator for DecoderOwned<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
+
+/// An iterator that decodes a run-length encoded sequence of bytes into a
+/// series of `T` values, owning both the bytes and a shared handle to the table.
+/// Unlike [BytesDecoder](crate::BytesDecoder), it has no borrow lifetimes, so
+/// it can be returned from functions or sent to other threads.
+///
+/// The bytes can be any type that can be viewed as a `[u8]`, such as a
+/// `Vec<u8>`, a `Box<[u8]>` or an `Arc<[u8]>`.
+///
+/// ```
+/// # use rle::{BytesDecoderOwned, Table};
+/// use std::sync::Arc;
+///
+/// fn decode(table: &Arc<Table<char>>, bytes: Vec<u8>) -> BytesDecoderOwned<char> {
+///     BytesDecoderOwned::new(table.clone(), bytes)
+/// }
+///
+/// let table = Arc::new(Table::from_slice(&['A', 'B']));
+/// let str: String = decode(&table, vec![0x01, 0x03, 0x02]).collect();
+/// assert_eq!(str, "AAAB");
+/// ```
+pub struct BytesDecoderOwned<T, S = SortedStore<T>, B = Vec<u8>> {
+    pub(crate) table: Arc<Table<T, S>>,
+    pub(crate) bytes: B,
+    pub(crate) offset: usize,
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<T, S, B> BytesDecoderOwned<T, S, B>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    B: AsRef<[u8]>,
+{
+    /// Creates a decoder for the bytes using the table as the index lookup.
+    pub fn new(table: Arc<Table<T, S>>, bytes: B) -> Self {
+        Self {
+            table,
+            bytes,
+            offset: 0,
+            run: None,
+        }
+    }
+
+    /// The table this decoder looks items up in.
+    pub fn table(&self) -> &Arc<Table<T, S>> {
+        &self.table
+    }
+
+    /// Stops decoding and returns the bytes.
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    fn stop(&mut self) -> Option<T> {
+        self.offset = self.bytes.as_ref().len();
+        self.run = None;
+        None
+    }
+}
+
+impl<T, S, B> Iterator for BytesDecoderOwned<T, S, B>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    B: AsRef<[u8]>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = match self.run.take() {
+            Some(run) => run,
+            None => match read_run(self.bytes.as_ref(), self.offset) {
+                Ok(Some((ind, len, size))) => {
+                    self.offset += size;
+                    (ind, len)
+                }
+                Ok(None) | Err(_) => return self.stop(),
+            },
+        };
+        match self.table.item(ind) {
+            Some(item) => {
+                if len > 1 {
+                    self.run = Some((ind, len - 1));
+                }
+                Some(item.clone())
+            }
+            None => self.stop(),
+        }
+    }
+}
+
+impl<T, S, B> FusedIterator for BytesDecoderOwned<T, S, B>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    B: AsRef<[u8]>,
+{
+}
@@ -0,0 +1,92 @@
+use crate::byte_scan::byte_run_len;
+use crate::Error;
+
+/// Encodes bytes with PackBits, the run-length scheme used by TIFF and
+/// Apple's MacPaint, which needs no table.
+///
+/// # Format
+///
+/// The output is a series of packets, each starting with a header byte `n`
+/// read as an `i8`:
+///
+/// - `0..=127`: the next `n + 1` bytes are copied as they are.
+/// - `-127..=-1`: the next byte is repeated `1 - n` times.
+/// - `-128`: no operation, skipped when decoding (never written).
+///
+/// Runs of 3 or more bytes are written as repeat packets, and everything
+/// else is gathered into literal packets.
+///
+/// ```
+/// # use rle::{decode_packbits, encode_packbits};
+/// let bytes = [0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA];
+/// let packed = encode_packbits(&bytes);
+/// assert_eq!(packed, [0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA]);
+/// assert_eq!(decode_packbits(&packed).unwrap(), bytes);
+/// ```
+pub fn encode_packbits(bytes: &[u8]) -> Vec<u8> {
+    let mut packed = Vec::with_capacity(bytes.len() + bytes.len() / 128 + 1);
+    let mut literal = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let len = byte_run_len(&bytes[i..]);
+        if len < 3 {
+            i += len;
+            continue;
+        }
+        push_literal(&mut packed, &bytes[literal..i]);
+        let mut rest = len;
+        while rest > 0 {
+            let num = rest.min(128);
+            if num < 3 {
+                // Too short to be worth a repeat packet
+                push_literal(&mut packed, &bytes[i..i + num]);
+            } else {
+                packed.push((1 - num as isize) as u8);
+                packed.push(bytes[i]);
+            }
+            i += num;
+            rest -= num;
+        }
+        literal = i;
+    }
+    push_literal(&mut packed, &bytes[literal..]);
+    packed
+}
+
+fn push_literal(packed: &mut Vec<u8>, bytes: &[u8]) {
+    for chunk in bytes.chunks(128) {
+        packed.push((chunk.len() - 1) as u8);
+        packed.extend_from_slice(chunk);
+    }
+}
+
+/// Decodes bytes that were encoded with PackBits (see [encode_packbits]).
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes
+/// end in the middle of a packet.
+pub fn decode_packbits(packed: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(packed.len() * 2);
+    let mut packed = packed;
+    while let Some((&n, rest)) = packed.split_first() {
+        let n = n as i8;
+        packed = match n {
+            0..=127 => {
+                let len = n as usize + 1;
+                if rest.len() < len {
+                    return Err(Error::TruncatedStream);
+                }
+                bytes.extend_from_slice(&rest[..len]);
+                &rest[len..]
+            }
+            -127..=-1 => {
+                let (&byte, rest) = rest.split_first().ok_or(Error::TruncatedStream)?;
+                bytes.resize(bytes.len() + (1 - n as isize) as usize, byte);
+                rest
+            }
+            -128 => rest,
+        };
+    }
+    Ok(bytes)
+}
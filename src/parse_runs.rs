@@ -0,0 +1,72 @@
+use crate::{Error, Index, Table, TableStore};
+use std::str::FromStr;
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone + FromStr,
+    S: TableStore<T>,
+{
+    /// Parses runs written as each run's length followed by its item, such as
+    /// `5A 10B 3C`, with the runs separated by whitespace. This is the format
+    /// written by [display_runs](Table::display_runs). Because the length is
+    /// read up to the first non-digit, items can't begin with a digit.
+    ///
+    /// Fails with [InvalidRunText](Error::InvalidRunText) if a run is
+    /// malformed or its item can't be parsed, or with
+    /// [TableMissingItems](Error::TableMissingItems) (containing the position
+    /// of the run) if an item is not in the table. Use
+    /// [parse_runs_mut](Table::parse_runs_mut) to add missing items instead.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// let runs = table.parse_runs("5A 10B 3C").unwrap();
+    /// assert_eq!(runs, vec![(0, 5), (1, 10), (2, 3)]);
+    /// assert!(table.parse_runs("5A 2D").is_err());
+    /// ```
+    pub fn parse_runs(&self, str: &str) -> Result<Vec<(Index, usize)>, Error> {
+        split_runs(str)
+            .enumerate()
+            .map(|(i, run)| {
+                let (len, item) = run?;
+                let ind = self.get_index(&item).ok_or(Error::TableMissingItems(i))?;
+                Ok((ind, len))
+            })
+            .collect()
+    }
+
+    /// Like [parse_runs](Table::parse_runs), but adds items that are not
+    /// in the table yet.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table: Table<char> = Table::default();
+    /// let runs = table.parse_runs_mut("2Z 3Y 1Z").unwrap();
+    /// assert_eq!(table.as_ref(), &['Z', 'Y']);
+    /// assert_eq!(table.decode(&runs).collect::<String>(), "ZZYYYZ");
+    /// ```
+    pub fn parse_runs_mut(&mut self, str: &str) -> Result<Vec<(Index, usize)>, Error> {
+        split_runs(str)
+            .map(|run| {
+                let (len, item) = run?;
+                Ok((self.insert_or_get(&item), len))
+            })
+            .collect()
+    }
+}
+
+/// Splits the string into runs, parsing each one's length and item.
+fn split_runs<T: FromStr>(str: &str) -> impl Iterator<Item = Result<(usize, T), Error>> + '_ {
+    str.split_whitespace().map(move |run| {
+        let offset = run.as_ptr() as usize - str.as_ptr() as usize;
+        let digits = run.bytes().take_while(u8::is_ascii_digit).count();
+        let (len, item) = run.split_at(digits);
+        match (len.parse(), item) {
+            (Ok(len), item) if !item.is_empty() => item
+                .parse()
+                .map(|item| (len, item))
+                .map_err(|_| Error::InvalidRunText(offset)),
+            _ => Err(Error::InvalidRunText(offset)),
+        }
+    })
+}
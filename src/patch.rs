@@ -0,0 +1,125 @@
+use crate::concat::append_runs;
+use crate::{slice_runs, Index};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single change in a [Patch]: `remove` elements starting at `start` are
+/// replaced by the elements of `runs`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PatchEdit {
+    /// The position of the first changed element in the old sequence.
+    pub start: usize,
+
+    /// How many elements of the old sequence are replaced.
+    pub remove: usize,
+
+    /// The runs to replace them with.
+    pub runs: Vec<(Index, usize)>,
+}
+
+/// The differences between two sequences of runs, created with [diff], which
+/// can turn the old sequence into the new one with [apply](Patch::apply).
+///
+/// A patch only contains the spans that changed, so when two large sequences
+/// are mostly the same, sending the patch is much cheaper than sending the
+/// new sequence. With the `serde` feature enabled, patches can be serialized.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Patch {
+    /// The changes in order of their position, which never overlap.
+    pub edits: Vec<PatchEdit>,
+}
+
+impl Patch {
+    /// Returns `true` if the sequences were the same.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Applies the patch to the old sequence of runs it was created from,
+    /// returning the new sequence. Adjacent runs with the same index are
+    /// merged where the edits meet the unchanged runs.
+    pub fn apply(&self, old: &[(Index, usize)]) -> Vec<(Index, usize)> {
+        let mut runs = Vec::with_capacity(old.len());
+        let mut pos = 0;
+        for edit in &self.edits {
+            append_runs(&mut runs, &slice_runs(old, pos..edit.start));
+            append_runs(&mut runs, &edit.runs);
+            pos = edit.start + edit.remove;
+        }
+        append_runs(&mut runs, &slice_runs(old, pos..));
+        runs
+    }
+}
+
+/// Compares two sequences of runs that were encoded with the same table,
+/// returning a [Patch] of the spans where their elements differ. The runs
+/// are compared directly, without decoding them.
+///
+/// ```
+/// # use rle::diff;
+/// let old = [(0, 100), (1, 50), (0, 100)];
+/// let new = [(0, 100), (1, 20), (2, 5), (1, 25), (0, 100)];
+///
+/// let patch = diff(&old, &new);
+/// assert_eq!(patch.edits.len(), 1);
+/// assert_eq!(patch.edits[0].start, 120);
+/// assert_eq!(patch.edits[0].runs, [(2, 5)]);
+/// assert_eq!(patch.apply(&old), new);
+/// ```
+pub fn diff(old: &[(Index, usize)], new: &[(Index, usize)]) -> Patch {
+    let mut edits: Vec<PatchEdit> = Vec::new();
+    let mut old_runs = old.iter().copied().filter(|&(_, len)| len > 0);
+    let mut new_runs = new.iter().copied().filter(|&(_, len)| len > 0);
+    let (mut a, mut b) = (old_runs.next(), new_runs.next());
+    let mut pos = 0;
+
+    // Walk both sequences in lockstep, one span of matching run boundaries
+    // at a time, until one of them ends
+    while let (Some((old_ind, old_len)), Some((new_ind, new_len))) = (a, b) {
+        let len = old_len.min(new_len);
+        if old_ind != new_ind {
+            match edits.last_mut() {
+                Some(edit) if edit.start + edit.remove == pos => {
+                    edit.remove += len;
+                    append_runs(&mut edit.runs, &[(new_ind, len)]);
+                }
+                _ => edits.push(PatchEdit {
+                    start: pos,
+                    remove: len,
+                    runs: vec![(new_ind, len)],
+                }),
+            }
+        }
+        pos += len;
+        a = if old_len > len {
+            Some((old_ind, old_len - len))
+        } else {
+            old_runs.next()
+        };
+        b = if new_len > len {
+            Some((new_ind, new_len - len))
+        } else {
+            new_runs.next()
+        };
+    }
+
+    // Whatever is left of either sequence was removed or added at the end
+    let removed: usize = a.into_iter().chain(old_runs).map(|(_, len)| len).sum();
+    let added: Vec<_> = b.into_iter().chain(new_runs).collect();
+    if removed > 0 || !added.is_empty() {
+        match edits.last_mut() {
+            Some(edit) if edit.start + edit.remove == pos => {
+                edit.remove += removed;
+                append_runs(&mut edit.runs, &added);
+            }
+            _ => edits.push(PatchEdit {
+                start: pos,
+                remove: removed,
+                runs: added,
+            }),
+        }
+    }
+    Patch { edits }
+}
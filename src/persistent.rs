@@ -0,0 +1,433 @@
+use crate::rle_vec::bounds;
+use std::fmt::{Debug, Formatter};
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+use std::rc::Rc;
+
+/// An immutable run-length encoded sequence, where edits return a new version
+/// of the sequence that shares most of its runs with the old one.
+///
+/// The runs are kept in a balanced tree, so [get](PersistentRle::get) and
+/// edits such as [set](PersistentRle::set) and [splice](PersistentRle::splice)
+/// take `O(log n)` time for `n` runs, and only copy the `O(log n)` runs on
+/// the path to the edit. Cloning is just a reference count increment, which
+/// makes it cheap to keep many versions around, such as an undo history.
+///
+/// Unlike [RleVec](crate::RleVec), items are stored in the runs rather than
+/// in a table, so each edit clones a few items. For large items, consider
+/// storing [Interned](crate::Interned) handles instead.
+///
+/// ```
+/// use rle::PersistentRle;
+///
+/// let v1: PersistentRle<char> = "AAAAABBBBBCCC".chars().collect();
+/// let v2 = v1.set(7, 'X');
+/// let v3 = v2.splice(0..5, "ZZ".chars());
+/// assert_eq!(v1.to_vec().into_iter().collect::<String>(), "AAAAABBBBBCCC");
+/// assert_eq!(v2.to_vec().into_iter().collect::<String>(), "AAAAABBXBBCCC");
+/// assert_eq!(v3.to_vec().into_iter().collect::<String>(), "ZZBBXBBCCC");
+/// assert_eq!(v3.run_count(), 5);
+/// ```
+pub struct PersistentRle<T> {
+    root: Link<T>,
+}
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    item: T,
+    len: usize,
+    left: Link<T>,
+    right: Link<T>,
+    height: u8,
+
+    /// The number of items in this subtree.
+    total: usize,
+
+    /// The number of runs in this subtree.
+    runs: usize,
+}
+
+fn height<T>(link: &Link<T>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn total<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.total)
+}
+
+fn runs<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.runs)
+}
+
+/// Makes a node without balancing it.
+fn node<T>(left: Link<T>, item: T, len: usize, right: Link<T>) -> Link<T> {
+    Some(Rc::new(Node {
+        height: height(&left).max(height(&right)) + 1,
+        total: total(&left) + len + total(&right),
+        runs: runs(&left) + 1 + runs(&right),
+        item,
+        len,
+        left,
+        right,
+    }))
+}
+
+/// Makes a node from subtrees whose heights differ by at most 2, rotating
+/// it to keep it balanced.
+fn balance<T: Clone>(left: Link<T>, item: T, len: usize, right: Link<T>) -> Link<T> {
+    let (hl, hr) = (height(&left), height(&right));
+    if hl > hr + 1 {
+        let l = left.unwrap();
+        if height(&l.left) >= height(&l.right) {
+            let right = node(l.right.clone(), item, len, right);
+            node(l.left.clone(), l.item.clone(), l.len, right)
+        } else {
+            let lr = l.right.as_ref().unwrap();
+            let left = node(l.left.clone(), l.item.clone(), l.len, lr.left.clone());
+            let right = node(lr.right.clone(), item, len, right);
+            node(left, lr.item.clone(), lr.len, right)
+        }
+    } else if hr > hl + 1 {
+        let r = right.unwrap();
+        if height(&r.right) >= height(&r.left) {
+            let left = node(left, item, len, r.left.clone());
+            node(left, r.item.clone(), r.len, r.right.clone())
+        } else {
+            let rl = r.left.as_ref().unwrap();
+            let left = node(left, item, len, rl.left.clone());
+            let right = node(rl.right.clone(), r.item.clone(), r.len, r.right.clone());
+            node(left, rl.item.clone(), rl.len, right)
+        }
+    } else {
+        node(left, item, len, right)
+    }
+}
+
+/// Joins two trees of any height with a run between them.
+fn join<T: Clone>(left: Link<T>, item: T, len: usize, right: Link<T>) -> Link<T> {
+    let (hl, hr) = (height(&left), height(&right));
+    if hl > hr + 1 {
+        let l = left.unwrap();
+        let right = join(l.right.clone(), item, len, right);
+        balance(l.left.clone(), l.item.clone(), l.len, right)
+    } else if hr > hl + 1 {
+        let r = right.unwrap();
+        let left = join(left, item, len, r.left.clone());
+        balance(left, r.item.clone(), r.len, r.right.clone())
+    } else {
+        node(left, item, len, right)
+    }
+}
+
+/// Splits the tree into one of the first `pos` items and one of the rest,
+/// splitting the run at `pos` in two if needed.
+fn split<T: Clone>(link: &Link<T>, pos: usize) -> (Link<T>, Link<T>) {
+    let n = match link {
+        Some(n) => n,
+        None => return (None, None),
+    };
+    let start = total(&n.left);
+    if pos <= start {
+        let (a, b) = split(&n.left, pos);
+        (a, join(b, n.item.clone(), n.len, n.right.clone()))
+    } else if pos >= start + n.len {
+        let (a, b) = split(&n.right, pos - start - n.len);
+        (join(n.left.clone(), n.item.clone(), n.len, a), b)
+    } else {
+        let k = pos - start;
+        let a = join(n.left.clone(), n.item.clone(), k, None);
+        let b = join(None, n.item.clone(), n.len - k, n.right.clone());
+        (a, b)
+    }
+}
+
+/// Removes the last run of the tree.
+fn split_last<T: Clone>(n: &Rc<Node<T>>) -> (Link<T>, T, usize) {
+    match &n.right {
+        None => (n.left.clone(), n.item.clone(), n.len),
+        Some(right) => {
+            let (right, item, len) = split_last(right);
+            (
+                join(n.left.clone(), n.item.clone(), n.len, right),
+                item,
+                len,
+            )
+        }
+    }
+}
+
+/// Removes the first run of the tree.
+fn split_first<T: Clone>(n: &Rc<Node<T>>) -> (T, usize, Link<T>) {
+    match &n.left {
+        None => (n.item.clone(), n.len, n.right.clone()),
+        Some(left) => {
+            let (item, len, left) = split_first(left);
+            (
+                item,
+                len,
+                join(left, n.item.clone(), n.len, n.right.clone()),
+            )
+        }
+    }
+}
+
+/// Concatenates two trees, merging the runs where they meet if they are of
+/// the same item.
+fn concat<T: PartialEq + Clone>(left: Link<T>, right: Link<T>) -> Link<T> {
+    let (l, r) = match (&left, &right) {
+        (None, _) => return right,
+        (_, None) => return left,
+        (Some(l), Some(r)) => (l, r),
+    };
+    let (left, item, len) = split_last(l);
+    let (first, first_len, rest) = split_first(r);
+    if item == first {
+        join(left, item, len + first_len, rest)
+    } else {
+        join(left, item, len, join(None, first, first_len, rest))
+    }
+}
+
+impl<T> PersistentRle<T> {
+    /// Constructs a new, empty sequence.
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    /// The number of items in the sequence.
+    pub fn len(&self) -> usize {
+        total(&self.root)
+    }
+
+    /// Returns `true` if the sequence has no items.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// The number of runs the items are stored as.
+    pub fn run_count(&self) -> usize {
+        runs(&self.root)
+    }
+
+    /// Returns `true` if both versions are the same version, rather than
+    /// just containing the same items.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    /// Returns the item at `index`, or `None` if out of bounds.
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        let mut link = &self.root;
+        while let Some(n) = link {
+            let start = total(&n.left);
+            if index < start {
+                link = &n.left;
+            } else if index < start + n.len {
+                return Some(&n.item);
+            } else {
+                index -= start + n.len;
+                link = &n.right;
+            }
+        }
+        None
+    }
+
+    /// Iterates over the runs in order, as each run's item and length.
+    pub fn runs(&self) -> PersistentRuns<'_, T> {
+        let mut runs = PersistentRuns { stack: Vec::new() };
+        runs.push_left(&self.root);
+        runs
+    }
+
+    /// Iterates over the items in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.runs()
+            .flat_map(|(item, len)| std::iter::repeat_n(item, len))
+    }
+}
+
+impl<T> PersistentRle<T>
+where
+    T: PartialEq + Clone,
+{
+    /// Constructs a sequence of the items.
+    pub fn from_slice(items: &[T]) -> Self {
+        items.iter().cloned().collect()
+    }
+
+    /// Returns a new version with the item appended to the end.
+    pub fn push(&self, item: T) -> Self {
+        self.push_run(item, 1)
+    }
+
+    /// Returns a new version with a run of the item repeated `len` times
+    /// appended to the end.
+    pub fn push_run(&self, item: T, len: usize) -> Self {
+        if len == 0 {
+            return self.clone();
+        }
+        Self {
+            root: concat(self.root.clone(), node(None, item, len, None)),
+        }
+    }
+
+    /// Returns a new version with the item at `index` replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, item: T) -> Self {
+        assert!(index < self.len(), "index out of bounds");
+        self.splice(index..=index, Some(item))
+    }
+
+    /// Returns a new version with the item inserted at `index`, shifting all
+    /// the items after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&self, index: usize, item: T) -> Self {
+        self.splice(index..index, Some(item))
+    }
+
+    /// Returns a new version with the item at `index` removed, shifting all
+    /// the items after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: usize) -> Self {
+        assert!(index < self.len(), "index out of bounds");
+        self.splice(index..=index, None)
+    }
+
+    /// Returns a new version with the items in `range` replaced by the items
+    /// of `replace_with`.
+    ///
+    /// Only the runs at the edges of the range are split, and any runs of
+    /// the same item that end up next to each other are merged back together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    pub fn splice<R, I>(&self, range: R, replace_with: I) -> Self
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = bounds(range, self.len());
+        let (before, rest) = split(&self.root, start);
+        let (_, after) = split(&rest, end - start);
+        let added: Self = replace_with.into_iter().collect();
+        Self {
+            root: concat(concat(before, added.root), after),
+        }
+    }
+
+    /// Returns a new version with every item in `range` replaced with the
+    /// item. Unlike [splice](PersistentRle::splice), this takes `O(log n)`
+    /// time however large the range is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    pub fn fill_range<R: RangeBounds<usize>>(&self, range: R, item: T) -> Self {
+        let (start, end) = bounds(range, self.len());
+        if start == end {
+            return self.clone();
+        }
+        let (before, rest) = split(&self.root, start);
+        let (_, after) = split(&rest, end - start);
+        let filled = node(None, item, end - start, None);
+        Self {
+            root: concat(concat(before, filled), after),
+        }
+    }
+
+    /// Collects the items into a vector.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Clone for PersistentRle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T> Default for PersistentRle<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for PersistentRle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.runs()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentRle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.runs().eq(other.runs())
+    }
+}
+
+impl<T: Eq> Eq for PersistentRle<T> {}
+
+impl<T> FromIterator<T> for PersistentRle<T>
+where
+    T: PartialEq + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut root = None;
+        let mut run: Option<(T, usize)> = None;
+        for item in iter {
+            match &mut run {
+                Some((last, len)) if *last == item => *len += 1,
+                _ => {
+                    if let Some((item, len)) = run.replace((item, 1)) {
+                        root = join(root, item, len, None);
+                    }
+                }
+            }
+        }
+        if let Some((item, len)) = run {
+            root = join(root, item, len, None);
+        }
+        Self { root }
+    }
+}
+
+/// An iterator over the runs of a [PersistentRle], yielding each run's item
+/// and length.
+pub struct PersistentRuns<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> PersistentRuns<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(n) = link {
+            self.stack.push(n);
+            link = &n.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for PersistentRuns<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.stack.pop()?;
+        self.push_left(&n.right);
+        Some((&n.item, n.len))
+    }
+}
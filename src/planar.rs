@@ -0,0 +1,263 @@
+//! Encoding for interleaved data, such as RGBA pixels or `(tile, rotation)`
+//! pairs, one channel at a time.
+//!
+//! Each channel is split out into its own sequence (or plane) and encoded
+//! with its own table, then the planes are interleaved back together when
+//! decoding. The runs in each channel are usually far longer than the runs
+//! of the combined values, so this compresses much better.
+//!
+//! ```
+//! use rle::{planar, Table};
+//!
+//! // Two rows of RGBA pixels that only differ by their alpha.
+//! let pixels = [
+//!     255, 0, 0, 255, 255, 0, 0, 128, 255, 0, 0, 255, 255, 0, 0, 128,
+//!     255, 0, 0, 255, 255, 0, 0, 128, 255, 0, 0, 255, 255, 0, 0, 128u8,
+//! ];
+//! let mut tables = vec![Table::default(); 4];
+//! let planes = planar::encode(&mut tables, &pixels);
+//! assert_eq!(planes[0], [(0, 8)]);
+//! assert_eq!(planes[3].len(), 8);
+//! assert_eq!(planar::decode(&tables, &planes).unwrap(), pixels);
+//! ```
+
+use crate::bulk::MAX_TABLE_LEN;
+use crate::{runs_of_slice, Error, Index, Table, TableStore};
+
+/// Splits interleaved items into one plane per channel, so that plane `c`
+/// contains every item at position `i * channels + c`.
+///
+/// # Panics
+///
+/// Panics if `channels` is 0, or the number of items is not a multiple of it.
+///
+/// ```
+/// # use rle::planar::deinterleave;
+/// assert_eq!(deinterleave(&[1, 2, 3, 4, 5, 6], 2), [[1, 3, 5], [2, 4, 6]]);
+/// ```
+pub fn deinterleave<T: Clone>(items: &[T], channels: usize) -> Vec<Vec<T>> {
+    assert!(channels > 0, "cannot split items into 0 channels");
+    assert!(
+        items.len().is_multiple_of(channels),
+        "{} items cannot be split into {} channels",
+        items.len(),
+        channels
+    );
+    (0..channels)
+        .map(|c| items.iter().skip(c).step_by(channels).cloned().collect())
+        .collect()
+}
+
+/// Interleaves the planes back together, reversing [deinterleave].
+///
+/// # Errors
+///
+/// Returns [PlaneLengthMismatch](Error::PlaneLengthMismatch) if the planes
+/// are not all the same length.
+///
+/// ```
+/// # use rle::planar::interleave;
+/// assert_eq!(interleave(&[vec![1, 3, 5], vec![2, 4, 6]]).unwrap(), [1, 2, 3, 4, 5, 6]);
+/// assert!(interleave(&[vec![1, 3, 5], vec![2, 4]]).is_err());
+/// ```
+pub fn interleave<T: Clone>(planes: &[Vec<T>]) -> Result<Vec<T>, Error> {
+    let len = planes.first().map_or(0, Vec::len);
+    if let Some(plane) = planes.iter().position(|plane| plane.len() != len) {
+        return Err(Error::PlaneLengthMismatch(plane, planes[plane].len()));
+    }
+    let mut items = Vec::with_capacity(len * planes.len());
+    for i in 0..len {
+        items.extend(planes.iter().map(|plane| plane[i].clone()));
+    }
+    Ok(items)
+}
+
+/// Run-length encodes the interleaved items one channel at a time, using
+/// one table per channel and adding items to them as they are found.
+/// Returns the runs of each channel.
+///
+/// # Panics
+///
+/// Panics if there are no tables, or the number of items is not a multiple
+/// of the number of tables.
+pub fn encode<T, S>(tables: &mut [Table<T, S>], items: &[T]) -> Vec<Vec<(Index, usize)>>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    let planes = deinterleave(items, tables.len());
+    tables
+        .iter_mut()
+        .zip(&planes)
+        .map(|(table, plane)| table.encode_mut(plane).collect())
+        .collect()
+}
+
+/// Decodes the runs of each channel with its table, and interleaves the
+/// channels back together.
+///
+/// # Errors
+///
+/// Returns [PlaneCountMismatch](Error::PlaneCountMismatch) if there is not
+/// one plane per table, [InvalidRun](Error::InvalidRun) if a run is not in
+/// its channel's table, or [PlaneLengthMismatch](Error::PlaneLengthMismatch)
+/// if the channels do not decode to the same number of items.
+pub fn decode<T, S>(tables: &[Table<T, S>], planes: &[Vec<(Index, usize)>]) -> Result<Vec<T>, Error>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    if planes.len() != tables.len() {
+        return Err(Error::PlaneCountMismatch(planes.len(), tables.len()));
+    }
+    let planes = tables
+        .iter()
+        .zip(planes)
+        .map(|(table, runs)| {
+            let mut plane = Vec::new();
+            table.decode_into(runs, &mut plane)?;
+            Ok(plane)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    interleave(&planes)
+}
+
+/// Like [encode], but encodes each channel as bytes (see
+/// [encode_bytes](Table::encode_bytes)).
+///
+/// # Errors
+///
+/// Returns [TableTooLarge](Error::TableTooLarge) if a channel has too many
+/// different items to encode as bytes, in which case none of the tables
+/// are changed.
+///
+/// # Panics
+///
+/// Panics if there are no tables, or the number of items is not a multiple
+/// of the number of tables.
+///
+/// ```
+/// # use rle::{planar, Table};
+/// let pairs = [(0u16, 0u8), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1)];
+/// let items: Vec<u16> = pairs.iter().flat_map(|&(tile, rot)| [tile, rot as u16]).collect();
+///
+/// let mut tables = vec![Table::default(); 2];
+/// let planes = planar::encode_bytes(&mut tables, &items).unwrap();
+/// assert_eq!(planes[0], [0x01, 0x04, 0x03, 0x02]);
+/// assert_eq!(planar::decode_bytes(&tables, &planes).unwrap(), items);
+/// ```
+pub fn encode_bytes<T, S>(tables: &mut [Table<T, S>], items: &[T]) -> Result<Vec<Vec<u8>>, Error>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    let planes = deinterleave(items, tables.len());
+    for (table, plane) in tables.iter().zip(&planes) {
+        let mut missing: Vec<&T> = Vec::new();
+        for (item, _) in runs_of_slice(plane) {
+            if table.get_index(item).is_none() && !missing.contains(&item) {
+                missing.push(item);
+                if table.len() + missing.len() > MAX_TABLE_LEN {
+                    return Err(Error::TableTooLarge(table.len() + missing.len()));
+                }
+            }
+        }
+    }
+    tables
+        .iter_mut()
+        .zip(&planes)
+        .map(|(table, plane)| {
+            table.extend_from_slice(plane);
+            let mut bytes = Vec::new();
+            table.encode_bytes_into(plane, &mut bytes)?;
+            Ok(bytes)
+        })
+        .collect()
+}
+
+/// Decodes bytes produced by [encode_bytes], interleaving the channels back
+/// together.
+///
+/// # Errors
+///
+/// Returns [PlaneCountMismatch](Error::PlaneCountMismatch) if there is not
+/// one plane per table, the same errors as [decode_bytes_into](Table::decode_bytes_into),
+/// or [PlaneLengthMismatch](Error::PlaneLengthMismatch) if the channels do
+/// not decode to the same number of items.
+pub fn decode_bytes<T, S>(tables: &[Table<T, S>], planes: &[Vec<u8>]) -> Result<Vec<T>, Error>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    if planes.len() != tables.len() {
+        return Err(Error::PlaneCountMismatch(planes.len(), tables.len()));
+    }
+    let planes = tables
+        .iter()
+        .zip(planes)
+        .map(|(table, bytes)| {
+            let mut plane = Vec::new();
+            table.decode_bytes_into(bytes, &mut plane)?;
+            Ok(plane)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    interleave(&planes)
+}
+
+/// Run-length encodes pairs of items as two channels, such as `(tile, rotation)`
+/// pairs where each half has its own type, adding items to the tables as
+/// they are found. Returns the runs of each half.
+///
+/// ```
+/// # use rle::{planar, Table};
+/// let tiles = [("grass", 0), ("grass", 1), ("grass", 2), ("water", 2)];
+/// let (mut names, mut rotations) = (Table::default(), Table::default());
+/// let (a, b) = planar::encode_pairs(&mut names, &mut rotations, &tiles);
+/// assert_eq!((a.len(), b.len()), (2, 3));
+/// assert_eq!(planar::decode_pairs(&names, &rotations, &a, &b).unwrap(), tiles);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn encode_pairs<A, B, SA, SB>(
+    first: &mut Table<A, SA>,
+    second: &mut Table<B, SB>,
+    items: &[(A, B)],
+) -> (Vec<(Index, usize)>, Vec<(Index, usize)>)
+where
+    A: PartialEq + Clone,
+    B: PartialEq + Clone,
+    SA: TableStore<A>,
+    SB: TableStore<B>,
+{
+    let (a, b): (Vec<A>, Vec<B>) = items.iter().cloned().unzip();
+    let a = first.encode_mut(&a).collect();
+    let b = second.encode_mut(&b).collect();
+    (a, b)
+}
+
+/// Decodes runs produced by [encode_pairs] back into pairs.
+///
+/// # Errors
+///
+/// Returns [InvalidRun](Error::InvalidRun) if a run is not in its table,
+/// or [PlaneLengthMismatch](Error::PlaneLengthMismatch) if the two halves
+/// do not decode to the same number of items.
+pub fn decode_pairs<A, B, SA, SB>(
+    first: &Table<A, SA>,
+    second: &Table<B, SB>,
+    first_runs: &[(Index, usize)],
+    second_runs: &[(Index, usize)],
+) -> Result<Vec<(A, B)>, Error>
+where
+    A: PartialEq + Clone,
+    B: PartialEq + Clone,
+    SA: TableStore<A>,
+    SB: TableStore<B>,
+{
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    first.decode_into(first_runs, &mut a)?;
+    second.decode_into(second_runs, &mut b)?;
+    if a.len() != b.len() {
+        return Err(Error::PlaneLengthMismatch(1, b.len()));
+    }
+    Ok(a.into_iter().zip(b).collect())
+}
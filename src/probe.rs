@@ -0,0 +1,135 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+
+/// The number of hashes kept to estimate the number of distinct symbols
+/// once a probe is no longer exact.
+const SKETCH_SIZE: usize = 256;
+
+/// A lightweight counter that estimates how many distinct symbols a sequence
+/// contains and roughly how often they appear, so that an encoding format
+/// can be picked before committing to an encode.
+///
+/// Counts are exact until more than `limit` distinct symbols have been seen.
+/// After that, the number of distinct symbols is estimated from a sketch of
+/// their hashes, and frequencies are only kept for the most common symbols
+/// (each count may be low by at most `total / (limit + 1)`).
+#[derive(Clone, Debug)]
+pub struct AlphabetProbe<T> {
+    counts: BTreeMap<T, usize>,
+    sketch: BTreeSet<u64>,
+    limit: usize,
+    total: usize,
+    exact: bool,
+}
+
+impl<T> Default for AlphabetProbe<T>
+where
+    T: Ord + Clone + Hash,
+{
+    fn default() -> Self {
+        Self::with_limit(256)
+    }
+}
+
+impl<T> AlphabetProbe<T>
+where
+    T: Ord + Clone + Hash,
+{
+    /// Constructs a new probe that counts exactly up to `limit` distinct symbols.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            sketch: BTreeSet::new(),
+            limit: limit.max(1),
+            total: 0,
+            exact: true,
+        }
+    }
+
+    /// Constructs a new probe with the default limit and feeds it every
+    /// item from the iterator.
+    pub fn probe<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut probe = Self::default();
+        probe.extend(iter);
+        probe
+    }
+
+    /// Counts a single item.
+    pub fn push(&mut self, item: T) {
+        self.total += 1;
+
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.sketch.len() < SKETCH_SIZE {
+            self.sketch.insert(hash);
+        } else if hash < *self.sketch.iter().next_back().unwrap() && self.sketch.insert(hash) {
+            let max = *self.sketch.iter().next_back().unwrap();
+            self.sketch.remove(&max);
+        }
+
+        if let Some(count) = self.counts.get_mut(&item) {
+            *count += 1;
+        } else if self.counts.len() < self.limit {
+            self.counts.insert(item, 1);
+        } else {
+            self.exact = false;
+            self.counts.retain(|_, count| {
+                *count -= 1;
+                *count > 0
+            });
+        }
+    }
+
+    /// Counts every item from the iterator.
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+
+    /// The total number of items counted.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns `true` if the counts are exact, which is the case as long
+    /// as no more than `limit` distinct symbols have been seen.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// The number of distinct symbols seen, which is an estimate if the
+    /// probe is no longer [exact](AlphabetProbe::is_exact).
+    pub fn distinct(&self) -> usize {
+        if self.exact {
+            self.counts.len()
+        } else if self.sketch.len() < SKETCH_SIZE {
+            self.sketch.len()
+        } else {
+            let max = *self.sketch.iter().next_back().unwrap() as f64;
+            (((SKETCH_SIZE - 1) as f64) * (u64::MAX as f64) / max) as usize
+        }
+    }
+
+    /// Returns `true` if the symbols are likely to fit in a table small
+    /// enough to be encoded with [encode_bytes](crate::Table::encode_bytes).
+    pub fn fits_in_bytes(&self) -> bool {
+        self.distinct() < 128
+    }
+
+    /// An iterator over the symbols and how many times they appeared, in
+    /// sorted order. If the probe is no longer exact, only the most common
+    /// symbols are included and their counts are approximate.
+    pub fn frequencies(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// The symbols and their counts, sorted from most to least common.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut freqs: Vec<(&T, usize)> = self.frequencies().collect();
+        freqs.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        freqs
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{Error, Index, Scanlines, Table, TableStore};
+use std::cmp::Ordering;
+use std::convert::TryInto;
+
+/// The byte that marks a node split into four quadrants in [Quadtree::to_bytes].
+const SPLIT: u8 = 255;
+
+/// The width and height that [Quadtree::to_bytes] writes before the nodes.
+const HEADER_LEN: usize = 8;
+
+/// A node in a [Quadtree].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuadNode {
+    /// The whole region has the same index.
+    Leaf(Index),
+
+    /// The region is split into four quadrants, in the order top-left,
+    /// top-right, bottom-left, bottom-right.
+    Split(Box<[QuadNode; 4]>),
+}
+
+/// A region quadtree of table indices covering a 2D grid.
+///
+/// Blocky data, where large square areas share the same item, can sometimes
+/// be stored more compactly as a quadtree than as runs. Use [compare_size](Quadtree::compare_size)
+/// to decide which representation to keep for a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quadtree {
+    width: usize,
+    height: usize,
+    size: usize,
+    root: QuadNode,
+}
+
+impl Quadtree {
+    /// Builds a quadtree from a grid of table indices stored row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0 or the number of indices is not a multiple of `width`.
+    pub fn from_indices(indices: &[Index], width: usize) -> Self {
+        assert!(width > 0, "width must be greater than 0");
+        assert_eq!(indices.len() % width, 0, "grid must be rectangular");
+        let height = indices.len() / width;
+        let size = width.max(height).next_power_of_two();
+        let root = build(indices, width, height, 0, 0, size).unwrap_or(QuadNode::Leaf(0));
+        Self {
+            width,
+            height,
+            size,
+            root,
+        }
+    }
+
+    /// Builds a quadtree from bytes produced by [encode_scanlines](Table::encode_scanlines),
+    /// where every row is `width` items wide.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBytes](Error::InvalidBytes) error if any row does not
+    /// decode to exactly `width` items from the table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn from_scanlines<T, S>(
+        table: &Table<T, S>,
+        bytes: &[u8],
+        width: usize,
+    ) -> Result<Self, Error>
+    where
+        T: PartialEq + Clone,
+        S: TableStore<T>,
+    {
+        let mut indices = Vec::new();
+        let mut offset = 0;
+        for row in Scanlines::new(bytes) {
+            let start = indices.len();
+            for item in table.decode_bytes(row) {
+                indices.push(table.get_index(item).unwrap());
+            }
+            if indices.len() - start != width {
+                return Err(Error::InvalidBytes(offset));
+            }
+            offset += row.len() + 2;
+        }
+        Ok(Self::from_indices(&indices, width))
+    }
+
+    /// The width of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The root node of the tree.
+    pub fn root(&self) -> &QuadNode {
+        &self.root
+    }
+
+    /// The index at a position in the grid, or `None` if out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<Index> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let (mut node, mut x, mut y, mut size) = (&self.root, x, y, self.size);
+        loop {
+            match node {
+                QuadNode::Leaf(ind) => return Some(*ind),
+                QuadNode::Split(children) => {
+                    size /= 2;
+                    let quad = (x >= size) as usize + 2 * (y >= size) as usize;
+                    node = &children[quad];
+                    x %= size;
+                    y %= size;
+                }
+            }
+        }
+    }
+
+    /// The table indices of every position in the grid, row by row.
+    pub fn to_indices(&self) -> Vec<Index> {
+        let mut indices = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                indices.push(self.get(x, y).unwrap());
+            }
+        }
+        indices
+    }
+
+    /// Converts the tree back into scanline-encoded bytes. See [encode_scanlines](Table::encode_scanlines).
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_scanlines](Table::encode_scanlines),
+    /// or with a [TableMissingItems](Error::TableMissingItems) error if the tree
+    /// contains indices that are not in the table.
+    pub fn to_scanlines<T, S>(&self, table: &Table<T, S>) -> Result<Vec<u8>, Error>
+    where
+        T: PartialEq + Clone,
+        S: TableStore<T>,
+    {
+        let mut items = Vec::with_capacity(self.width * self.height);
+        for (i, ind) in self.to_indices().into_iter().enumerate() {
+            items.push(table.item(ind).ok_or(Error::TableMissingItems(i))?.clone());
+        }
+        table.encode_scanlines(&items, self.width)
+    }
+
+    /// Serializes the tree, starting with its width and height as two
+    /// little-endian `u32`s, followed by its nodes in pre-order with one byte
+    /// per node: `255` for a split node (followed by its four children), or
+    /// the index of a leaf.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidGridSize](Error::InvalidGridSize) error if the width
+    /// or height doesn't fit in a `u32`, or a [TableTooLarge](Error::TableTooLarge)
+    /// error if a leaf has an index that doesn't fit in a byte below `255`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let (w, h): (u32, u32) = match (self.width.try_into(), self.height.try_into()) {
+            (Ok(w), Ok(h)) => (w, h),
+            _ => return Err(Error::InvalidGridSize(self.width * self.height)),
+        };
+        let mut bytes = Vec::with_capacity(self.encoded_size());
+        bytes.extend_from_slice(&w.to_le_bytes());
+        bytes.extend_from_slice(&h.to_le_bytes());
+        write_node(&self.root, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes a tree produced by [to_bytes](Quadtree::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes
+    /// end before the header or the last node, an [InvalidGridSize](Error::InvalidGridSize)
+    /// error if the width is 0 or the grid is too large to index, or an
+    /// [InvalidBytes](Error::InvalidBytes) error with the offset of the problem
+    /// if a single cell is split or there are bytes left over after the tree.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let header = bytes.get(..HEADER_LEN).ok_or(Error::TruncatedStream)?;
+        let width = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let height = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        let size = width
+            .checked_mul(height)
+            .filter(|_| width > 0)
+            .and_then(|_| width.max(height).checked_next_power_of_two())
+            .ok_or(Error::InvalidGridSize(width.saturating_mul(height)))?;
+        let mut offset = HEADER_LEN;
+        let root = read_node(bytes, &mut offset, size)?;
+        if offset < bytes.len() {
+            return Err(Error::InvalidBytes(offset));
+        }
+        Ok(Self {
+            width,
+            height,
+            size,
+            root,
+        })
+    }
+
+    /// The number of bytes that [to_bytes](Quadtree::to_bytes) will produce.
+    pub fn encoded_size(&self) -> usize {
+        HEADER_LEN + count_nodes(&self.root)
+    }
+
+    /// Compares the size of the tree as bytes against the size of the same
+    /// grid encoded as scanlines, returning [Less](Ordering::Less) if the tree
+    /// is smaller.
+    pub fn compare_size(&self, scanlines: &[u8]) -> Ordering {
+        self.encoded_size().cmp(&scanlines.len())
+    }
+}
+
+/// Builds the node for the square region at (x, y), or returns `None` if
+/// the region lies entirely outside the grid.
+fn build(
+    indices: &[Index],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    size: usize,
+) -> Option<QuadNode> {
+    if x >= width || y >= height {
+        return None;
+    }
+    if size == 1 {
+        return Some(QuadNode::Leaf(indices[y * width + x]));
+    }
+    let half = size / 2;
+    let children = [
+        build(indices, width, height, x, y, half),
+        build(indices, width, height, x + half, y, half),
+        build(indices, width, height, x, y + half, half),
+        build(indices, width, height, x + half, y + half, half),
+    ];
+
+    // If every child inside the grid is the same leaf, merge them
+    let mut leaf = None;
+    let mut same = true;
+    for child in children.iter().flatten() {
+        match (child, leaf) {
+            (QuadNode::Leaf(ind), None) => leaf = Some(*ind),
+            (QuadNode::Leaf(ind), Some(prev)) if *ind == prev => {}
+            _ => same = false,
+        }
+    }
+    if same {
+        return leaf.map(QuadNode::Leaf);
+    }
+
+    let fill = leaf.unwrap_or(0);
+    let [a, b, c, d] = children;
+    let fill = |child: Option<QuadNode>| child.unwrap_or(QuadNode::Leaf(fill));
+    Some(QuadNode::Split(Box::new([
+        fill(a),
+        fill(b),
+        fill(c),
+        fill(d),
+    ])))
+}
+
+fn write_node(node: &QuadNode, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    match node {
+        QuadNode::Leaf(ind) if *ind < SPLIT as usize => bytes.push(*ind as u8),
+        QuadNode::Leaf(ind) => return Err(Error::TableTooLarge(ind + 1)),
+        QuadNode::Split(children) => {
+            bytes.push(SPLIT);
+            for child in children.iter() {
+                write_node(child, bytes)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the node at `offset` covering a square region of `size` cells.
+fn read_node(bytes: &[u8], offset: &mut usize, size: usize) -> Result<QuadNode, Error> {
+    let byte = *bytes.get(*offset).ok_or(Error::TruncatedStream)?;
+    *offset += 1;
+    if byte != SPLIT {
+        return Ok(QuadNode::Leaf(byte as Index));
+    }
+    if size == 1 {
+        return Err(Error::InvalidBytes(*offset - 1));
+    }
+    let half = size / 2;
+    Ok(QuadNode::Split(Box::new([
+        read_node(bytes, offset, half)?,
+        read_node(bytes, offset, half)?,
+        read_node(bytes, offset, half)?,
+        read_node(bytes, offset, half)?,
+    ])))
+}
+
+fn count_nodes(node: &QuadNode) -> usize {
+    match node {
+        QuadNode::Leaf(_) => 1,
+        QuadNode::Split(children) => 1 + children.iter().map(count_nodes).sum::<usize>(),
+    }
+}
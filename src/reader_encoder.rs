@@ -0,0 +1,130 @@
+use crate::{Error, SortedStore, Table, TableStore};
+use std::io::{self, ErrorKind, Read};
+use std::iter::FusedIterator;
+
+/// An iterator that run-length encodes bytes read from a [Read] source into
+/// a compressed byte format, without loading the whole source into memory.
+/// See [encode_bytes_from_reader](crate::Table::encode_bytes_from_reader).
+pub struct ReaderEncoder<'a, R, S = SortedStore<u8>> {
+    pub(crate) table: &'a Table<u8, S>,
+    pub(crate) reader: R,
+    pub(crate) buf: Box<[u8]>,
+    pub(crate) pos: usize,
+    pub(crate) filled: usize,
+    pub(crate) read: usize,
+    pub(crate) len: Option<u8>,
+    pub(crate) failed: bool,
+}
+
+impl<'a, R, S> ReaderEncoder<'a, R, S>
+where
+    R: Read,
+    S: TableStore<u8>,
+{
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        while self.pos == self.filled {
+            match self.reader.read(&mut self.buf) {
+                Ok(0) => return Ok(None),
+                Ok(n) => {
+                    self.pos = 0;
+                    self.filled = n;
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Some(self.buf[self.pos]))
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        if let Some(len) = self.len.take() {
+            return Ok(Some(len));
+        }
+        let item = match self.peek()? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let ind = self.table.get_index(&item).ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidData, Error::TableMissingItems(self.read))
+        })? as u8;
+        let mut len = 0;
+        while len < 127 && self.peek()? == Some(item) {
+            self.pos += 1;
+            self.read += 1;
+            len += 1;
+        }
+        if len > 1 {
+            self.len = Some(len);
+            Ok(Some((ind << 1) | 1))
+        } else {
+            Ok(Some(ind << 1))
+        }
+    }
+}
+
+impl<'a, R, S> Iterator for ReaderEncoder<'a, R, S>
+where
+    R: Read,
+    S: TableStore<u8>,
+{
+    type Item = io::Result<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let byte = self.next_byte().transpose();
+        // Nothing more is encoded after an error
+        self.failed = matches!(byte, Some(Err(_)));
+        byte
+    }
+}
+
+impl<'a, R, S> FusedIterator for ReaderEncoder<'a, R, S>
+where
+    R: Read,
+    S: TableStore<u8>,
+{
+}
+
+impl<S> Table<u8, S>
+where
+    S: TableStore<u8>,
+{
+    /// Returns an iterator that run-length encodes bytes read from `reader`
+    /// into the same format as [encode_bytes](Table::encode_bytes).
+    ///
+    /// The source is read in small blocks as the iterator advances, so even
+    /// very large files can be encoded without loading them into memory. The
+    /// reader is already buffered internally, so there is no need to wrap it
+    /// in a [BufReader](std::io::BufReader).
+    ///
+    /// If the source contains a byte that is not in the table, the iterator
+    /// will yield an [InvalidData](ErrorKind::InvalidData) error wrapping a
+    /// [TableMissingItems](Error::TableMissingItems) error. The iterator ends
+    /// after the first error, whether it came from the table or the reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableTooLarge](Error::TableTooLarge) error if the table
+    /// contains >= 128 items.
+    pub fn encode_bytes_from_reader<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<ReaderEncoder<'_, R, S>, Error> {
+        if self.len() < 128 {
+            Ok(ReaderEncoder {
+                table: self,
+                reader,
+                buf: vec![0; 8 * 1024].into_boxed_slice(),
+                pos: 0,
+                filled: 0,
+                read: 0,
+                len: None,
+                failed: false,
+            })
+        } else {
+            Err(Error::TableTooLarge(self.len()))
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::bulk::{push_run, read_run};
+use crate::{Error, Index, Table, TableStore};
+
+/// Translates runs to new indices, such as after a table is reordered with
+/// [optimize_for](crate::Table::optimize_for), merged into another with
+/// [merge](crate::Table::merge), or has items removed with [remove](crate::Table::remove).
+///
+/// `mapping` holds the new index for each old index, either as an [Index]
+/// or as an `Option<Index>` where `None` means the item was removed. Adjacent
+/// runs that end up with the same index are merged.
+///
+/// # Errors
+///
+/// Returns an [InvalidRun](Error::InvalidRun) error if a run's index is not
+/// in the mapping, or was mapped to `None`.
+///
+/// ```
+/// # use rle::remap_runs;
+/// let runs = [(0, 2), (1, 3), (2, 1)];
+/// assert_eq!(remap_runs(&runs, &[1, 0, 0]).unwrap(), [(1, 2), (0, 4)]);
+/// assert!(remap_runs(&runs, &[Some(1), None, Some(0)]).is_err());
+/// ```
+pub fn remap_runs<M>(runs: &[(Index, usize)], mapping: &[M]) -> Result<Vec<(Index, usize)>, Error>
+where
+    M: Copy + Into<Option<Index>>,
+{
+    let mut remapped: Vec<(Index, usize)> = Vec::with_capacity(runs.len());
+    for (i, &(ind, len)) in runs.iter().enumerate() {
+        let new_ind = remap(mapping, ind).ok_or(Error::InvalidRun(i, ind))?;
+        match remapped.last_mut() {
+            Some(last) if last.0 == new_ind => last.1 += len,
+            _ => remapped.push((new_ind, len)),
+        }
+    }
+    Ok(remapped)
+}
+
+/// Translates run-length encoded bytes (see [encode_bytes](crate::Table::encode_bytes))
+/// to new indices, like [remap_runs] but without decoding the runs first.
+///
+/// # Errors
+///
+/// Returns an [UnknownIndex](Error::UnknownIndex) error if a run's index is
+/// not in the mapping or was mapped to `None`, a [TableTooLarge](Error::TableTooLarge)
+/// error if it was mapped to an index too large for the format, or a
+/// [TruncatedStream](Error::TruncatedStream) error if the bytes end in the
+/// middle of a run.
+///
+/// ```
+/// # use rle::remap_bytes;
+/// let bytes = [0x01, 0x02, 0x03, 0x03, 0x04];
+/// assert_eq!(remap_bytes(&bytes, &[1, 0, 0]).unwrap(), [0x03, 0x02, 0x01, 0x04]);
+/// ```
+pub fn remap_bytes<M>(bytes: &[u8], mapping: &[M]) -> Result<Vec<u8>, Error>
+where
+    M: Copy + Into<Option<Index>>,
+{
+    let mut remapped = Vec::with_capacity(bytes.len());
+    let mut run: Option<(Index, usize)> = None;
+    let mut offset = 0;
+    while let Some((ind, len, size)) = read_run(bytes, offset)? {
+        let new_ind = remap(mapping, ind).ok_or(Error::UnknownIndex(ind))?;
+        if new_ind >= 128 {
+            return Err(Error::TableTooLarge(new_ind + 1));
+        }
+        match &mut run {
+            Some(run) if run.0 == new_ind => run.1 += len,
+            run => {
+                if let Some((ind, len)) = run.replace((new_ind, len)) {
+                    push_run(&mut remapped, ind as u8, len);
+                }
+            }
+        }
+        offset += size;
+    }
+    if let Some((ind, len)) = run {
+        push_run(&mut remapped, ind as u8, len);
+    }
+    Ok(remapped)
+}
+
+fn remap<M>(mapping: &[M], ind: Index) -> Option<Index>
+where
+    M: Copy + Into<Option<Index>>,
+{
+    mapping.get(ind).and_then(|&new_ind| new_ind.into())
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Returns a mapping from each of this table's indices to the index of
+    /// the same item in `target`, or `None` if `target` does not have it.
+    /// This can be passed to [remap_runs] or [remap_bytes] to move data
+    /// encoded with this table over to `target`.
+    ///
+    /// ```
+    /// # use rle::{remap_runs, Table};
+    /// let old = Table::from_slice(&['A', 'B', 'C']);
+    /// let new = Table::from_slice(&['C', 'A']);
+    /// let mapping = old.remap_to(&new);
+    /// assert_eq!(mapping, [Some(1), None, Some(0)]);
+    /// assert_eq!(remap_runs(&[(2, 3), (0, 1)], &mapping).unwrap(), [(0, 3), (1, 1)]);
+    /// ```
+    pub fn remap_to<S2>(&self, target: &Table<T, S2>) -> Vec<Option<Index>>
+    where
+        S2: TableStore<T>,
+    {
+        (0..self.len())
+            .filter_map(|ind| self.item(ind))
+            .map(|item| target.index_of(item))
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use crate::byte_scan::run_len;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::iter::repeat_n;
+use std::ops::{Deref, DerefMut};
+
+/// A wrapper around a `Vec<T>` that is serialized as a sequence of runs,
+/// each a pair of an item and how many times it repeats. Enabled with the
+/// `serde` feature.
+///
+/// Wrapping a field in this type is all it takes for large, mostly uniform
+/// arrays to take up a fraction of the space when saved. The wrapper derefs
+/// to the vector, so the rest of the code can use it as before.
+///
+/// ```
+/// use rle::RleSeq;
+///
+/// let tiles = RleSeq(vec![0, 0, 0, 0, 1, 1, 0]);
+/// let json = serde_json::to_string(&tiles).unwrap();
+/// assert_eq!(json, "[[0,4],[1,2],[0,1]]");
+///
+/// let loaded: RleSeq<u8> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(loaded, tiles);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RleSeq<T>(pub Vec<T>);
+
+impl<T> RleSeq<T> {
+    /// Unwraps the sequence, returning the vector.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for RleSeq<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> From<RleSeq<T>> for Vec<T> {
+    fn from(seq: RleSeq<T>) -> Self {
+        seq.0
+    }
+}
+
+impl<T> Deref for RleSeq<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for RleSeq<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Serialize for RleSeq<T>
+where
+    T: PartialEq + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items = &self.0[..];
+        serializer.collect_seq(std::iter::from_fn(|| {
+            let item = items.first()?;
+            let len = run_len(items);
+            items = &items[len..];
+            Some((item, len))
+        }))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RleSeq<T>
+where
+    T: Clone + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let runs = Vec::<(T, usize)>::deserialize(deserializer)?;
+        let mut items = Vec::new();
+        for (i, (item, len)) in runs.into_iter().enumerate() {
+            // The lengths are untrusted, so reserving for them may fail
+            if items.len().checked_add(len).is_none() || items.try_reserve(len).is_err() {
+                return Err(de::Error::custom(format!(
+                    "run [{}] of length {} is too long",
+                    i, len
+                )));
+            }
+            items.extend(repeat_n(item, len));
+        }
+        Ok(Self(items))
+    }
+}
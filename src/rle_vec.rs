@@ -0,0 +1,514 @@
+use crate::{Decoder, Index, Table};
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+use std::sync::OnceLock;
+
+/// A vector that permanently stores its items in run-length encoded form,
+/// while still allowing them to be accessed by position.
+///
+/// Each run stores the position where it ends, so indexed access with
+/// [get](RleVec::get) is a binary search over the runs, taking `O(log n)`
+/// time for `n` runs. This makes it possible to keep large, mostly uniform
+/// buffers (such as tile layers or voxel columns) compressed in memory.
+///
+/// ```
+/// use rle::RleVec;
+///
+/// let vec: RleVec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+/// assert_eq!(vec.len(), 18);
+/// assert_eq!(vec.runs().len(), 3);
+/// assert_eq!(vec.get(7), Some(&'B'));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RleVec<T> {
+    table: Table<T>,
+    runs: Vec<(Index, usize)>,
+
+    /// The position in the sequence where each run ends.
+    ends: Vec<usize>,
+
+    /// For each item in the table, the runs of that item as their start
+    /// position, length, and the number of times the item appears before
+    /// them. Built on demand by [rank](RleVec::rank) and [select](RleVec::select),
+    /// and thrown away whenever the runs change.
+    occurrences: OnceLock<Vec<Vec<Occurrence>>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Occurrence {
+    start: usize,
+    len: usize,
+    before: usize,
+}
+
+impl<T> Default for RleVec<T> {
+    fn default() -> Self {
+        Self {
+            table: Table::default(),
+            runs: Vec::new(),
+            ends: Vec::new(),
+            occurrences: OnceLock::new(),
+        }
+    }
+}
+
+impl<T> RleVec<T>
+where
+    T: Ord + Clone,
+{
+    /// Constructs a new, empty vector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new vector containing the items of a slice.
+    pub fn from_slice(items: &[T]) -> Self {
+        let mut vec = Self::new();
+        vec.extend_from_slice(items);
+        vec
+    }
+
+    /// The number of items in the vector.
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the vector contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// The table of unique items in the vector.
+    pub fn table(&self) -> &Table<T> {
+        &self.table
+    }
+
+    /// The runs the vector is made of.
+    pub fn runs(&self) -> &[(Index, usize)] {
+        &self.runs
+    }
+
+    /// Returns the position of the run containing the item at `index`,
+    /// or `None` if out of bounds.
+    pub(crate) fn run_at(&self, index: usize) -> Option<usize> {
+        let run = self.ends.partition_point(|&end| end <= index);
+        (run < self.runs.len()).then_some(run)
+    }
+
+    /// Returns a reference to the item at `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let run = self.run_at(index)?;
+        self.table.get(self.runs[run].0)
+    }
+
+    /// The first item in the vector, or `None` if it is empty.
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// The last item in the vector, or `None` if it is empty.
+    pub fn last(&self) -> Option<&T> {
+        self.runs.last().and_then(|&(ind, _)| self.table.get(ind))
+    }
+
+    /// Appends an item to the end of the vector, extending the last run if
+    /// it is the same item.
+    pub fn push(&mut self, item: T) {
+        self.push_run(item, 1);
+    }
+
+    /// Appends `len` copies of an item to the end of the vector.
+    pub fn push_run(&mut self, item: T, len: usize) {
+        if len > 0 {
+            let ind = self.table.insert_or_get(&item);
+            self.push_index(ind, len);
+        }
+    }
+
+    fn push_index(&mut self, ind: Index, len: usize) {
+        self.occurrences.take();
+        let end = self.len() + len;
+        match self.runs.last_mut() {
+            Some(last) if last.0 == ind => {
+                last.1 += len;
+                *self.ends.last_mut().unwrap() = end;
+            }
+            _ => {
+                self.runs.push((ind, len));
+                self.ends.push(end);
+            }
+        }
+    }
+
+    /// Appends every item of the slice to the end of the vector.
+    pub fn extend_from_slice(&mut self, items: &[T]) {
+        let runs: Vec<_> = self.table.encode_mut(items).collect();
+        for (ind, len) in runs {
+            self.push_index(ind, len);
+        }
+    }
+
+    /// Replaces the item at `index`, returning the item that was there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, item: T) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        self.splice(index..=index, Some(item)).pop().unwrap()
+    }
+
+    /// Inserts an item at `index`, shifting all the items after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, item: T) {
+        self.splice(index..index, Some(item));
+    }
+
+    /// Removes and returns the item at `index`, shifting all the items after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        self.splice(index..=index, None).pop().unwrap()
+    }
+
+    /// Replaces the items in `range` with the items of `replace_with`,
+    /// returning the items that were removed.
+    ///
+    /// Only the runs at the edges of the range are split, and any runs of
+    /// the same item that end up next to each other are merged back together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Vec<T>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = self.bounds(range);
+        self.occurrences.take();
+        let a = self.split_at(start);
+        let b = self.split_at(end);
+        let mut removed = Vec::with_capacity(end - start);
+        for &(ind, len) in &self.runs[a..b] {
+            removed.extend(std::iter::repeat_n(self.table[ind].clone(), len));
+        }
+
+        let mut runs: Vec<(Index, usize)> = Vec::new();
+        for item in replace_with {
+            let ind = self.table.insert_or_get(&item);
+            match runs.last_mut() {
+                Some(last) if last.0 == ind => last.1 += 1,
+                _ => runs.push((ind, 1)),
+            }
+        }
+        let added = runs.len();
+        self.runs.splice(a..b, runs);
+
+        // Merge the runs at both edges of the new items with their neighbours
+        self.merge_at(a + added);
+        self.merge_at(a);
+        self.update_ends(a.saturating_sub(1));
+        removed
+    }
+
+    /// Replaces every item in `range` with the item, without decoding the
+    /// runs in the range.
+    ///
+    /// The runs covered by the range are replaced with a single run, which
+    /// is merged with its neighbours if they are of the same item. This makes
+    /// filling a large area much cheaper than [splice](RleVec::splice), which
+    /// has to produce each item that was removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    ///
+    /// ```
+    /// use rle::RleVec;
+    ///
+    /// let mut vec: RleVec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+    /// vec.fill_range(3..15, 'C');
+    /// assert_eq!(vec.to_vec().into_iter().collect::<String>(), "AAACCCCCCCCCCCCCCC");
+    /// assert_eq!(vec.runs(), [(0, 3), (2, 15)]);
+    /// ```
+    pub fn fill_range<R: RangeBounds<usize>>(&mut self, range: R, item: T) {
+        let (start, end) = self.bounds(range);
+        if start == end {
+            return;
+        }
+        self.occurrences.take();
+        let a = self.split_at(start);
+        let b = self.split_at(end);
+        let ind = self.table.insert_or_get(&item);
+        self.runs.splice(a..b, Some((ind, end - start)));
+        self.ends.splice(a..b, Some(end));
+
+        // The total length is unchanged, so the ends of the runs after the
+        // range stay the same as long as the merged runs are removed
+        if a + 1 < self.runs.len() && self.runs[a + 1].0 == ind {
+            self.runs[a].1 += self.runs.remove(a + 1).1;
+            self.ends[a] = self.ends.remove(a + 1);
+        }
+        if a > 0 && self.runs[a - 1].0 == ind {
+            self.runs[a - 1].1 += self.runs.remove(a).1;
+            self.ends[a - 1] = self.ends.remove(a);
+        }
+    }
+
+    /// Returns the runs that overlap the range, with their lengths clipped
+    /// to the range, without decoding them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    ///
+    /// ```
+    /// use rle::RleVec;
+    ///
+    /// let vec: RleVec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+    /// let runs: Vec<_> = vec.runs_in_range(3..16).collect();
+    /// assert_eq!(runs, [(0, 2), (1, 10), (2, 1)]);
+    /// ```
+    pub fn runs_in_range<R>(&self, range: R) -> impl Iterator<Item = (Index, usize)> + '_
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.bounds(range);
+        let first = if start < end {
+            self.run_at(start).unwrap_or(self.runs.len())
+        } else {
+            self.runs.len()
+        };
+        self.runs[first..]
+            .iter()
+            .zip(&self.ends[first..])
+            .map_while(move |(&(ind, len), &run_end)| {
+                let run_start = run_end - len;
+                (run_start < end).then(|| (ind, run_end.min(end) - run_start.max(start)))
+            })
+    }
+
+    /// Counts how many times the item appears in the range, without
+    /// decoding it. This takes `O(log n + k)` time, where `k` is the number
+    /// of runs in the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is after its end.
+    ///
+    /// ```
+    /// use rle::RleVec;
+    ///
+    /// let vec: RleVec<char> = "AAAAABBBBBBBBBBCCC".chars().collect();
+    /// assert_eq!(vec.count_in_range(&'B', 3..8), 3);
+    /// assert_eq!(vec.count_in_range(&'A', ..), 5);
+    /// assert_eq!(vec.count_in_range(&'D', ..), 0);
+    /// ```
+    pub fn count_in_range<R>(&self, item: &T, range: R) -> usize
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.bounds(range);
+        match self.table.index_of(item) {
+            Some(ind) => self
+                .runs_in_range(start..end)
+                .filter(|run| run.0 == ind)
+                .map(|run| run.1)
+                .sum(),
+            None => 0,
+        }
+    }
+
+    /// Counts how many times the item appears among the first `i` items.
+    ///
+    /// The first call after the vector changes builds an index of every
+    /// item's runs in `O(n)` time for `n` runs, after which each query takes
+    /// `O(log n)` time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i > len`.
+    ///
+    /// ```
+    /// use rle::RleVec;
+    ///
+    /// let vec: RleVec<char> = "AAABBAAAAC".chars().collect();
+    /// assert_eq!(vec.rank(&'A', 4), 3);
+    /// assert_eq!(vec.rank(&'A', 7), 5);
+    /// assert_eq!(vec.rank(&'C', 9), 0);
+    /// ```
+    pub fn rank(&self, item: &T, i: usize) -> usize {
+        assert!(i <= self.len(), "index out of bounds");
+        let runs = match self.occurrences_of(item) {
+            Some(runs) => runs,
+            None => return 0,
+        };
+        match runs.partition_point(|run| run.start < i) {
+            0 => 0,
+            n => {
+                let run = runs[n - 1];
+                run.before + run.len.min(i - run.start)
+            }
+        }
+    }
+
+    /// Returns the position of the `k`th occurrence of the item (counting
+    /// from zero), or `None` if it appears `k` times or fewer.
+    ///
+    /// This uses the same index as [rank](RleVec::rank).
+    ///
+    /// ```
+    /// use rle::RleVec;
+    ///
+    /// let vec: RleVec<char> = "AAABBAAAAC".chars().collect();
+    /// assert_eq!(vec.select(&'A', 0), Some(0));
+    /// assert_eq!(vec.select(&'A', 3), Some(5));
+    /// assert_eq!(vec.select(&'B', 2), None);
+    /// ```
+    pub fn select(&self, item: &T, k: usize) -> Option<usize> {
+        let runs = self.occurrences_of(item)?;
+        let run = runs[runs.partition_point(|run| run.before <= k).checked_sub(1)?];
+        (k - run.before < run.len).then(|| run.start + k - run.before)
+    }
+
+    /// The runs of the item, building the occurrence index if needed.
+    fn occurrences_of(&self, item: &T) -> Option<&[Occurrence]> {
+        let ind = self.table.index_of(item)?;
+        let occurrences = self.occurrences.get_or_init(|| {
+            let mut occurrences = vec![Vec::new(); self.table.len()];
+            let mut counts = vec![0; self.table.len()];
+            for (&(ind, len), &end) in self.runs.iter().zip(&self.ends) {
+                occurrences[ind].push(Occurrence {
+                    start: end - len,
+                    len,
+                    before: counts[ind],
+                });
+                counts[ind] += len;
+            }
+            occurrences
+        });
+        occurrences.get(ind).map(Vec::as_slice)
+    }
+
+    /// Resolves the range to a start and end position.
+    fn bounds<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        bounds(range, self.len())
+    }
+
+    /// Splits the run containing position `pos` so that a run starts exactly
+    /// at `pos`, returning the index of that run (or the number of runs, if
+    /// `pos` is the end of the vector).
+    fn split_at(&mut self, pos: usize) -> usize {
+        let run = match self.run_at(pos) {
+            Some(run) => run,
+            None => return self.runs.len(),
+        };
+        let (ind, len) = self.runs[run];
+        let start = self.ends[run] - len;
+        if start == pos {
+            return run;
+        }
+        self.runs[run].1 = pos - start;
+        self.runs.insert(run + 1, (ind, start + len - pos));
+        self.ends.insert(run, pos);
+        run + 1
+    }
+
+    /// Merges the run at `run` into the one before it, if they are the same item.
+    fn merge_at(&mut self, run: usize) {
+        if run > 0 && run < self.runs.len() && self.runs[run - 1].0 == self.runs[run].0 {
+            self.runs[run - 1].1 += self.runs[run].1;
+            self.runs.remove(run);
+        }
+    }
+
+    /// Recalculates where each run ends, starting from the run at `from`.
+    fn update_ends(&mut self, from: usize) {
+        self.ends.truncate(from);
+        let mut end = self.ends.last().copied().unwrap_or(0);
+        for &(_, len) in &self.runs[from..] {
+            end += len;
+            self.ends.push(end);
+        }
+    }
+
+    /// Removes all items from the vector. The table is left unchanged.
+    pub fn clear(&mut self) {
+        self.occurrences.take();
+        self.runs.clear();
+        self.ends.clear();
+    }
+
+    /// Returns an iterator over the items in the vector.
+    pub fn iter(&self) -> Decoder<'_, T> {
+        self.table.decode(&self.runs)
+    }
+
+    /// Decodes the whole vector into a regular `Vec`.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+/// Converts the range into a start and end position in a sequence of `len`
+/// items.
+///
+/// # Panics
+///
+/// Panics if the range is out of bounds or its start is after its end.
+pub(crate) fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i + 1,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start is after its end");
+    assert!(end <= len, "range end out of bounds");
+    (start, end)
+}
+
+impl<T> FromIterator<T> for RleVec<T>
+where
+    T: Ord + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T> Extend<T> for RleVec<T>
+where
+    T: Ord + Clone,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RleVec<T>
+where
+    T: Ord + Clone,
+{
+    type Item = &'a T;
+    type IntoIter = Decoder<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
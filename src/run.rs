@@ -0,0 +1,104 @@
+use crate::{Index, RunLen};
+use std::fmt::{Display, Formatter};
+
+/// A run of one item repeated `len` times, where the item is stored as its
+/// `index` in a table.
+///
+/// Most of the crate produces runs as `(Index, usize)` tuples, which convert
+/// to and from this type with [From] and [Into].
+///
+/// ```
+/// # use rle::Run;
+/// let run = Run::new(2, 5);
+/// let (left, right) = run.split_at(2);
+/// assert_eq!((left, right), (Run::new(2, 2), Run::new(2, 3)));
+/// assert_eq!(run.to_string(), "2:5");
+/// assert_eq!(<(usize, usize)>::from(run), (2, 5));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct Run<L = usize> {
+    /// The index of the item in the table.
+    pub index: Index,
+
+    /// How many times the item is repeated.
+    pub len: L,
+}
+
+impl<L> Run<L> {
+    /// Constructs a run of the item at `index` repeated `len` times.
+    pub fn new(index: Index, len: L) -> Self {
+        Self { index, len }
+    }
+}
+
+impl<L: RunLen> Run<L> {
+    /// How many times the item is repeated.
+    pub fn len(&self) -> usize {
+        self.len.to_usize()
+    }
+
+    /// Returns `true` if the run has a length of 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Splits the run into one of the first `mid` items and one of the rest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length of the run.
+    pub fn split_at(self, mid: usize) -> (Self, Self) {
+        let len = self.len();
+        assert!(
+            mid <= len,
+            "split position {} is past the end of the run",
+            mid
+        );
+        let left = L::from_usize(mid).unwrap();
+        let right = L::from_usize(len - mid).unwrap();
+        (Self::new(self.index, left), Self::new(self.index, right))
+    }
+}
+
+impl<L: Display> Display for Run<L> {
+    /// Formats the run as `INDEX:LEN`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.index, self.len)
+    }
+}
+
+impl<L> From<(Index, L)> for Run<L> {
+    fn from((index, len): (Index, L)) -> Self {
+        Self { index, len }
+    }
+}
+
+impl<L> From<Run<L>> for (Index, L) {
+    fn from(run: Run<L>) -> Self {
+        (run.index, run.len)
+    }
+}
+
+/// A type that can be read as a run, so that sequences of either [Run]s or
+/// `(Index, L)` tuples can be decoded. See [decode_as](crate::Table::decode_as).
+pub trait IntoRun: Copy {
+    /// Converts this into a run with a `usize` length.
+    fn into_run(self) -> Run;
+}
+
+impl<L: RunLen> IntoRun for Run<L> {
+    fn into_run(self) -> Run {
+        Run::new(self.index, self.len.to_usize())
+    }
+}
+
+impl<L: RunLen> IntoRun for (Index, L) {
+    fn into_run(self) -> Run {
+        Run::new(self.0, self.1.to_usize())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::bulk::read_run;
+use crate::{Index, IntoRun, SortedStore, Table, TableStore};
+use std::iter::FusedIterator;
+
+/// An iterator that decodes a sequence of runs into each run's item and
+/// length, without repeating the item. See [decode_runs](crate::Table::decode_runs).
+pub struct RunDecoder<'a, T, S = SortedStore<T>, R = (Index, usize)> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) runs: &'a [R],
+}
+
+impl<'a, T, S, R> Iterator for RunDecoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (run, rest) = self.runs.split_first()?;
+            let run = run.into_run();
+            match self.table.item(run.index) {
+                Some(item) => {
+                    self.runs = rest;
+                    // Skip runs of zero length
+                    if run.len > 0 {
+                        return Some((item, run.len));
+                    }
+                }
+                None => {
+                    // Stop decoding at the first index that isn't in the table
+                    self.runs = &[];
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.runs.len()))
+    }
+}
+
+impl<'a, T, S, R> FusedIterator for RunDecoder<'a, T, S, R>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    R: IntoRun,
+{
+}
+
+/// An iterator that decodes run-length encoded bytes into each run's item
+/// and length, without repeating the item. See [decode_bytes_runs](crate::Table::decode_bytes_runs).
+pub struct BytesRunDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) offset: usize,
+}
+
+impl<'a, T, S> Iterator for BytesRunDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = match read_run(self.bytes, self.offset) {
+            Ok(Some((ind, len, size))) => self.table.item(ind).map(|item| (item, len, size)),
+            _ => None,
+        };
+        match run {
+            Some((item, len, size)) => {
+                self.offset += size;
+                Some((item, len))
+            }
+            None => {
+                // Stop decoding at malformed bytes or an unknown index
+                self.offset = self.bytes.len();
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.bytes.len() - self.offset))
+    }
+}
+
+impl<'a, T, S> FusedIterator for BytesRunDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+}
@@ -0,0 +1,135 @@
+use crate::byte_scan::{byte_run_len, run_len};
+use std::iter::{FusedIterator, Peekable};
+
+/// An extension trait that groups the items of any iterator into runs,
+/// without needing a [Table](crate::Table).
+///
+/// ```
+/// use rle::RunLength;
+///
+/// let runs: Vec<(char, usize)> = "AAABCC".chars().runs().collect();
+/// assert_eq!(runs, [('A', 3), ('B', 1), ('C', 2)]);
+/// ```
+pub trait RunLength: Iterator + Sized {
+    /// Returns an iterator that yields each item along with how many times
+    /// it repeats in a row.
+    fn runs(self) -> RunLengthIter<Self>;
+}
+
+impl<I> RunLength for I
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    fn runs(self) -> RunLengthIter<Self> {
+        RunLengthIter {
+            iter: self.peekable(),
+        }
+    }
+}
+
+/// An iterator that groups the items of another iterator into runs.
+/// See [runs](RunLength::runs).
+pub struct RunLengthIter<I: Iterator> {
+    pub(crate) iter: Peekable<I>,
+}
+
+impl<I> Iterator for RunLengthIter<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (I::Item, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let mut len = 1;
+        while self.iter.next_if_eq(&item).is_some() {
+            len += 1;
+        }
+        Some((item, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.iter.size_hint();
+        ((min > 0) as usize, max)
+    }
+}
+
+impl<I> FusedIterator for RunLengthIter<I>
+where
+    I: FusedIterator,
+    I::Item: PartialEq,
+{
+}
+
+/// Returns an iterator over the runs of a byte slice, as each byte along
+/// with how many times it repeats in a row, without needing a [Table](crate::Table).
+///
+/// Runs are found by comparing 8 bytes at a time.
+///
+/// ```
+/// let runs: Vec<(u8, usize)> = rle::runs_of(&[0, 0, 0, 255, 7, 7]).collect();
+/// assert_eq!(runs, [(0, 3), (255, 1), (7, 2)]);
+/// ```
+pub fn runs_of(bytes: &[u8]) -> ByteRuns<'_> {
+    ByteRuns { bytes }
+}
+
+/// Returns an iterator over the runs of a slice, as a reference to each
+/// item along with how many times it repeats in a row, without needing a
+/// [Table](crate::Table).
+///
+/// ```
+/// let runs: Vec<(&char, usize)> = rle::runs_of_slice(&['A', 'A', 'B']).collect();
+/// assert_eq!(runs, [(&'A', 2), (&'B', 1)]);
+/// ```
+pub fn runs_of_slice<T: PartialEq>(items: &[T]) -> SliceRuns<'_, T> {
+    SliceRuns { items }
+}
+
+/// An iterator over the runs of a byte slice. See [runs_of].
+#[derive(Clone, Debug)]
+pub struct ByteRuns<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for ByteRuns<'a> {
+    type Item = (u8, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &byte = self.bytes.first()?;
+        let len = byte_run_len(self.bytes);
+        self.bytes = &self.bytes[len..];
+        Some((byte, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (!self.bytes.is_empty() as usize, Some(self.bytes.len()))
+    }
+}
+
+impl<'a> FusedIterator for ByteRuns<'a> {}
+
+/// An iterator over the runs of a slice. See [runs_of_slice].
+#[derive(Clone, Debug)]
+pub struct SliceRuns<'a, T> {
+    items: &'a [T],
+}
+
+impl<'a, T: PartialEq> Iterator for SliceRuns<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.items.first()?;
+        let len = run_len(self.items);
+        self.items = &self.items[len..];
+        Some((item, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (!self.items.is_empty() as usize, Some(self.items.len()))
+    }
+}
+
+impl<'a, T: PartialEq> FusedIterator for SliceRuns<'a, T> {}
@@ -0,0 +1,197 @@
+use crate::{Index, Table};
+
+/// How much of a decoded sequence is made up of one symbol. See [coverage](Runs::coverage).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coverage<'a, T> {
+    /// The index of the symbol in the table.
+    pub index: Index,
+
+    /// The symbol.
+    pub item: &'a T,
+
+    /// How many elements of the sequence are this symbol.
+    pub count: usize,
+
+    /// The percentage (from `0.0` to `100.0`) of the sequence that is this symbol.
+    pub percent: f64,
+}
+
+/// Merges adjacent runs with the same index and removes runs of length 0,
+/// so that encoding the runs as bytes produces as few bytes as possible.
+///
+/// ```
+/// # use rle::normalize_runs;
+/// let mut runs = vec![(0, 3), (0, 2), (1, 0), (2, 1), (1, 0), (2, 4)];
+/// normalize_runs(&mut runs);
+/// assert_eq!(runs, [(0, 5), (2, 5)]);
+/// ```
+pub fn normalize_runs(runs: &mut Vec<(Index, usize)>) {
+    runs.retain(|&(_, len)| len > 0);
+    runs.dedup_by(|(ind, len), prev| {
+        let same = *ind == prev.0;
+        if same {
+            prev.1 += *len;
+        }
+        same
+    });
+}
+
+/// Returns an iterator that merges adjacent runs with the same index and
+/// skips runs of length 0, like [normalize_runs] but without collecting.
+///
+/// ```
+/// # use rle::normalized;
+/// let runs = [(0, 3), (0, 2), (1, 0), (2, 1), (1, 0), (2, 4)];
+/// assert!(normalized(runs).eq([(0, 5), (2, 5)]));
+/// ```
+pub fn normalized<I>(runs: I) -> NormalizedRuns<I::IntoIter>
+where
+    I: IntoIterator<Item = (Index, usize)>,
+{
+    NormalizedRuns {
+        runs: runs.into_iter(),
+        peeked: None,
+    }
+}
+
+/// An iterator that merges adjacent runs with the same index and skips runs
+/// of length 0. See [normalized].
+pub struct NormalizedRuns<I> {
+    pub(crate) runs: I,
+    pub(crate) peeked: Option<(Index, usize)>,
+}
+
+impl<I> Iterator for NormalizedRuns<I>
+where
+    I: Iterator<Item = (Index, usize)>,
+{
+    type Item = (Index, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut run = match self.peeked.take() {
+            Some(run) => run,
+            None => self.runs.by_ref().find(|&(_, len)| len > 0)?,
+        };
+        for (ind, len) in self.runs.by_ref() {
+            if len == 0 {
+                continue;
+            }
+            if ind == run.0 {
+                run.1 += len;
+            } else {
+                self.peeked = Some((ind, len));
+                break;
+            }
+        }
+        Some(run)
+    }
+}
+
+/// Queries that work directly on a sequence of runs, such as the output
+/// of [encode](crate::Table::encode), without decoding it.
+pub trait Runs {
+    /// The total number of elements the runs decode to.
+    fn total_len(&self) -> usize;
+
+    /// For every symbol in the table, in index order, counts how many elements
+    /// of the decoded sequence are that symbol and what percentage of the
+    /// sequence they make up. Runs with indices not in the table are ignored.
+    fn coverage<'a, T>(&self, table: &'a Table<T>) -> Vec<Coverage<'a, T>>;
+
+    /// Picks a uniformly random element from the decoded sequence, and
+    /// returns its value from the table. Longer runs are proportionally more
+    /// likely to be picked. Returns `None` if the runs are empty or the index
+    /// picked is not in the table.
+    #[cfg(feature = "rand")]
+    fn sample<'a, T, R>(&self, table: &'a Table<T>, rng: &mut R) -> Option<&'a T>
+    where
+        R: rand::Rng + ?Sized;
+
+    /// Picks `amount` distinct random positions from the decoded sequence
+    /// (or all of them, if there are fewer), and returns their values from
+    /// the table in the order they appear in the sequence.
+    #[cfg(feature = "rand")]
+    fn choose_multiple<'a, T, R>(
+        &self,
+        table: &'a Table<T>,
+        rng: &mut R,
+        amount: usize,
+    ) -> Vec<&'a T>
+    where
+        R: rand::Rng + ?Sized;
+}
+
+impl Runs for [(Index, usize)] {
+    fn total_len(&self) -> usize {
+        self.iter().map(|&(_, len)| len).sum()
+    }
+
+    fn coverage<'a, T>(&self, table: &'a Table<T>) -> Vec<Coverage<'a, T>> {
+        let mut counts = vec![0; table.len()];
+        for &(ind, len) in self {
+            if let Some(count) = counts.get_mut(ind) {
+                *count += len;
+            }
+        }
+        let total = self.total_len().max(1) as f64;
+        table
+            .iter()
+            .zip(counts)
+            .enumerate()
+            .map(|(index, (item, count))| Coverage {
+                index,
+                item,
+                count,
+                percent: count as f64 * 100.0 / total,
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample<'a, T, R>(&self, table: &'a Table<T>, rng: &mut R) -> Option<&'a T>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let total = self.total_len();
+        if total == 0 {
+            return None;
+        }
+        let mut pos = rng.gen_range(0..total);
+        for &(ind, len) in self {
+            if pos < len {
+                return table.get(ind);
+            }
+            pos -= len;
+        }
+        None
+    }
+
+    #[cfg(feature = "rand")]
+    fn choose_multiple<'a, T, R>(
+        &self,
+        table: &'a Table<T>,
+        rng: &mut R,
+        amount: usize,
+    ) -> Vec<&'a T>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let total = self.total_len();
+        let mut positions = rand::seq::index::sample(rng, total, amount.min(total)).into_vec();
+        positions.sort_unstable();
+
+        let mut values = Vec::with_capacity(positions.len());
+        let mut runs = self.iter();
+        let mut end = 0;
+        let mut ind = 0;
+        for pos in positions {
+            while pos >= end {
+                let &(i, len) = runs.next().unwrap();
+                ind = i;
+                end += len;
+            }
+            values.extend(table.get(ind));
+        }
+        values
+    }
+}
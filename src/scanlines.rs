@@ -0,0 +1,152 @@
+use crate::{BytesDecoder, Error, SortedStore, Table, TableStore};
+
+/// An iterator over the rows of a scanline-encoded byte sequence, yielding
+/// the encoded bytes of each row. See [encode_scanlines](crate::Table::encode_scanlines).
+///
+/// Each row is stored as a 2-byte little-endian length header followed by
+/// that many bytes of run-length encoded data, so rows can be skipped
+/// without decoding them.
+pub struct Scanlines<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+impl<'a> Scanlines<'a> {
+    /// Constructs an iterator over the rows of the scanline-encoded bytes.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+}
+
+impl<'a> Iterator for Scanlines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.len() < 2 {
+            return None;
+        }
+        let len = u16::from_le_bytes([self.bytes[0], self.bytes[1]]) as usize;
+        let row = self.bytes.get(2..2 + len)?;
+        self.bytes = &self.bytes[2 + len..];
+        Some(row)
+    }
+}
+
+/// An iterator that decodes every row of a scanline-encoded byte sequence
+/// into a series of `T` values fetched from the table. See [decode_scanlines](crate::Table::decode_scanlines).
+pub struct ScanlineDecoder<'a, T, S = SortedStore<T>>
+where
+    T: PartialEq + Clone,
+{
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) rows: Scanlines<'a>,
+    pub(crate) row: Option<BytesDecoder<'a, T, S>>,
+}
+
+impl<'a, T, S> Iterator for ScanlineDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.row.as_mut().and_then(|row| row.next()) {
+                return Some(item);
+            }
+            self.row = Some(self.table.decode_bytes(self.rows.next()?));
+        }
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes, treating every
+    /// `width` items as a separate row (or scanline).
+    ///
+    /// Runs never cross from one row into the next, and each row is prefixed
+    /// with a 2-byte little-endian header containing the number of encoded
+    /// bytes that follow. This allows rows to be decoded individually with
+    /// [decode_scanline](Table::decode_scanline), and matches image formats
+    /// that forbid runs from wrapping around scanlines. If the number of items
+    /// is not a multiple of `width`, the final row will be shorter.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table::encode_bytes),
+    /// or with a [ScanlineTooLarge](Error::ScanlineTooLarge) error if a row
+    /// encodes to more than 65535 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn encode_scanlines(&self, items: &[T], width: usize) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        for (row, chunk) in items.chunks(width).enumerate() {
+            let encoded = self.encode_bytes(chunk).map_err(|err| match err {
+                Error::TableMissingItems(i) => Error::TableMissingItems(row * width + i),
+                err => err,
+            })?;
+            push_scanline(&mut bytes, row, encoded)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Run-length encodes the items as a sequence of bytes, treating every
+    /// `width` items as a separate row (or scanline).
+    ///
+    /// Unlike [encode_scanlines](Table::encode_scanlines), this method will add
+    /// items to the table as they are found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn encode_scanlines_mut(&mut self, items: &[T], width: usize) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        for (row, chunk) in items.chunks(width).enumerate() {
+            let encoded: Result<Vec<u8>, Error> = self.encode_bytes_mut(chunk)?.collect();
+            push_scanline(&mut bytes, row, encoded?)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Return an iterator that decodes a single row of scanline-encoded bytes,
+    /// or `None` if the row is out of bounds.
+    pub fn decode_scanline<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        row: usize,
+    ) -> Option<BytesDecoder<'a, T, S>> {
+        Scanlines::new(bytes)
+            .nth(row)
+            .map(|row| self.decode_bytes(row))
+    }
+
+    /// Return an iterator that decodes all rows of scanline-encoded bytes using
+    /// this table as the index lookup for the elements.
+    pub fn decode_scanlines<'a>(&'a self, bytes: &'a [u8]) -> ScanlineDecoder<'a, T, S> {
+        ScanlineDecoder {
+            table: self,
+            rows: Scanlines::new(bytes),
+            row: None,
+        }
+    }
+}
+
+fn push_scanline<I>(bytes: &mut Vec<u8>, row: usize, encoded: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = u8>,
+{
+    let header = bytes.len();
+    bytes.extend_from_slice(&[0, 0]);
+    bytes.extend(encoded);
+    let len = bytes.len() - header - 2;
+    if len > u16::MAX as usize {
+        return Err(Error::ScanlineTooLarge(row));
+    }
+    bytes[header..header + 2].copy_from_slice(&(len as u16).to_le_bytes());
+    Ok(())
+}
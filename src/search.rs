@@ -0,0 +1,71 @@
+use crate::bulk::read_run;
+use crate::{Error, Index};
+use std::ops::Range;
+
+/// Returns the position in the decoded sequence of the first item with the
+/// index, reading only the run headers of the run-length encoded bytes (see
+/// [encode_bytes](crate::Table::encode_bytes)), or `None` if no run has it.
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes end
+/// in the middle of a run before the index is found.
+///
+/// ```
+/// # use rle::{find_symbol, Table};
+/// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+/// let table = Table::from_slice(&['A', 'B', 'C']);
+/// let bytes: Vec<u8> = table.encode_bytes(&str).unwrap().collect();
+///
+/// assert_eq!(find_symbol(&bytes, 2).unwrap(), Some(15));
+/// assert_eq!(find_symbol(&bytes, 3).unwrap(), None);
+/// ```
+pub fn find_symbol(bytes: &[u8], index: Index) -> Result<Option<usize>, Error> {
+    let (mut offset, mut pos) = (0, 0);
+    while let Some((ind, len, size)) = read_run(bytes, offset)? {
+        if ind == index {
+            return Ok(Some(pos));
+        }
+        offset += size;
+        pos += len;
+    }
+    Ok(None)
+}
+
+/// Returns the index of the run covering position `pos` of the decoded
+/// sequence, along with the positions that run covers, reading only the
+/// run headers of the run-length encoded bytes (see [encode_bytes](crate::Table::encode_bytes)).
+/// Returns `None` if `pos` is past the end of the sequence.
+///
+/// Runs that were split into several runs of the same index when encoded
+/// are joined back together.
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes end
+/// in the middle of a run before the run is found.
+///
+/// ```
+/// # use rle::{find_run_at, Table};
+/// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+/// let table = Table::from_slice(&['A', 'B', 'C']);
+/// let bytes: Vec<u8> = table.encode_bytes(&str).unwrap().collect();
+///
+/// assert_eq!(find_run_at(&bytes, 7).unwrap(), Some((1, 5..15)));
+/// assert_eq!(find_run_at(&bytes, 28).unwrap(), None);
+/// ```
+pub fn find_run_at(bytes: &[u8], pos: usize) -> Result<Option<(Index, Range<usize>)>, Error> {
+    let (mut offset, mut start) = (0, 0);
+    let mut run: Option<(Index, Range<usize>)> = None;
+    while let Some((ind, len, size)) = read_run(bytes, offset)? {
+        let end = start + len;
+        match &mut run {
+            Some((run_ind, range)) if *run_ind == ind => range.end = end,
+            Some((_, range)) if range.contains(&pos) => break,
+            _ => run = Some((ind, start..end)),
+        }
+        offset += size;
+        start = end;
+    }
+    Ok(run.filter(|(_, range)| range.contains(&pos)))
+}
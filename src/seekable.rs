@@ -0,0 +1,191 @@
+use crate::{Error, Table, TableItem, TableStore};
+use std::convert::TryFrom;
+use std::ops::{Bound, RangeBounds};
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Encodes the items in independent chunks of `chunk_len` items, along
+    /// with an index of where each chunk starts, so that any range of items
+    /// can later be decoded with [decode_range](Table::decode_range) without
+    /// decoding everything before it.
+    ///
+    /// # Format
+    ///
+    /// The bytes start with `chunk_len` and the total number of items, both
+    /// as little-endian `u32`s. Then, for each chunk, the offset where its
+    /// bytes start (relative to the end of the index) as a little-endian `u32`.
+    /// Chunk `i` always starts with item `i * chunk_len`, since runs are split
+    /// at the chunk boundaries. The rest of the bytes are the chunks, each
+    /// encoded as in [encode_bytes](Table::encode_bytes).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let items: Vec<u8> = (0..10_000).map(|i| (i / 300 % 3) as u8).collect();
+    /// let table = Table::from_slice(&[0, 1, 2]);
+    /// let bytes = table.encode_chunked(&items, 1024).unwrap();
+    ///
+    /// assert_eq!(rle::chunked_len(&bytes).unwrap(), 10_000);
+    /// assert_eq!(table.decode_range(&bytes, 5000..5010).unwrap(), &items[5000..5010]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is zero or does not fit in a `u32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [LengthTooLarge](Error::LengthTooLarge) error if the number of
+    /// items or the offset of a chunk does not fit in a `u32`. Otherwise, fails
+    /// under the same conditions as [encode_bytes](Table::encode_bytes).
+    pub fn encode_chunked(&self, items: &[T], chunk_len: usize) -> Result<Vec<u8>, Error> {
+        assert!(chunk_len > 0, "chunk_len must be greater than zero");
+        let chunk_len = u32::try_from(chunk_len).expect("chunk_len must fit in a u32");
+        let len = u32::try_from(items.len()).map_err(|_| Error::LengthTooLarge(items.len()))?;
+        let mut payload = Vec::new();
+        let mut offsets = Vec::new();
+        for chunk in items.chunks(chunk_len as usize) {
+            let offset =
+                u32::try_from(payload.len()).map_err(|_| Error::LengthTooLarge(payload.len()))?;
+            offsets.push(offset);
+            self.encode_bytes_into(chunk, &mut payload)?;
+        }
+        let mut bytes = Vec::with_capacity(8 + offsets.len() * 4 + payload.len());
+        chunk_len.write_bytes(&mut bytes);
+        len.write_bytes(&mut bytes);
+        for offset in offsets {
+            offset.write_bytes(&mut bytes);
+        }
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// Decodes the items in `range` from bytes produced by [encode_chunked](Table::encode_chunked),
+    /// only decoding the chunks that overlap the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds (see [chunked_len](crate::chunked_len))
+    /// or its start is after its end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBytes](Error::InvalidBytes) error if the header or
+    /// index is malformed, or if a chunk does not decode to the expected number
+    /// of items. Otherwise, fails under the same conditions as [decode_bytes_into](Table::decode_bytes_into).
+    pub fn decode_range<R>(&self, bytes: &[u8], range: R) -> Result<Vec<T>, Error>
+    where
+        R: RangeBounds<usize>,
+    {
+        let header = ChunkedHeader::read(bytes)?;
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => header.len,
+        };
+        assert!(start <= end, "range start is after its end");
+        assert!(end <= header.len, "range end out of bounds");
+        if start == end {
+            return Ok(Vec::new());
+        }
+
+        let first = start / header.chunk_len;
+        let last = (end - 1) / header.chunk_len;
+        let mut items = Vec::with_capacity(end - start);
+        for chunk in first..=last {
+            let (from, to) = header.chunk_bytes(bytes, chunk);
+            let before = items.len();
+            self.decode_bytes_into(&bytes[from..to], &mut items)?;
+            let expected = header.chunk_len.min(header.len - chunk * header.chunk_len);
+            if items.len() - before != expected {
+                return Err(Error::InvalidBytes(from));
+            }
+        }
+        items.truncate(end - first * header.chunk_len);
+        items.drain(..start - first * header.chunk_len);
+        Ok(items)
+    }
+}
+
+/// Returns the number of items in bytes produced by [encode_chunked](Table::encode_chunked).
+///
+/// # Errors
+///
+/// Returns an [InvalidBytes](Error::InvalidBytes) error if the header or
+/// index is malformed.
+pub fn chunked_len(bytes: &[u8]) -> Result<usize, Error> {
+    Ok(ChunkedHeader::read(bytes)?.len)
+}
+
+struct ChunkedHeader {
+    chunk_len: usize,
+    len: usize,
+    chunks: usize,
+}
+
+impl ChunkedHeader {
+    fn read(bytes: &[u8]) -> Result<Self, Error> {
+        let (chunk_len, _) = u32::read_bytes(bytes).ok_or(Error::InvalidBytes(0))?;
+        let (len, _) = bytes
+            .get(4..)
+            .and_then(u32::read_bytes)
+            .ok_or(Error::InvalidBytes(4))?;
+        if chunk_len == 0 {
+            return Err(Error::InvalidBytes(0));
+        }
+        let (chunk_len, len) = (chunk_len as usize, len as usize);
+        let chunks = len.div_ceil(chunk_len);
+        if bytes.len() < 8 + chunks * 4 {
+            return Err(Error::InvalidBytes(bytes.len()));
+        }
+        let header = Self {
+            chunk_len,
+            len,
+            chunks,
+        };
+
+        // Every chunk must lie inside the payload, after the one before it,
+        // and have enough bytes to hold its items (a run holds at most 255)
+        let mut prev = header.payload_start();
+        for chunk in 0..chunks {
+            let (from, to) = header.chunk_bytes(bytes, chunk);
+            let expected = chunk_len.min(len - chunk * chunk_len);
+            if from < prev
+                || from > to
+                || to > bytes.len()
+                || (to - from).saturating_mul(255) < expected
+            {
+                return Err(Error::InvalidBytes(8 + chunk * 4));
+            }
+            prev = from;
+        }
+        Ok(header)
+    }
+
+    fn payload_start(&self) -> usize {
+        8 + self.chunks * 4
+    }
+
+    /// The start and end of the chunk's bytes, which are only known to be
+    /// in bounds once [read](ChunkedHeader::read) has checked them.
+    fn chunk_bytes(&self, bytes: &[u8], chunk: usize) -> (usize, usize) {
+        let offset = |i: usize| {
+            let at = 8 + i * 4;
+            self.payload_start() + u32::read_bytes(&bytes[at..]).unwrap().0 as usize
+        };
+        let from = offset(chunk);
+        let to = if chunk + 1 < self.chunks {
+            offset(chunk + 1)
+        } else {
+            bytes.len()
+        };
+        (from, to)
+    }
+}
@@ -0,0 +1,218 @@
+use crate::bulk::{read_run, MAX_TABLE_LEN};
+use crate::{runs_of_slice, Error, Index, Table, TableItem};
+
+/// Encodes many independent sequences as bytes against one shared table,
+/// which it owns, so that the table only has to be stored once.
+///
+/// Each sequence is encoded as in [encode_bytes](Table::encode_bytes), adding
+/// its new items to the table first, and is given an id that it can be
+/// looked up and decoded with later. The session also tracks which items each
+/// sequence uses. The whole session, table included, can be saved with
+/// [to_bytes](EncodeSession::to_bytes).
+///
+/// ```
+/// use rle::EncodeSession;
+///
+/// let mut session = EncodeSession::new();
+/// let a = session.encode(&[1u8, 1, 1, 2, 2]).unwrap();
+/// let b = session.encode(&[3, 3, 1]).unwrap();
+/// assert_eq!(session.table().as_ref(), &[1, 2, 3]);
+/// assert_eq!(session.used(b), Some(&[0, 2][..]));
+///
+/// let loaded = EncodeSession::<u8>::from_bytes(&session.to_bytes()).unwrap();
+/// assert_eq!(loaded.decode(a), Some(vec![1, 1, 1, 2, 2]));
+/// assert_eq!(loaded.decode(b), Some(vec![3, 3, 1]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EncodeSession<T> {
+    table: Table<T>,
+    sequences: Vec<Vec<u8>>,
+
+    /// The sorted indices of the items each sequence uses.
+    used: Vec<Vec<Index>>,
+}
+
+impl<T> Default for EncodeSession<T> {
+    fn default() -> Self {
+        Self {
+            table: Table::default(),
+            sequences: Vec::new(),
+            used: Vec::new(),
+        }
+    }
+}
+
+impl<T> EncodeSession<T>
+where
+    T: Ord + Clone,
+{
+    /// Constructs a session with an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a session that starts with the items already in `table`.
+    pub fn with_table(table: Table<T>) -> Self {
+        Self {
+            table,
+            ..Self::default()
+        }
+    }
+
+    /// The table shared by every sequence.
+    pub fn table(&self) -> &Table<T> {
+        &self.table
+    }
+
+    /// Consumes the session, returning the shared table.
+    pub fn into_table(self) -> Table<T> {
+        self.table
+    }
+
+    /// The number of sequences that have been encoded.
+    pub fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    /// Returns `true` if no sequences have been encoded.
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    /// Encodes the items as another sequence, adding any items that are not
+    /// in the table yet, and returns the id of the sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns [TableTooLarge](Error::TableTooLarge) if adding the new items
+    /// would give the table more than 127 items, in which case the table is
+    /// left unchanged and no sequence is added.
+    pub fn encode(&mut self, items: &[T]) -> Result<usize, Error> {
+        let mut missing: Vec<&T> = Vec::new();
+        for (item, _) in runs_of_slice(items) {
+            if self.table.get_index(item).is_none() && !missing.contains(&item) {
+                missing.push(item);
+                if self.table.len() + missing.len() > MAX_TABLE_LEN {
+                    return Err(Error::TableTooLarge(self.table.len() + missing.len()));
+                }
+            }
+        }
+
+        let mut used: Vec<Index> = runs_of_slice(items)
+            .map(|(item, _)| self.table.insert_or_get(item))
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+
+        let mut bytes = Vec::new();
+        self.table.encode_bytes_into(items, &mut bytes)?;
+        self.sequences.push(bytes);
+        self.used.push(used);
+        Ok(self.sequences.len() - 1)
+    }
+
+    /// The encoded bytes of the sequence, or `None` if there is no sequence
+    /// with that id.
+    pub fn bytes(&self, id: usize) -> Option<&[u8]> {
+        self.sequences.get(id).map(Vec::as_slice)
+    }
+
+    /// The sorted indices of the items the sequence uses, or `None` if there
+    /// is no sequence with that id.
+    pub fn used(&self, id: usize) -> Option<&[Index]> {
+        self.used.get(id).map(Vec::as_slice)
+    }
+
+    /// Decodes the sequence, or returns `None` if there is no sequence with
+    /// that id.
+    pub fn decode(&self, id: usize) -> Option<Vec<T>> {
+        let bytes = self.sequences.get(id)?;
+        let mut items = Vec::new();
+        self.table.decode_bytes_into(bytes, &mut items).ok()?;
+        Some(items)
+    }
+}
+
+impl<T> EncodeSession<T>
+where
+    T: Ord + Clone + TableItem,
+{
+    /// Serializes the table, followed by every sequence.
+    ///
+    /// # Format
+    ///
+    /// The table is written as in [encode_container](Table::encode_container),
+    /// as a little-endian `u32` count followed by each item's bytes. Then
+    /// comes the number of sequences as a `u32`, and each sequence as its
+    /// length in bytes as a `u32` followed by its bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        (self.table.len() as u32).write_bytes(&mut bytes);
+        for item in self.table.iter() {
+            item.write_bytes(&mut bytes);
+        }
+        (self.sequences.len() as u32).write_bytes(&mut bytes);
+        for sequence in &self.sequences {
+            (sequence.len() as u32).write_bytes(&mut bytes);
+            bytes.extend_from_slice(sequence);
+        }
+        bytes
+    }
+
+    /// Loads a session serialized with [to_bytes](EncodeSession::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [InvalidBytes](Error::InvalidBytes) error if the table is
+    /// malformed or contains the same item twice, a sequence is cut short or
+    /// refers to an index not in the table, or there are bytes left over.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let read_u32 = |offset: usize| {
+            bytes
+                .get(offset..)
+                .and_then(u32::read_bytes)
+                .map(|(n, size)| (n as usize, offset + size))
+                .ok_or(Error::InvalidBytes(offset))
+        };
+
+        let (len, mut offset) = read_u32(0)?;
+        let mut session = Self::new();
+        for i in 0..len {
+            let (item, size) =
+                T::read_bytes(&bytes[offset..]).ok_or(Error::InvalidBytes(offset))?;
+            if session.table.insert_or_get(&item) != i {
+                return Err(Error::InvalidBytes(offset));
+            }
+            offset += size;
+        }
+
+        let (count, start) = read_u32(offset)?;
+        offset = start;
+        for _ in 0..count {
+            let (len, start) = read_u32(offset)?;
+            let sequence = bytes
+                .get(start..start + len)
+                .ok_or(Error::InvalidBytes(offset))?;
+            let mut used = Vec::new();
+            let mut pos = 0;
+            while let Some((ind, _, size)) =
+                read_run(sequence, pos).map_err(|_| Error::InvalidBytes(start + pos))?
+            {
+                if ind >= session.table.len() {
+                    return Err(Error::InvalidBytes(start + pos));
+                }
+                used.push(ind);
+                pos += size;
+            }
+            used.sort_unstable();
+            used.dedup();
+            session.sequences.push(sequence.to_vec());
+            session.used.push(used);
+            offset = start + len;
+        }
+        if offset != bytes.len() {
+            return Err(Error::InvalidBytes(offset));
+        }
+        Ok(session)
+    }
+}
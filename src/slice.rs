@@ -0,0 +1,89 @@
+use crate::bulk::{push_run, read_run};
+use crate::{Error, Index};
+use std::ops::{Bound, RangeBounds};
+
+/// Resolves a range of elements into start and end positions.
+fn bounds<R: RangeBounds<usize>>(range: R) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i + 1,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => usize::MAX,
+    };
+    (start, end)
+}
+
+/// Returns the runs covering a range of elements of the decoded sequence,
+/// shortening the runs at either end of the range to fit, without decoding.
+/// Any part of the range past the end of the sequence is ignored.
+///
+/// ```
+/// # use rle::slice_runs;
+/// let runs = [(0, 5), (1, 10), (2, 3), (0, 10)];
+/// assert_eq!(slice_runs(&runs, 3..17), [(0, 2), (1, 10), (2, 2)]);
+/// assert_eq!(slice_runs(&runs, 25..), [(0, 3)]);
+/// ```
+pub fn slice_runs<R>(runs: &[(Index, usize)], range: R) -> Vec<(Index, usize)>
+where
+    R: RangeBounds<usize>,
+{
+    let (start, end) = bounds(range);
+    let mut sliced = Vec::new();
+    let mut pos = 0;
+    for &(ind, len) in runs {
+        if pos >= end {
+            break;
+        }
+        let (from, to) = (pos.max(start), (pos + len).min(end));
+        if from < to {
+            sliced.push((ind, to - from));
+        }
+        pos += len;
+    }
+    sliced
+}
+
+/// Returns the run-length encoded bytes (see [encode_bytes](crate::Table::encode_bytes))
+/// covering a range of elements of the decoded sequence, like [slice_runs].
+/// Only the run headers are read, so the items are never decoded.
+///
+/// ```
+/// # use rle::{slice_bytes, Table};
+/// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+/// let table = Table::from_slice(&['A', 'B', 'C']);
+/// let bytes: Vec<u8> = table.encode_bytes(&str).unwrap().collect();
+///
+/// let viewport = slice_bytes(&bytes, 3..17).unwrap();
+/// assert!(table.decode_bytes(&viewport).eq(&str[3..17]));
+/// ```
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes
+/// end in the middle of a run before the end of the range.
+pub fn slice_bytes<R>(bytes: &[u8], range: R) -> Result<Vec<u8>, Error>
+where
+    R: RangeBounds<usize>,
+{
+    let (start, end) = bounds(range);
+    let mut sliced = Vec::new();
+    let mut pos = 0;
+    let mut offset = 0;
+    while pos < end {
+        let (ind, len, size) = match read_run(bytes, offset)? {
+            Some(run) => run,
+            None => break,
+        };
+        let (from, to) = (pos.max(start), (pos + len).min(end));
+        if from < to {
+            push_run(&mut sliced, ind as u8, to - from);
+        }
+        pos += len;
+        offset += size;
+    }
+    Ok(sliced)
+}
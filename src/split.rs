@@ -0,0 +1,83 @@
+use crate::{Error, Index, Table};
+
+/// An iterator that decodes a pair of index and length streams into a
+/// series of `T` values fetched from the table. See [decode_split](crate::Table::decode_split).
+pub struct SplitDecoder<'a, T> {
+    pub(crate) table: &'a Table<T>,
+    pub(crate) indices: &'a [u8],
+    pub(crate) lengths: &'a [u8],
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<'a, T> Iterator for SplitDecoder<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = self.run.take().or_else(|| {
+            let (&ind, indices) = self.indices.split_first()?;
+            let (&len, lengths) = self.lengths.split_first()?;
+            self.indices = indices;
+            self.lengths = lengths;
+            Some((ind as usize, len as usize))
+        })?;
+        if len > 1 {
+            self.run = Some((ind, len - 1));
+        }
+        self.table.get(ind)
+    }
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone,
+{
+    /// Run-length encodes the items into two separate streams of bytes: one
+    /// containing the index of each run, and one containing its length.
+    ///
+    /// Both streams have exactly one byte per run, so the `n`th index always
+    /// goes with the `n`th length. Runs longer than 255 items are split. Keeping
+    /// similar values together like this tends to help general-purpose
+    /// compressors applied afterwards, and allows the runs to be read in bulk.
+    /// Use [decode_split](Table::decode_split) to decode the streams.
+    ///
+    /// # Errors
+    ///
+    /// If `items` contains elements not in the table, this will return a
+    /// [TableMissingItems](Error::TableMissingItems) error. Because each index
+    /// is stored in a byte, this will return a [TableTooLarge](Error::TableTooLarge)
+    /// error if the table contains more than 256 items.
+    pub fn encode_split(&self, items: &[T]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        if self.len() > 256 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut indices = Vec::new();
+        let mut lengths = Vec::new();
+        for run in self.try_encode(items) {
+            let (ind, mut len) = run?;
+            while len > 0 {
+                let num = len.min(255);
+                indices.push(ind as u8);
+                lengths.push(num as u8);
+                len -= num;
+            }
+        }
+        Ok((indices, lengths))
+    }
+
+    /// Return an iterator that decodes the index and length streams produced by
+    /// [encode_split](Table::encode_split) using this table as the index lookup
+    /// for the elements.
+    ///
+    /// If one stream is longer than the other, its extra bytes are ignored.
+    pub fn decode_split<'a>(&'a self, indices: &'a [u8], lengths: &'a [u8]) -> SplitDecoder<'a, T> {
+        SplitDecoder {
+            table: self,
+            indices,
+            lengths,
+            run: None,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::bulk::{MAX_RUN, MAX_TABLE_LEN};
+use crate::compact::MAX_RUN as COMPACT_MAX_RUN;
+use crate::{EncodeOptions, Error, RunHeader, Table, TableStore};
+use std::collections::BTreeMap;
+
+/// Statistics about how a sequence of items would compress, gathered
+/// without encoding it. See [analyze](crate::Table::analyze).
+///
+/// The projected sizes assume every item is in the table, and ignore any
+/// limits the formats have on the size of the table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RleStats {
+    /// The number of items in the sequence.
+    pub items: usize,
+
+    /// The number of runs in the sequence.
+    pub runs: usize,
+
+    /// The number of distinct items in the sequence that are in the table.
+    pub symbols: usize,
+
+    /// The number of items in the sequence that are not in the table.
+    pub missing: usize,
+
+    /// How many runs there are of each length.
+    pub histogram: BTreeMap<usize, usize>,
+
+    /// The size in bytes of [encode_bytes](crate::Table::encode_bytes).
+    pub bytes_len: usize,
+
+    /// The size in bytes of [encode_bytes_varint](crate::Table::encode_bytes_varint).
+    pub varint_len: usize,
+
+    /// The size in bytes of [encode_bytes_with](crate::Table::encode_bytes_with)
+    /// with [literal_blocks](crate::EncodeOptions::literal_blocks).
+    pub literal_blocks_len: usize,
+
+    /// The size in bytes of [encode_bytes_compact](crate::Table::encode_bytes_compact).
+    pub compact_len: usize,
+}
+
+impl RleStats {
+    /// The average length of a run.
+    pub fn mean_run_len(&self) -> f64 {
+        self.items as f64 / self.runs.max(1) as f64
+    }
+
+    /// The size of [encode_bytes](crate::Table::encode_bytes) compared to
+    /// one byte per item, where anything below `1.0` is a saving.
+    pub fn ratio(&self) -> f64 {
+        self.bytes_len as f64 / self.items.max(1) as f64
+    }
+
+    /// The smallest projected size of any of the formats.
+    pub fn min_len(&self) -> usize {
+        self.bytes_len
+            .min(self.varint_len)
+            .min(self.literal_blocks_len)
+            .min(self.compact_len)
+    }
+
+    fn add_run(&mut self, len: usize) {
+        self.runs += 1;
+        self.items += len;
+        *self.histogram.entry(len).or_insert(0) += 1;
+
+        self.bytes_len += flagged_size(len, MAX_RUN);
+        self.varint_len += if len == 1 { 1 } else { 1 + varint_size(len) };
+        let (full, rest) = (len / COMPACT_MAX_RUN, len % COMPACT_MAX_RUN);
+        self.compact_len += full * 2
+            + match rest {
+                0 => 0,
+                1..=15 => 1,
+                _ => 2,
+            };
+    }
+}
+
+/// The size of a run written with [RunHeader::Flagged] headers, split into
+/// runs of at most `max_run` (see [push_run_max](crate::bulk::push_run_max)).
+fn flagged_size(len: usize, max_run: usize) -> usize {
+    let (full, rest) = (len / max_run, len % max_run);
+    full * if max_run == 1 { 1 } else { 2 } + rest.min(2)
+}
+
+fn varint_size(mut len: usize) -> usize {
+    let mut size = 1;
+    while len >= 0x80 {
+        len >>= 7;
+        size += 1;
+    }
+    size
+}
+
+/// Tracks the size of PackBits output (see [encode_packbits](crate::encode_packbits))
+/// as runs are added, following the same choices the encoder makes.
+#[derive(Default)]
+struct PackBitsSize {
+    size: usize,
+    literal: usize,
+}
+
+impl PackBitsSize {
+    fn add_run(&mut self, len: usize) {
+        if len < 3 {
+            self.literal += len;
+            return;
+        }
+        self.flush();
+        let (full, rest) = (len / 128, len % 128);
+        self.size += full * 2;
+        self.size += match rest {
+            0 => 0,
+            1 | 2 => rest + 1,
+            _ => 2,
+        };
+    }
+
+    fn flush(&mut self) {
+        self.size += self.literal + self.literal.div_ceil(128);
+        self.literal = 0;
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Gathers statistics about how the items would compress, such as how
+    /// many runs they form and how large each byte format would be, without
+    /// encoding them. This can be used to decide whether compressing a
+    /// sequence is worth it, or which format to compress it with.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    ///
+    /// let stats = table.analyze(&str);
+    /// assert_eq!(stats.runs, 4);
+    /// assert_eq!(stats.symbols, 3);
+    /// assert_eq!(stats.histogram[&10], 2);
+    /// assert_eq!(stats.bytes_len, 8);
+    /// assert!(stats.ratio() < 0.5);
+    /// ```
+    pub fn analyze(&self, items: &[T]) -> RleStats {
+        let mut stats = RleStats::default();
+        let mut packbits = PackBitsSize::default();
+        let mut used = vec![false; self.len()];
+        let mut i = 0;
+        while i < items.len() {
+            let len = self.run_len(&items[i..]);
+            match self.get_index(&items[i]) {
+                Some(ind) => used[ind] = true,
+                None => stats.missing += len,
+            }
+            stats.add_run(len);
+            packbits.add_run(len);
+            i += len;
+        }
+        packbits.flush();
+        stats.symbols = used.iter().filter(|&&used| used).count();
+        stats.literal_blocks_len = packbits.size;
+        stats
+    }
+
+    /// Returns the exact number of bytes [encode_bytes](Table::encode_bytes)
+    /// would produce for the items, without producing them. This can be used
+    /// to preallocate a buffer, or to decide whether to store the items raw.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table::encode_bytes).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAAAABBBBBBBBBBCCCAAAAAAAAAA".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// assert_eq!(table.encoded_size(&str).unwrap(), 8);
+    /// ```
+    pub fn encoded_size(&self, items: &[T]) -> Result<usize, Error> {
+        if self.len() > MAX_TABLE_LEN {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut size = 0;
+        let mut i = 0;
+        while i < items.len() {
+            self.get_index(&items[i])
+                .ok_or(Error::TableMissingItems(i))?;
+            let len = self.run_len(&items[i..]);
+            size += flagged_size(len, MAX_RUN);
+            i += len;
+        }
+        Ok(size)
+    }
+
+    /// Returns the exact number of bytes [encode_bytes_with](Table::encode_bytes_with)
+    /// would produce for the items with the same `options`.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes_with](Table::encode_bytes_with).
+    ///
+    /// ```
+    /// # use rle::{EncodeOptions, RunHeader, Table};
+    /// let str = vec!['A'; 100];
+    /// let table = Table::from_slice(&['A']);
+    /// let options = EncodeOptions::new().max_run(63).header(RunHeader::Pair);
+    /// assert_eq!(table.encoded_size_with(&str, options).unwrap(), 4);
+    /// ```
+    pub fn encoded_size_with(&self, items: &[T], options: EncodeOptions) -> Result<usize, Error> {
+        let max_table_len = match (options.literal_blocks, options.header) {
+            (true, _) | (false, RunHeader::Pair) => 256,
+            (false, RunHeader::Flagged) => MAX_RUN,
+        };
+        if self.len() > max_table_len {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let runs = self.runs_with_fallback(items, options.fallback, None)?;
+        let max_run = options.max_run.clamp(1, 255);
+        let mut packbits = PackBitsSize::default();
+        let mut size = 0;
+        for (_, len) in runs {
+            match (options.literal_blocks, options.header) {
+                (true, _) => packbits.add_run(len),
+                (false, RunHeader::Flagged) => size += flagged_size(len, max_run),
+                (false, RunHeader::Pair) => size += len.div_ceil(max_run) * 2,
+            }
+        }
+        packbits.flush();
+        Ok(size + packbits.size)
+    }
+}
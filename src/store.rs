@@ -0,0 +1,226 @@
+use crate::Index;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// The storage behind a [Table](crate::Table), which assigns every unique
+/// item a permanent index.
+///
+/// The default store is [SortedStore], but any type implementing this trait
+/// (such as a wrapper around an existing interner or arena) can be used with
+/// [Table::with_store](crate::Table::with_store), and all of the table's
+/// core encoders and decoders will work with it.
+///
+/// Features that need to remove, replace or sort items, such as
+/// [retain](crate::Table::retain), [iter_sorted](crate::Table::iter_sorted)
+/// and [BoundedTable](crate::BoundedTable), are only available with the
+/// default store.
+pub trait TableStore<T> {
+    /// The number of unique items in the store.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the store contains no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the item at `index`, or `None` if out of bounds.
+    fn get(&self, index: Index) -> Option<&T>;
+
+    /// Returns the index of the item, or `None` if it is not in the store.
+    fn index_of(&self, item: &T) -> Option<Index>;
+
+    /// Returns the index of the item, adding it to the store first if it
+    /// is not already there. New items must be given the next index in
+    /// sequence (the length of the store before they were added).
+    fn insert_or_get(&mut self, item: &T) -> Index;
+
+    /// Removes all items from the store.
+    fn clear(&mut self);
+
+    /// Returns `true` if the store would give both items the same index.
+    ///
+    /// Encoders use this to find where each run ends, so that runs always
+    /// agree with the store's lookups. By default it compares with `==`,
+    /// but stores that look items up another way (such as by [Ord]) should
+    /// compare the same way.
+    fn same_item(&self, a: &T, b: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        a == b
+    }
+}
+
+/// A [TableStore] that can look up items by a borrowed form `Q` of the item,
+/// such as a `&str` for a store of `String`s, without constructing an item.
+///
+/// This is what lets [index_of_by_key](crate::Table::index_of_by_key), [contains_by_key](crate::Table::contains_by_key)
+/// and [encode_borrowed](crate::Table::encode_borrowed) take borrowed keys, in
+/// the same way as the lookups on [BTreeMap]. Custom stores must implement it
+/// (with `Q` as the item type, at least) to support those methods.
+pub trait StoreLookup<Q: ?Sized> {
+    /// Returns the index of the item that is equal to `key`, or `None` if
+    /// there is no such item in the store.
+    fn index_of_key(&self, key: &Q) -> Option<Index>;
+}
+
+/// The default [TableStore], which keeps the items in a list along with
+/// an ordered map of the items to their indices for fast lookup and insertion.
+#[derive(Clone, Debug)]
+pub struct SortedStore<T> {
+    /// This is a list of the items in the order they were added,
+    /// their positions in this list will not ever change.
+    pub(crate) items: Vec<T>,
+
+    /// This maps each item to its index in `items` for fast lookup/retrieval
+    /// when encoding, and for adding new items without shifting the rest.
+    lookup: BTreeMap<T, Index>,
+
+    /// This is a sorted list of the items (usize maps to the item's index in
+    /// `items`), built on demand for sorted queries and thrown away whenever
+    /// the items change.
+    sorted: OnceLock<Vec<Index>>,
+}
+
+impl<T> Default for SortedStore<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            lookup: BTreeMap::new(),
+            sorted: OnceLock::new(),
+        }
+    }
+}
+
+impl<T> SortedStore<T>
+where
+    T: Ord + Clone,
+{
+    /// Constructs a new, empty store with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// The items in the order they were added.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The indices of the items, in the sorted order of the items.
+    pub(crate) fn sorted(&self) -> &[Index] {
+        self.sorted
+            .get_or_init(|| self.lookup.values().copied().collect())
+    }
+
+    /// Removes every item that was added after the first `len` items.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.items.truncate(len);
+        self.lookup.retain(|_, &mut i| i < len);
+        self.sorted.take();
+    }
+
+    /// Removes every item for which `f` returns `false`, moving the items
+    /// after them down to fill the gaps. Returns the new index of each old
+    /// index, or `None` if its item was removed.
+    pub(crate) fn retain<F>(&mut self, mut f: F) -> Vec<Option<Index>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut next = 0;
+        let remap: Vec<Option<Index>> = self
+            .items
+            .iter()
+            .map(|item| {
+                f(item).then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+        let mut i = 0;
+        self.items.retain(|_| {
+            i += 1;
+            remap[i - 1].is_some()
+        });
+        self.lookup.retain(|_, i| match remap[*i] {
+            Some(new) => {
+                *i = new;
+                true
+            }
+            None => false,
+        });
+        self.sorted.take();
+        remap
+    }
+
+    /// Binary searches the sorted order for the item, returning its sorted
+    /// position if found, or the position it would be inserted at if not.
+    pub(crate) fn search(&self, item: &T) -> Result<usize, usize> {
+        self.sorted().binary_search_by(|&i| self.items[i].cmp(item))
+    }
+
+    /// Replaces the item at `index` with a new item that is not already in
+    /// the store, keeping all other items at their current positions.
+    pub(crate) fn replace(&mut self, index: usize, item: T) {
+        self.lookup.remove(&self.items[index]);
+        self.lookup.insert(item.clone(), index);
+        self.items[index] = item;
+        self.sorted.take();
+    }
+}
+
+impl<T> TableStore<T> for SortedStore<T>
+where
+    T: Ord + Clone,
+{
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn get(&self, index: Index) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    fn index_of(&self, item: &T) -> Option<Index> {
+        self.lookup.get(item).copied()
+    }
+
+    fn insert_or_get(&mut self, item: &T) -> Index {
+        if let Some(&ind) = self.lookup.get(item) {
+            return ind;
+        }
+        let ind = self.items.len();
+        self.items.push(item.clone());
+        self.lookup.insert(item.clone(), ind);
+        self.sorted.take();
+        ind
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.lookup.clear();
+        self.sorted.take();
+    }
+
+    fn same_item(&self, a: &T, b: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        a.cmp(b) == Ordering::Equal
+    }
+}
+
+impl<T, Q> StoreLookup<Q> for SortedStore<T>
+where
+    T: Borrow<Q> + Ord,
+    Q: Ord + ?Sized,
+{
+    fn index_of_key(&self, key: &Q) -> Option<Index> {
+        self.lookup.get(key).copied()
+    }
+}
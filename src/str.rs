@@ -0,0 +1,94 @@
+//! Helpers for encoding and decoding text directly, without first collecting
+//! it into a `Vec<char>`.
+//!
+//! ```
+//! use rle::str::{decode_to_string, encode_str};
+//! use rle::Table;
+//!
+//! let mut table = Table::default();
+//! let runs = encode_str(&mut table, "AAAAABBBBBBBBBBCCCAAAAAAAAAA");
+//! assert_eq!(runs, [(0, 5), (1, 10), (2, 3), (0, 10)]);
+//! assert_eq!(decode_to_string(&table, &runs), "AAAAABBBBBBBBBBCCCAAAAAAAAAA");
+//! ```
+
+use crate::bulk::{push_run, read_run};
+use crate::{Error, Index, Table, TableStore};
+
+/// Run-length encodes the characters of the string, adding any characters
+/// that are missing from the table as it goes.
+pub fn encode_str<S>(table: &mut Table<char, S>, str: &str) -> Vec<(Index, usize)>
+where
+    S: TableStore<char>,
+{
+    let mut runs = Vec::new();
+    let mut rest = str;
+    while let Some(chr) = rest.chars().next() {
+        let next = rest.trim_start_matches(chr);
+        let len = (rest.len() - next.len()) / chr.len_utf8();
+        runs.push((table.insert_or_get(&chr), len));
+        rest = next;
+    }
+    runs
+}
+
+/// Run-length encodes the characters of the string as a sequence of bytes
+/// (see [encode_bytes](crate::Table::encode_bytes)), adding any characters
+/// that are missing from the table as it goes.
+///
+/// # Errors
+///
+/// Returns a [TableTooLarge](Error::TableTooLarge) error if the table ends
+/// up with more than 127 characters, in which case the table keeps any
+/// characters that were added.
+pub fn encode_str_bytes<S>(table: &mut Table<char, S>, str: &str) -> Result<Vec<u8>, Error>
+where
+    S: TableStore<char>,
+{
+    let runs = encode_str(table, str);
+    if table.len() >= 128 {
+        return Err(Error::TableTooLarge(table.len()));
+    }
+    let mut bytes = Vec::with_capacity(runs.len() * 2);
+    for (ind, len) in runs {
+        push_run(&mut bytes, ind as u8, len);
+    }
+    Ok(bytes)
+}
+
+/// Decodes the runs into a string. Decoding stops at the first run with an
+/// index that is not in the table, as with [decode](crate::Table::decode).
+pub fn decode_to_string<S>(table: &Table<char, S>, runs: &[(Index, usize)]) -> String
+where
+    S: TableStore<char>,
+{
+    let mut str = String::new();
+    for &(ind, len) in runs {
+        match table.item(ind) {
+            Some(&chr) => str.extend(std::iter::repeat_n(chr, len)),
+            None => break,
+        }
+    }
+    str
+}
+
+/// Decodes run-length encoded bytes (see [decode_bytes](crate::Table::decode_bytes))
+/// into a string.
+///
+/// # Errors
+///
+/// Returns the same errors as [decode_bytes_into](crate::Table::decode_bytes_into).
+pub fn decode_bytes_to_string<S>(table: &Table<char, S>, bytes: &[u8]) -> Result<String, Error>
+where
+    S: TableStore<char>,
+{
+    let summary = table.validate_bytes(bytes)?;
+    let mut str = String::with_capacity(summary.items);
+    let mut offset = 0;
+    while let Some((ind, len, size)) = read_run(bytes, offset)? {
+        if let Some(&chr) = table.item(ind) {
+            str.extend(std::iter::repeat_n(chr, len));
+        }
+        offset += size;
+    }
+    Ok(str)
+}
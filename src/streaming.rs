@@ -0,0 +1,283 @@
+use crate::bulk::push_run;
+use crate::{Error, Index, SortedStore, Table, TableStore};
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::vec::Drain;
+
+/// An encoder that items are pushed into a few at a time, for when they
+/// arrive in pieces and runs may continue from one piece into the next.
+/// See [encode_streaming](crate::Table::encode_streaming).
+///
+/// Runs are only completed once an item that doesn't belong to them is
+/// pushed, or the encoder is finished.
+pub struct StreamingEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) runs: Vec<(Index, usize)>,
+    pub(crate) position: usize,
+}
+
+impl<'a, T, S> StreamingEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Pushes the next item, which extends the current run or starts a new one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableMissingItems](Error::TableMissingItems) error with the
+    /// position of the item in the whole stream if it is not in the table,
+    /// in which case the item is not pushed.
+    pub fn push<B: Borrow<T>>(&mut self, item: B) -> Result<(), Error> {
+        let ind = self
+            .table
+            .get_index(item.borrow())
+            .ok_or(Error::TableMissingItems(self.position))?;
+        self.push_run(ind, 1);
+        Ok(())
+    }
+
+    /// Pushes a slice of items.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableMissingItems](Error::TableMissingItems) error with the
+    /// position of the first item in the whole stream that is not in the
+    /// table. The items before it will have been pushed.
+    pub fn push_slice(&mut self, items: &[T]) -> Result<(), Error> {
+        let mut i = 0;
+        while i < items.len() {
+            let ind = self
+                .table
+                .get_index(&items[i])
+                .ok_or(Error::TableMissingItems(self.position))?;
+            let len = self.table.run_len(&items[i..]);
+            self.push_run(ind, len);
+            i += len;
+        }
+        Ok(())
+    }
+
+    fn push_run(&mut self, ind: Index, len: usize) {
+        self.position += len;
+        match &mut self.run {
+            Some(run) if run.0 == ind => run.1 += len,
+            run => {
+                if let Some(done) = run.replace((ind, len)) {
+                    self.runs.push(done);
+                }
+            }
+        }
+    }
+
+    /// The number of items pushed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Removes and returns the runs that have been completed so far.
+    pub fn runs(&mut self) -> Drain<'_, (Index, usize)> {
+        self.runs.drain(..)
+    }
+
+    /// Removes the runs that have been completed so far, appending them to
+    /// `bytes` in the format of [encode_bytes](crate::Table::encode_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [TableTooLarge](Error::TableTooLarge) error if the table has
+    /// more than 127 items, in which case no runs are removed.
+    pub fn write_bytes(&mut self, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        if self.table.len() >= 128 {
+            return Err(Error::TableTooLarge(self.table.len()));
+        }
+        for (ind, len) in self.runs.drain(..) {
+            push_run(bytes, ind as u8, len);
+        }
+        Ok(())
+    }
+
+    /// Completes the current run, returning it along with any other runs that
+    /// have not been removed yet.
+    pub fn finish(mut self) -> Vec<(Index, usize)> {
+        self.runs.extend(self.run.take());
+        self.runs
+    }
+
+    /// Completes the current run, appending it and any other runs that have
+    /// not been removed yet to `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [write_bytes](StreamingEncoder::write_bytes).
+    pub fn finish_bytes(mut self, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        self.runs.extend(self.run.take());
+        self.write_bytes(bytes)
+    }
+}
+
+/// A decoder that bytes are fed into a few at a time, for when they arrive
+/// in pieces and runs may be split from one piece to the next.
+/// See [decode_streaming](crate::Table::decode_streaming).
+pub struct StreamingDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) header: Option<u8>,
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) buffered: VecDeque<u8>,
+    pub(crate) error: Option<Error>,
+}
+
+impl<'a, T, S> StreamingDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Feeds the next piece of bytes, returning an iterator over the items
+    /// that can be decoded so far.
+    ///
+    /// If the bytes end with a run's header but not its length, the header
+    /// is held onto until the next call. If the iterator is dropped before
+    /// it finishes, the rest of the bytes are held onto as well.
+    ///
+    /// The iterator yields an [UnknownIndex](Error::UnknownIndex) error if a
+    /// run refers to an index not in the table, after which the decoder
+    /// stops for good.
+    pub fn feed<'d>(&'d mut self, bytes: &'d [u8]) -> StreamingItems<'d, 'a, T, S> {
+        StreamingItems {
+            decoder: self,
+            bytes,
+        }
+    }
+
+    /// Checks that the bytes fed in ended cleanly.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the decoder stopped with, if any, or a
+    /// [TruncatedStream](Error::TruncatedStream) error if the last run's
+    /// length never arrived.
+    pub fn finish(self) -> Result<(), Error> {
+        match (self.error, self.header) {
+            (Some(err), _) => Err(err),
+            (None, Some(_)) => Err(Error::TruncatedStream),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// An iterator over the items decoded from a piece of bytes.
+/// See [feed](crate::StreamingDecoder::feed).
+pub struct StreamingItems<'d, 'a, T, S = SortedStore<T>> {
+    pub(crate) decoder: &'d mut StreamingDecoder<'a, T, S>,
+    pub(crate) bytes: &'d [u8],
+}
+
+impl<'d, 'a, T, S> StreamingItems<'d, 'a, T, S> {
+    fn next_byte(&mut self) -> Option<u8> {
+        self.decoder.buffered.pop_front().or_else(|| {
+            let (&byte, rest) = self.bytes.split_first()?;
+            self.bytes = rest;
+            Some(byte)
+        })
+    }
+}
+
+impl<'d, 'a, T, S> Iterator for StreamingItems<'d, 'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = Result<&'a T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let table = self.decoder.table;
+        loop {
+            if self.decoder.error.is_some() {
+                return None;
+            }
+            if let Some((ind, len)) = self.decoder.run.take() {
+                if len > 1 {
+                    self.decoder.run = Some((ind, len - 1));
+                }
+                return table.item(ind).map(Ok);
+            }
+            let byte = self.next_byte()?;
+            let (ind, len) = match self.decoder.header.take() {
+                Some(header) => (header >> 1, (byte as usize).max(1)),
+                None if (byte & 1) == 1 => {
+                    self.decoder.header = Some(byte);
+                    continue;
+                }
+                None => (byte >> 1, 1),
+            };
+            let ind = ind as usize;
+            if table.item(ind).is_none() {
+                self.decoder.error = Some(Error::UnknownIndex(ind));
+                return Some(Err(Error::UnknownIndex(ind)));
+            }
+            self.decoder.run = Some((ind, len));
+        }
+    }
+}
+
+impl<'d, 'a, T, S> Drop for StreamingItems<'d, 'a, T, S> {
+    fn drop(&mut self) {
+        self.decoder.buffered.extend(self.bytes);
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Returns an encoder that items can be pushed into as they arrive,
+    /// carrying runs over from one push to the next.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let mut encoder = table.encode_streaming();
+    /// encoder.push_slice(&['A', 'A', 'B']).unwrap();
+    /// assert_eq!(encoder.runs().collect::<Vec<_>>(), [(0, 2)]);
+    ///
+    /// encoder.push_slice(&['B', 'B']).unwrap();
+    /// encoder.push('A').unwrap();
+    /// assert_eq!(encoder.finish(), [(1, 3), (0, 1)]);
+    /// ```
+    pub fn encode_streaming(&self) -> StreamingEncoder<'_, T, S> {
+        StreamingEncoder {
+            table: self,
+            run: None,
+            runs: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Returns a decoder that bytes can be fed into as they arrive, carrying
+    /// runs over from one piece to the next.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let mut decoder = table.decode_streaming();
+    ///
+    /// // The first piece ends between a run's header and its length
+    /// let str: String = decoder.feed(&[0x00, 0x03]).map(|item| item.unwrap()).collect();
+    /// assert_eq!(str, "A");
+    ///
+    /// let str: String = decoder.feed(&[0x04]).map(|item| item.unwrap()).collect();
+    /// assert_eq!(str, "BBBB");
+    /// decoder.finish().unwrap();
+    /// ```
+    pub fn decode_streaming(&self) -> StreamingDecoder<'_, T, S> {
+        StreamingDecoder {
+            table: self,
+            header: None,
+            run: None,
+            buffered: VecDeque::new(),
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Index, StoreLookup, TableStore};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// A [TableStore] optimized for large alphabets of strings, such as log
+/// levels, enum names or tokens. Enabled with the `strings` feature.
+///
+/// Lookups hash the string once instead of comparing it against other
+/// strings during a binary search, and each string is only stored once:
+/// the lookup map holds hashes and indices rather than its own copy of
+/// every string. Any string-like type can be used, such as `String`,
+/// `&str`, `Box<str>` or `Rc<str>`.
+///
+/// ```
+/// use rle::{StringStore, Table};
+///
+/// let levels: Vec<&str> = vec!["INFO", "INFO", "INFO", "WARN", "INFO"];
+/// let mut table = Table::with_store(StringStore::new());
+/// let runs: Vec<_> = table.encode_mut(&levels).collect();
+/// assert_eq!(runs, [(0, 3), (1, 1), (0, 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StringStore<T = String> {
+    items: Vec<T>,
+
+    /// Maps the hash of a string to the first item with that hash.
+    lookup: HashMap<u64, Index>,
+
+    /// For each item, the next item with the same hash, if any.
+    next: Vec<Option<Index>>,
+}
+
+impl<T> Default for StringStore<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            lookup: HashMap::new(),
+            next: Vec::new(),
+        }
+    }
+}
+
+impl<T> StringStore<T>
+where
+    T: AsRef<str> + Clone,
+{
+    /// Constructs a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new, empty store with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            lookup: HashMap::with_capacity(capacity),
+            next: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The strings in the order they were added.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    fn hash(item: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn find(&self, hash: u64, item: &str) -> Option<Index> {
+        let mut ind = self.lookup.get(&hash).copied();
+        while let Some(i) = ind {
+            if self.items[i].as_ref() == item {
+                return Some(i);
+            }
+            ind = self.next[i];
+        }
+        None
+    }
+}
+
+impl<T> TableStore<T> for StringStore<T>
+where
+    T: AsRef<str> + Clone,
+{
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn get(&self, index: Index) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    fn index_of(&self, item: &T) -> Option<Index> {
+        self.find(Self::hash(item.as_ref()), item.as_ref())
+    }
+
+    fn insert_or_get(&mut self, item: &T) -> Index {
+        let hash = Self::hash(item.as_ref());
+        self.find(hash, item.as_ref()).unwrap_or_else(|| {
+            let ind = self.items.len();
+            self.items.push(item.clone());
+            self.next.push(self.lookup.insert(hash, ind));
+            ind
+        })
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.lookup.clear();
+        self.next.clear();
+    }
+}
+
+/// Strings can be looked up by any string-like key, such as a `&str` for a
+/// store of `String`s.
+impl<T, Q> StoreLookup<Q> for StringStore<T>
+where
+    T: AsRef<str> + Clone,
+    Q: AsRef<str> + ?Sized,
+{
+    fn index_of_key(&self, key: &Q) -> Option<Index> {
+        self.find(Self::hash(key.as_ref()), key.as_ref())
+    }
+}
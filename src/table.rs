@@ -1,29 +1,48 @@
+use crate::bulk::MAX_RUN;
+use crate::byte_scan::run_len;
 use crate::{
-    BytesDecoder, BytesEncoder, BytesEncoderMut, Decoder, Encoder, EncoderMut, Error, Index,
+    BytesDecoder, BytesDecoderMut, BytesEncoder, BytesEncoderMut, BytesRunDecoder, Decoder,
+    DecoderState, DisplayRuns, Encoder, EncoderMut, EnumeratedDecoder, Error, Index,
+    InlineBytesEncoder, IntoRun, OffsetEncoder, Run, RunDecoder, RunEncoder, RunLen, TableItem,
+    TryEncoder,
 };
-use serde::{Deserialize, Serialize};
+use crate::{SortedStore, StoreLookup, TableStore};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
 use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::ops::Deref;
 use std::slice::SliceIndex;
 
 /// A table to store items to be encoded into run-length format.
+///
+/// The items are kept in a [TableStore], which is a [SortedStore] unless
+/// another store is provided with [with_store](Table::with_store).
+///
+/// # Serialization
+///
+/// With the `serde` feature enabled, a table is serialized as a plain
+/// sequence of its items in insertion order, which is the order of their
+/// indices in encoded data. For example, a table of `['.', 'X']` is stored
+/// in JSON as `[".","X"]`. This representation is stable, so tables can be
+/// hand-edited and kept alongside the data that was encoded with them.
+/// Deserializing a sequence that contains the same item twice will fail,
+/// since that would shift the indices of every item after it.
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Table<T> {
-    /// This is a list of the items in the order they were added,
-    /// their positions in this list will not ever change.
-    items: Vec<T>,
-
-    /// This is a sorted list of the items (usize maps to the item's
-    /// index in `items`) for fast lookup/retrieval when encoding.
-    sorted: Vec<usize>,
+pub struct Table<T, S = SortedStore<T>> {
+    store: S,
+    marker: PhantomData<T>,
 }
 
 impl<T> Default for Table<T> {
     fn default() -> Self {
         Self {
-            items: Vec::new(),
-            sorted: Vec::new(),
+            store: SortedStore::default(),
+            marker: PhantomData,
         }
     }
 }
@@ -34,10 +53,7 @@ where
 {
     /// Constructs a new, empty table with the specified capacity.
     pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            items: Vec::with_capacity(capacity),
-            sorted: Vec::with_capacity(capacity),
-        }
+        Self::with_store(SortedStore::with_capacity(capacity))
     }
 
     /// Constructs a new table with items collected from an iterator.
@@ -54,11 +70,6 @@ where
         table
     }
 
-    /// The number of unique elements in the table.
-    pub fn len(&self) -> usize {
-        self.items.len()
-    }
-
     /// Returns a reference to an item or subslice depending on the type of index.
     ///
     /// - If given a position, returns a reference to the item at that
@@ -69,7 +80,155 @@ where
     where
         I: SliceIndex<[T]>,
     {
-        self.items.get(index)
+        self.store.items.get(index)
+    }
+
+    /// Removes every item that was added after the first `len` items.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.store.truncate(len);
+    }
+
+    /// Replaces the item at `index` with a new item that is not already in
+    /// the table, keeping all other items at their current positions.
+    pub(crate) fn replace(&mut self, index: usize, item: T) {
+        self.store.replace(index, item);
+    }
+
+    /// Removes an item from the table, moving every item after it down
+    /// by one index.
+    ///
+    /// Since this changes the indices of other items, a remapping is returned
+    /// where `remap[old_index]` is the item's new index, or `None` for the
+    /// removed item. This can be used to translate runs that were encoded
+    /// with the table before the item was removed. Returns `None` if the item
+    /// was not in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table = Table::from_slice(&['A', 'B', 'C']);
+    /// let runs = vec![(0, 3), (2, 5)];
+    ///
+    /// let remap = table.remove(&'B').unwrap();
+    /// let runs: Vec<_> = runs.iter().map(|&(i, n)| (remap[i].unwrap(), n)).collect();
+    /// assert_eq!(runs, vec![(0, 3), (1, 5)]);
+    /// assert_eq!(table[1], 'C');
+    /// ```
+    pub fn remove(&mut self, item: &T) -> Option<Vec<Option<Index>>> {
+        self.get_index(item)?;
+        Some(self.store.retain(|x| x != item))
+    }
+
+    /// Removes every item for which `f` returns `false`, moving the remaining
+    /// items down to fill the gaps. Returns the same remapping as
+    /// [remove](Table::remove), where `remap[old_index]` is `None` for every
+    /// removed item.
+    pub fn retain<F>(&mut self, f: F) -> Vec<Option<Index>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.store.retain(f)
+    }
+
+    /// Returns the item at position `i` in sorted order, or `None` if out
+    /// of bounds.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['C', 'A', 'B']);
+    /// assert_eq!(table.get_sorted(0), Some(&'A'));
+    /// assert_eq!(table.get_sorted(3), None);
+    /// ```
+    pub fn get_sorted(&self, i: usize) -> Option<&T> {
+        self.store
+            .sorted()
+            .get(i)
+            .map(|&ind| &self.store.items[ind])
+    }
+
+    /// Returns the position of the item in sorted order, or `None` if it
+    /// is not in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['C', 'A', 'B']);
+    /// assert_eq!(table.sorted_position(&'C'), Some(2));
+    /// assert_eq!(table.sorted_position(&'D'), None);
+    /// ```
+    pub fn sorted_position(&self, item: &T) -> Option<usize> {
+        self.store.search(item).ok()
+    }
+
+    pub fn iter(&self) -> TableIter<'_, T> {
+        TableIter {
+            items: &self.store.items,
+        }
+    }
+
+    pub fn iter_sorted(&self) -> SortedTableIter<'_, T> {
+        SortedTableIter {
+            items: &self.store.items,
+            sorted: self.store.sorted(),
+        }
+    }
+
+    /// Iterates the items in sorted order, along with the index each one
+    /// has in the table (the index that runs refer to it by).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['C', 'A', 'B']);
+    /// let sorted: Vec<_> = table.iter_sorted_with_index().collect();
+    /// assert_eq!(sorted, vec![(1, &'A'), (2, &'B'), (0, &'C')]);
+    /// ```
+    pub fn iter_sorted_with_index(&self) -> impl Iterator<Item = (Index, &T)> + '_ {
+        let items = &self.store.items;
+        self.store.sorted().iter().map(move |&i| (i, &items[i]))
+    }
+
+    /// Returns the table's indices in the sorted order of their items, so
+    /// `sorted_indices()[k]` is the index of the `k`th smallest item.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['C', 'A', 'B']);
+    /// assert_eq!(table.sorted_indices(), &[1, 2, 0]);
+    /// ```
+    pub fn sorted_indices(&self) -> &[Index] {
+        self.store.sorted()
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Constructs a new table that keeps its items in the store.
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            marker: PhantomData,
+        }
+    }
+
+    /// The store that holds the table's items.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The number of unique elements in the table.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Returns `true` if the table contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Returns a reference to the item at `index`, or `None` if out of bounds.
+    pub fn item(&self, index: Index) -> Option<&T> {
+        self.store.get(index)
     }
 
     /// Clears the table, removing all items.
@@ -77,29 +236,11 @@ where
     /// Note that this method has no effect on the allocated capacity
     /// of the table.
     pub fn clear(&mut self) {
-        self.items.clear();
-        self.sorted.clear();
+        self.store.clear();
     }
 
     pub(crate) fn insert_or_get(&mut self, item: &T) -> usize {
-        match self.sorted.binary_search_by(|&i| self.items[i].cmp(item)) {
-            Ok(i) => self.sorted[i],
-            Err(i) => {
-                let ind = self.items.len();
-                self.items.push(item.clone());
-                self.sorted.insert(i, ind);
-                ind
-            }
-        }
-        /*self.sorted
-        .binary_search_by(|&ind| self.items[ind].cmp(item))
-        .and_then(|i| Ok(self.sorted[i]))
-        .unwrap_or_else(|i| {
-            let ind = self.items.len();
-            self.items.push(item.clone());
-            self.sorted.insert(i, ind);
-            ind
-        })*/
+        self.store.insert_or_get(item)
     }
 
     /// Inserts the item into the table. Tables only contain unique
@@ -110,10 +251,82 @@ where
     }
 
     pub(crate) fn get_index(&self, item: &T) -> Option<usize> {
-        self.sorted
-            .binary_search_by(|&i| self.items[i].cmp(item))
-            .ok()
-            .and_then(|i| Some(self.sorted[i]))
+        self.store.index_of(item)
+    }
+
+    /// The length of the run at the start of `items`, comparing the items
+    /// the same way as the store does (see [same_item](TableStore::same_item)).
+    pub(crate) fn run_len(&self, items: &[T]) -> usize {
+        match items.split_first() {
+            Some((first, rest)) => {
+                1 + rest
+                    .iter()
+                    .take_while(|item| self.store.same_item(first, item))
+                    .count()
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns `true` if the item is in the table.
+    pub fn contains(&self, item: &T) -> bool {
+        self.get_index(item).is_some()
+    }
+
+    /// Returns the index of the item in the table, or `None` if it is
+    /// not in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['C', 'A', 'B']);
+    /// assert_eq!(table.index_of(&'A'), Some(1));
+    /// assert_eq!(table.index_of(&'D'), None);
+    /// ```
+    pub fn index_of(&self, item: &T) -> Option<Index> {
+        self.get_index(item)
+    }
+
+    /// Like [contains](Table::contains), but takes any borrowed form of `T`
+    /// supported by the store (see [StoreLookup]), such as a `&str` for a
+    /// `Table<String>`.
+    pub fn contains_by_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized,
+        S: StoreLookup<Q>,
+    {
+        self.store.index_of_key(key).is_some()
+    }
+
+    /// Like [index_of](Table::index_of), but takes any borrowed form of `T`
+    /// supported by the store (see [StoreLookup]), such as a `&str` for a
+    /// `Table<String>`.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&["grass".to_string(), "water".to_string()]);
+    /// assert_eq!(table.index_of_by_key("water"), Some(1));
+    /// assert_eq!(table.index_of_by_key("lava"), None);
+    /// ```
+    pub fn index_of_by_key<Q>(&self, key: &Q) -> Option<Index>
+    where
+        Q: ?Sized,
+        S: StoreLookup<Q>,
+    {
+        self.store.index_of_key(key)
+    }
+
+    /// Returns the index of the item in the table, inserting it first if it
+    /// is not already in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table = Table::from_slice(&['A', 'B']);
+    /// assert_eq!(table.index_of_or_insert(&'B'), 1);
+    /// assert_eq!(table.index_of_or_insert(&'C'), 2);
+    /// assert_eq!(table.len(), 3);
+    /// ```
+    pub fn index_of_or_insert(&mut self, item: &T) -> Index {
+        self.insert_or_get(item)
     }
 
     /// Extend the table with the contents of an iterator.
@@ -129,16 +342,37 @@ where
     /// Extend the table with the contents of a slice.
     pub fn extend_from_slice(&mut self, items: &[T]) {
         for item in items {
-            self.insert_or_get(&item);
+            self.insert_or_get(item);
         }
     }
 
+    /// Inserts every item from `other` that is not already in this table,
+    /// returning a remap from each of `other`'s indices to the index of the
+    /// same item in this table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let mut table = Table::from_slice(&['A', 'B']);
+    /// let remap = table.merge(&Table::from_slice(&['C', 'A']));
+    /// assert_eq!(table.as_ref(), ['A', 'B', 'C']);
+    /// assert_eq!(remap, [2, 0]);
+    /// ```
+    pub fn merge<S2>(&mut self, other: &Table<T, S2>) -> Vec<Index>
+    where
+        S2: TableStore<T>,
+    {
+        (0..other.len())
+            .filter_map(|ind| other.item(ind))
+            .map(|item| self.insert_or_get(item))
+            .collect()
+    }
+
     /// Returns an iterator to run-length encode the items.
     ///
     /// Unlike [encode](Table<T>::encode), this method will not fail because
     /// it will add items to the table as they were found, resulting in a table
     /// that contains one of every item encountered in the encoded slice.
-    pub fn encode_mut<'a>(&'a mut self, items: &'a [T]) -> EncoderMut<T> {
+    pub fn encode_mut<'a>(&'a mut self, items: &'a [T]) -> EncoderMut<'a, T, S> {
         EncoderMut {
             table: self,
             items,
@@ -153,17 +387,165 @@ where
     ///
     /// If `items` contains any elements not found in the table, this method
     /// will return a [TableMissingItems](Error::TableMissingItems) error.
-    pub fn encode<'a>(&'a self, items: &'a [T]) -> Result<Encoder<T>, Error> {
-        // Fail if any of the items are not in the table
-        for i in 0..items.len() {
-            if let None = self.get_index(&items[i]) {
+    /// To do this, it checks every run before returning; use
+    /// [try_encode](Table::try_encode) to check them while encoding instead.
+    pub fn encode<'a>(&'a self, items: &'a [T]) -> Result<Encoder<'a, T, S>, Error> {
+        self.encode_with(items, Self::run_len)
+    }
+
+    /// Like [encode](Table::encode), but stores run lengths as `L`, splitting
+    /// any runs that are too long for it.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode](Table::encode).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let items = vec!['A'; 300];
+    /// let table = Table::from_slice(&['A']);
+    /// let runs: Vec<(usize, u8)> = table.encode_as(&items).unwrap().collect();
+    /// assert_eq!(runs, [(0, 255), (0, 45)]);
+    /// assert_eq!(table.decode_as(&runs).count(), 300);
+    /// ```
+    pub fn encode_as<'a, L: RunLen>(
+        &'a self,
+        items: &'a [T],
+    ) -> Result<Encoder<'a, T, S, L>, Error> {
+        self.encode_with(items, Self::run_len)
+    }
+
+    /// Like [encode](Table::encode), but yields [Run]s rather than tuples.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode](Table::encode).
+    ///
+    /// ```
+    /// # use rle::{Run, Table};
+    /// let str: Vec<char> = "AAABCC".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// let runs: Vec<Run> = table.encode_runs(&str).unwrap().collect();
+    /// assert_eq!(runs, [Run::new(0, 3), Run::new(1, 1), Run::new(2, 2)]);
+    /// assert_eq!(table.decode_as(&runs).collect::<String>(), "AAABCC");
+    /// ```
+    pub fn encode_runs<'a>(&'a self, items: &'a [T]) -> Result<RunEncoder<'a, T, S>, Error> {
+        Ok(self.encode(items)?.map(Run::from as fn(_) -> _))
+    }
+
+    /// Like [encode](Table::encode), but yields `(index, len, start)` runs,
+    /// where `start` is the position in `items` that the run begins at.
+    ///
+    /// This maps each run back to where it came from, such as for building
+    /// collision rectangles out of the runs in a row of tiles.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode](Table::encode).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let str: Vec<char> = "AAABCC".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// let runs: Vec<_> = table.encode_with_offsets(&str).unwrap().collect();
+    /// assert_eq!(runs, [(0, 3, 0), (1, 1, 3), (2, 2, 4)]);
+    /// ```
+    pub fn encode_with_offsets<'a>(
+        &'a self,
+        items: &'a [T],
+    ) -> Result<OffsetEncoder<'a, T, S>, Error> {
+        Ok(OffsetEncoder {
+            encoder: self.encode(items)?,
+            position: 0,
+        })
+    }
+
+    /// Returns an iterator to run-length encode the items, using this table
+    /// as a lookup. Unlike [encode](Table::encode), the items are only
+    /// scanned once, and each run is checked against the table as it is
+    /// yielded.
+    ///
+    /// If an item is not found in the table, a [TableMissingItems](Error::TableMissingItems)
+    /// error is yielded in place of its run, and iteration stops.
+    ///
+    /// ```
+    /// # use rle::{Error, Table};
+    /// let str: Vec<char> = "AAABXX".chars().collect();
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let mut runs = table.try_encode(&str);
+    /// assert_eq!(runs.next().unwrap().unwrap(), (0, 3));
+    /// assert_eq!(runs.next().unwrap().unwrap(), (1, 1));
+    /// assert!(matches!(runs.next(), Some(Err(Error::TableMissingItems(4)))));
+    /// assert!(runs.next().is_none());
+    ///
+    /// let runs: Result<Vec<_>, _> = table.try_encode(&str[..4]).collect();
+    /// assert_eq!(runs.unwrap(), [(0, 3), (1, 1)]);
+    /// ```
+    pub fn try_encode<'a>(&'a self, items: &'a [T]) -> TryEncoder<'a, T, S> {
+        TryEncoder {
+            table: self,
+            items,
+            index: 0,
+        }
+    }
+
+    /// Run-length encodes a sequence of borrowed items, such as a `&[&str]`
+    /// for a `Table<String>`, looking each run up in the table without
+    /// constructing an owned item.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&["INFO".to_string(), "WARN".to_string()]);
+    /// let levels = ["INFO", "INFO", "WARN", "INFO"];
+    /// let runs = table.encode_borrowed(&levels).unwrap();
+    /// assert_eq!(runs, [(0, 2), (1, 1), (0, 1)]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `items` contains any elements not found in the table, this method
+    /// will return a [TableMissingItems](Error::TableMissingItems) error.
+    pub fn encode_borrowed<Q>(&self, items: &[&Q]) -> Result<Vec<(Index, usize)>, Error>
+    where
+        Q: PartialEq + ?Sized,
+        S: StoreLookup<Q>,
+    {
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let ind = self
+                .store
+                .index_of_key(items[i])
+                .ok_or(Error::TableMissingItems(i))?;
+            let len = run_len(&items[i..]);
+            runs.push((ind, len));
+            i += len;
+        }
+        Ok(runs)
+    }
+
+    /// Creates an encoder that uses `scan` to find the length of each run.
+    pub(crate) fn encode_with<'a, L: RunLen>(
+        &'a self,
+        items: &'a [T],
+        scan: fn(&Self, &[T]) -> usize,
+    ) -> Result<Encoder<'a, T, S, L>, Error> {
+        // Fail if any of the items are not in the table, only checking
+        // the first item of each run
+        let mut i = 0;
+        while i < items.len() {
+            if self.get_index(&items[i]).is_none() {
                 return Err(Error::TableMissingItems(i));
             }
+            i += scan(self, &items[i..]);
         }
         Ok(Encoder {
             table: self,
             items,
             index: 0,
+            scan,
+            run: None,
+            len: PhantomData,
         })
     }
 
@@ -218,15 +600,16 @@ where
     ///
     /// If the provided table contains >= 128 items, this will return a
     /// [TableTooLarge](Error::TableTooLarge) error.
-    pub fn encode_bytes<'a>(&'a self, items: &'a [T]) -> Result<BytesEncoder<T>, Error> {
-        if self.items.len() < 128 {
+    pub fn encode_bytes<'a>(&'a self, items: &'a [T]) -> Result<BytesEncoder<'a, T, S>, Error> {
+        if self.len() < 128 {
             Ok(BytesEncoder {
                 rle: self.encode(items)?,
                 run: None,
                 len: None,
+                metrics: None,
             })
         } else {
-            Err(Error::TableTooLarge(self.items.len()))
+            Err(Error::TableTooLarge(self.len()))
         }
     }
 
@@ -235,21 +618,94 @@ where
     /// Unlike [encode_bytes](Table<T>::encode_bytes), this method will add items
     /// to the table as they were found, resulting in a table that contains one of
     /// every item encountered in the encoded slice.
-    pub fn encode_bytes_mut<'a>(&'a mut self, items: &'a [T]) -> Result<BytesEncoderMut<T>, Error> {
-        if self.items.len() < 128 {
+    pub fn encode_bytes_mut<'a>(
+        &'a mut self,
+        items: &'a [T],
+    ) -> Result<BytesEncoderMut<'a, T, S>, Error> {
+        if self.len() < 128 {
             Ok(BytesEncoderMut {
                 rle: self.encode_mut(items),
                 run: None,
                 len: None,
             })
         } else {
-            Err(Error::TableTooLarge(self.items.len()))
+            Err(Error::TableTooLarge(self.len()))
         }
     }
 
+    /// Run-length encodes the items as a sequence of bytes, stopping early
+    /// if the output would grow larger than `max_bytes`.
+    ///
+    /// Returns the encoded bytes along with the number of items they contain.
+    /// If that number is less than `items.len()`, the budget ran out, and the
+    /// remaining items can be encoded separately starting from that position.
+    /// Runs are split if needed to fill as much of the budget as possible.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table<T>::encode_bytes).
+    pub fn encode_bytes_bounded(
+        &self,
+        items: &[T],
+        max_bytes: usize,
+    ) -> Result<(Vec<u8>, usize), Error> {
+        let mut bytes = Vec::new();
+        let mut consumed = 0;
+        for (ind, mut len) in self.encode_bytes(items)?.rle {
+            let ind = ind as u8;
+            while len > 0 {
+                let num = len.min(MAX_RUN);
+                let remaining = max_bytes - bytes.len();
+                if num > 1 && remaining >= 2 {
+                    bytes.push((ind << 1) | 1);
+                    bytes.push(num as u8);
+                } else if remaining >= 1 {
+                    bytes.push(ind << 1);
+                    consumed += 1;
+                    len -= 1;
+                    continue;
+                } else {
+                    return Ok((bytes, consumed));
+                }
+                consumed += num;
+                len -= num;
+            }
+        }
+        Ok((bytes, consumed))
+    }
+
+    /// Run-length encodes the items as a sequence of bytes (see [encode_bytes](Table<T>::encode_bytes))
+    /// and writes them into `writer` in blocks, returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing, or an [InvalidInput](io::ErrorKind::InvalidInput)
+    /// error wrapping the [Error] if the items could not be encoded.
+    pub fn encode_bytes_to<W: io::Write>(&self, items: &[T], mut writer: W) -> io::Result<usize> {
+        let encoder = self
+            .encode_bytes(items)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let mut buf = Vec::with_capacity(4096);
+        let mut written = 0;
+        for byte in encoder {
+            buf.push(byte);
+            if buf.len() == buf.capacity() {
+                writer.write_all(&buf)?;
+                written += buf.len();
+                buf.clear();
+            }
+        }
+        writer.write_all(&buf)?;
+        Ok(written + buf.len())
+    }
+
+    /// Run-length encodes the items as a string of runs, where each run is
+    /// written as its index and length in hexadecimal followed by a comma
+    /// (`IND:LEN,`). The string can be decoded with [decode_hex_str](Table::decode_hex_str).
     pub fn encode_hex_str<'a>(&'a self, items: &'a [T]) -> Result<String, Error> {
         let mut str = String::new();
-        for (ind, len) in self.encode(items)? {
+        for run in self.try_encode(items) {
+            let (ind, len) = run?;
             write!(str, "{:X}:{:X},", ind, len).unwrap();
         }
         Ok(str)
@@ -257,39 +713,240 @@ where
 
     /// Return an iterator that decodes the series of runs using this table
     /// as the index lookup for the elements.
-    pub fn decode<'a>(&'a self, runs: &'a [(Index, usize)]) -> Decoder<T> {
-        Decoder {
+    ///
+    /// Runs of zero length decode to nothing, and decoding stops at the first
+    /// run whose index isn't in the table, even if the run is empty. The runs
+    /// are checked when the iterator is created, so decoding from the back
+    /// stops at the same place, and the iterator's length is exact.
+    pub fn decode<'a>(&'a self, runs: &'a [(Index, usize)]) -> Decoder<'a, T, S> {
+        self.decode_as(runs)
+    }
+
+    /// Like [decode](Table::decode), but for runs whose lengths are stored
+    /// as another type (see [encode_as](Table::encode_as)), or runs stored
+    /// as [Run]s rather than tuples.
+    pub fn decode_as<'a, R: IntoRun>(&'a self, runs: &'a [R]) -> Decoder<'a, T, S, R> {
+        Decoder::new(self, runs)
+    }
+
+    /// Return an iterator that decodes the runs into each run's item and
+    /// length, rather than repeating each item like [decode_as](Table::decode_as).
+    /// Runs of zero length are skipped, and decoding stops at the first
+    /// index that isn't in the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let runs: Vec<_> = table.decode_runs(&[(0, 5usize), (1, 0), (1, 3)]).collect();
+    /// assert_eq!(runs, [(&'A', 5), (&'B', 3)]);
+    /// ```
+    pub fn decode_runs<'a, R: IntoRun>(&'a self, runs: &'a [R]) -> RunDecoder<'a, T, S, R> {
+        RunDecoder { table: self, runs }
+    }
+
+    /// Returns an adapter that formats the runs as each run's length followed
+    /// by its item, such as `5A 10B 3C 10A`. See [DisplayRuns] for how to
+    /// change the separators.
+    pub fn display_runs<'a, R: IntoRun>(&'a self, runs: &'a [R]) -> DisplayRuns<'a, T, R, S> {
+        DisplayRuns::new(self, runs)
+    }
+
+    /// Return an iterator that decodes the run-length encoded bytes into
+    /// each run's item and length, like [decode_runs](Table::decode_runs).
+    /// Decoding stops at the first malformed run or index that isn't in
+    /// the table.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// let bytes = [0x01, 0x05, 0x03, 0x0A, 0x04];
+    /// let runs: Vec<_> = table.decode_bytes_runs(&bytes).collect();
+    /// assert_eq!(runs, [(&'A', 5), (&'B', 10), (&'C', 1)]);
+    /// ```
+    pub fn decode_bytes_runs<'a>(&'a self, bytes: &'a [u8]) -> BytesRunDecoder<'a, T, S> {
+        BytesRunDecoder {
             table: self,
-            runs,
-            run: None,
+            bytes,
+            offset: 0,
         }
     }
 
     /// Return an iterator that decodes the run-length encoded bytes using
     /// this table as the index lookup for the elements.
-    pub fn decode_bytes<'a>(&'a self, bytes: &'a [u8]) -> BytesDecoder<T> {
+    pub fn decode_bytes<'a>(&'a self, bytes: &'a [u8]) -> BytesDecoder<'a, T, S> {
         BytesDecoder {
             table: self,
             bytes,
             run: None,
+            offset: 0,
+            position: 0,
+            metrics: None,
+            back: VecDeque::new(),
         }
     }
 
-    pub fn iter(&self) -> TableIter<T> {
-        TableIter { items: &self.items }
+    /// Return an iterator that decodes the run-length encoded bytes using
+    /// this table as the index lookup for the elements, yielding each element
+    /// along with its position in the sequence.
+    ///
+    /// Skipping elements with [nth](Iterator::nth) or [skip](Iterator::skip)
+    /// skips over whole runs at a time without decoding them.
+    pub fn decode_enumerated<'a>(&'a self, bytes: &'a [u8]) -> EnumeratedDecoder<'a, T, S> {
+        EnumeratedDecoder {
+            decoder: self.decode_bytes(bytes),
+        }
     }
 
-    pub fn iter_sorted(&self) -> SortedTableIter<T> {
-        SortedTableIter {
-            items: &self.items,
-            sorted: &self.sorted,
+    /// Return an iterator that continues decoding the run-length encoded bytes
+    /// from a previously saved [state](crate::BytesDecoder::state).
+    ///
+    /// The `bytes` should be the full sequence that was originally being decoded,
+    /// not just the part of it that remains.
+    pub fn resume_bytes<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        state: DecoderState,
+    ) -> BytesDecoder<'a, T, S> {
+        BytesDecoder {
+            table: self,
+            bytes: bytes.get(state.byte_offset..).unwrap_or_default(),
+            run: state.run,
+            offset: state.byte_offset,
+            position: state.element_offset,
+            metrics: None,
+            back: VecDeque::new(),
+        }
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone + TableItem,
+    S: TableStore<T>,
+{
+    /// Returns an iterator to run-length encode the items as a self-describing
+    /// sequence of bytes.
+    ///
+    /// The format is the same as [encode_bytes_mut](Table<T>::encode_bytes_mut),
+    /// except that the first time a new item appears, its bytes (see [TableItem])
+    /// are written directly after its run. This means the sequence can be decoded
+    /// with [decode_bytes_mut](Table<T>::decode_bytes_mut) by a receiver that
+    /// doesn't have a copy of the table, as long as it starts with the same
+    /// table this one started with (usually an empty one).
+    pub fn encode_bytes_inline<'a>(
+        &'a mut self,
+        items: &'a [T],
+    ) -> Result<InlineBytesEncoder<'a, T, S>, Error> {
+        if self.len() < 128 {
+            Ok(InlineBytesEncoder {
+                rle: self.encode_mut(items),
+                run: None,
+                packet: Vec::new(),
+                pos: 0,
+            })
+        } else {
+            Err(Error::TableTooLarge(self.len()))
+        }
+    }
+
+    /// Return an iterator that decodes a sequence of bytes produced by
+    /// [encode_bytes_inline](Table<T>::encode_bytes_inline), adding the items
+    /// embedded in the sequence to this table as they are found.
+    ///
+    /// If the bytes are malformed, the iterator stops at the first invalid run.
+    pub fn decode_bytes_mut<'a>(&'a mut self, bytes: &'a [u8]) -> BytesDecoderMut<'a, T, S> {
+        BytesDecoderMut {
+            table: self,
+            bytes,
+            run: None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Table<T>
+where
+    T: Ord + Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Serializes the table as a pretty-printed JSON array of its items.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a table from a JSON array of items, such as one
+    /// produced by [to_json](Table::to_json).
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Serialize for Table<T>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.store.items.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for Table<T>
+where
+    T: Ord + Clone + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = Vec::<T>::deserialize(deserializer)?;
+        let mut table = Self::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            if table.insert_or_get(item) != i {
+                return Err(de::Error::custom(format!(
+                    "duplicate item at [{}] in table",
+                    i
+                )));
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Tables are equal if they contain the same items in the same order, so
+/// that they encode and decode identically, even if they use different stores.
+impl<T, S, S2> PartialEq<Table<T, S2>> for Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+    S2: TableStore<T>,
+{
+    fn eq(&self, other: &Table<T, S2>) -> bool {
+        self.len() == other.len() && (0..self.len()).all(|i| self.item(i) == other.item(i))
+    }
+}
+
+impl<T, S> Eq for Table<T, S>
+where
+    T: Eq + Clone,
+    S: TableStore<T>,
+{
+}
+
+/// Hashes the number of items, followed by each item in insertion order.
+impl<T, S> Hash for Table<T, S>
+where
+    T: Hash + PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in (0..self.len()).filter_map(|i| self.item(i)) {
+            item.hash(state);
         }
     }
 }
 
 impl<T> AsRef<[T]> for Table<T> {
     fn as_ref(&self) -> &[T] {
-        &self.items
+        &self.store.items
     }
 }
 
@@ -297,7 +954,64 @@ impl<T> Deref for Table<T> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
-        &self.items
+        &self.store.items
+    }
+}
+
+impl<T, S> FromIterator<T> for Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut table = Self::with_store(S::default());
+        Extend::extend(&mut table, iter);
+        table
+    }
+}
+
+impl<T, S> Extend<T> for Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert_or_get(&item);
+        }
+    }
+}
+
+impl<'a, T, S> Extend<&'a T> for Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert_or_get(item);
+        }
+    }
+}
+
+impl<T> IntoIterator for Table<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the table, returning its items in index order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.store.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Table<T> {
+    type Item = &'a T;
+    type IntoIter = TableIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        TableIter {
+            items: &self.store.items,
+        }
     }
 }
 
@@ -309,10 +1023,9 @@ impl<'a, T> Iterator for TableIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.items.get(0).and_then(|item| {
-            self.items = &self.items[1..];
-            Some(item)
-        })
+        let (item, rest) = self.items.split_first()?;
+        self.items = rest;
+        Some(item)
     }
 }
 
@@ -325,9 +1038,9 @@ impl<'a, T> Iterator for SortedTableIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.sorted.get(0).and_then(|&i| {
+        self.sorted.first().map(|&i| {
             self.sorted = &self.sorted[1..];
-            Some(&self.items[i])
+            &self.items[i]
         })
     }
 }
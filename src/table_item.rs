@@ -0,0 +1,75 @@
+/// A type that can be converted to and from bytes, so that items can be
+/// stored directly inside an encoded byte sequence alongside the runs.
+///
+/// Implementations are provided for the primitive integer types, `bool`,
+/// `char` and `String`. Multi-byte values are always stored little-endian.
+pub trait TableItem: Sized {
+    /// Appends the byte representation of this item to `bytes`.
+    fn write_bytes(&self, bytes: &mut Vec<u8>);
+
+    /// Reads an item from the front of `bytes`, returning the item and the
+    /// number of bytes that were read, or `None` if the bytes are invalid or
+    /// there aren't enough of them.
+    fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)>;
+}
+
+macro_rules! impl_table_item_int {
+    ($($t:ty),*) => {
+        $(
+            impl TableItem for $t {
+                fn write_bytes(&self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)> {
+                    const SIZE: usize = std::mem::size_of::<$t>();
+                    let mut buf = [0; SIZE];
+                    buf.copy_from_slice(bytes.get(..SIZE)?);
+                    Some((<$t>::from_le_bytes(buf), SIZE))
+                }
+            }
+        )*
+    };
+}
+
+impl_table_item_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl TableItem for bool {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        bytes.push(*self as u8);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)> {
+        match bytes.first()? {
+            0 => Some((false, 1)),
+            1 => Some((true, 1)),
+            _ => None,
+        }
+    }
+}
+
+impl TableItem for char {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        (*self as u32).write_bytes(bytes);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)> {
+        let (code, size) = u32::read_bytes(bytes)?;
+        std::char::from_u32(code).map(|chr| (chr, size))
+    }
+}
+
+/// Strings are stored as a `u32` byte length followed by their UTF-8 bytes.
+impl TableItem for String {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        (self.len() as u32).write_bytes(bytes);
+        bytes.extend_from_slice(self.as_bytes());
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)> {
+        let (len, size) = u32::read_bytes(bytes)?;
+        let str = bytes.get(size..size + len as usize)?;
+        let str = String::from_utf8(str.to_vec()).ok()?;
+        Some((str, size + len as usize))
+    }
+}
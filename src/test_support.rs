@@ -0,0 +1,154 @@
+//! Helpers for testing code that uses this crate, enabled with the
+//! `test-support` feature.
+//!
+//! ```
+//! use rle::test_support::{assert_roundtrip, Generator, RunLengths};
+//!
+//! let mut gen = Generator::new(1234);
+//! let (table, items) = gen.pair(16, 1000, RunLengths::Uniform(1, 20));
+//! assert_roundtrip(&table, &items);
+//! ```
+
+use crate::Table;
+use std::fmt::Debug;
+
+/// How the lengths of generated runs are distributed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunLengths {
+    /// Every run has the same length.
+    Fixed(usize),
+
+    /// Run lengths are picked uniformly between the two values (inclusive).
+    Uniform(usize, usize),
+
+    /// Each run continues with the given probability, so short runs are
+    /// common and long runs are rare.
+    Geometric(f64),
+}
+
+/// A small, seeded pseudo-random generator of tables and sequences.
+///
+/// The same seed always produces the same values, so failing tests can
+/// be reproduced.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    /// Constructs a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        // splitmix64
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in the range `0..max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0.
+    pub fn below(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+
+    /// Returns a random run length from the distribution.
+    pub fn run_length(&mut self, runs: RunLengths) -> usize {
+        match runs {
+            RunLengths::Fixed(len) => len.max(1),
+            RunLengths::Uniform(min, max) => {
+                let min = min.max(1);
+                min + self.below(max.max(min) - min + 1)
+            }
+            RunLengths::Geometric(p) => {
+                let mut len = 1;
+                while (self.next_u64() as f64 / u64::MAX as f64) < p {
+                    len += 1;
+                }
+                len
+            }
+        }
+    }
+
+    /// Generates a table of `symbols` unique values in a random order.
+    pub fn table(&mut self, symbols: usize) -> Table<u32> {
+        let mut values: Vec<u32> = (0..symbols as u32).collect();
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i + 1));
+        }
+        Table::from_slice(&values)
+    }
+
+    /// Generates a sequence of `len` items from the table, made of runs with
+    /// lengths picked from the distribution. Neighbouring runs may have the
+    /// same item if the table has only one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is empty and `len` is not 0.
+    pub fn sequence<T: Ord + Clone>(
+        &mut self,
+        table: &Table<T>,
+        len: usize,
+        runs: RunLengths,
+    ) -> Vec<T> {
+        let mut items = Vec::with_capacity(len);
+        let mut prev = None;
+        while items.len() < len {
+            let mut ind = self.below(table.len());
+            if table.len() > 1 && prev == Some(ind) {
+                ind = (ind + 1 + self.below(table.len() - 1)) % table.len();
+            }
+            prev = Some(ind);
+            let run = self.run_length(runs).min(len - items.len());
+            items.extend(std::iter::repeat_n(table[ind].clone(), run));
+        }
+        items
+    }
+
+    /// Generates a table with `symbols` items and a sequence of `len` items
+    /// from it. See [table](Generator::table) and [sequence](Generator::sequence).
+    pub fn pair(&mut self, symbols: usize, len: usize, runs: RunLengths) -> (Table<u32>, Vec<u32>) {
+        let table = self.table(symbols);
+        let items = self.sequence(&table, len, runs);
+        (table, items)
+    }
+}
+
+/// Asserts that the items survive being encoded and decoded with the table,
+/// both as runs and (if the table is small enough) as bytes.
+///
+/// # Panics
+///
+/// Panics if any of the items are missing from the table, or if decoding
+/// does not produce the original items.
+pub fn assert_roundtrip<T>(table: &Table<T>, items: &[T])
+where
+    T: Ord + Clone + Debug,
+{
+    let runs: Vec<_> = table
+        .encode(items)
+        .expect("failed to encode runs")
+        .collect();
+    let decoded: Vec<T> = table.decode(&runs).cloned().collect();
+    assert_eq!(decoded, items, "runs did not round-trip");
+
+    if table.len() < 128 {
+        let bytes: Vec<u8> = table
+            .encode_bytes(items)
+            .expect("failed to encode bytes")
+            .collect();
+        let decoded: Vec<T> = table.decode_bytes(&bytes).cloned().collect();
+        assert_eq!(decoded, items, "bytes did not round-trip");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::byte_scan::run_len;
+use crate::Error;
+
+/// Encodes pixels into Truevision TGA run-length packets, as used by image
+/// types 9, 10 and 11.
+///
+/// The pixels are rows of `width` pixels, each `bytes_per_pixel` bytes long,
+/// and every row is packed separately so that no packet crosses from one row
+/// into the next (as the TGA 2.0 specification requires).
+///
+/// # Format
+///
+/// Each packet starts with a header byte, where the lower 7 bits are the
+/// number of pixels in the packet minus one:
+///
+/// - If the high bit is set, it is a run-length packet, followed by a
+///   single pixel that is repeated for the whole packet.
+/// - Otherwise, it is a raw packet, followed by every pixel in the packet.
+///
+/// ```
+/// # use rle::{decode_tga, encode_tga};
+/// let pixels = [1, 2, 1, 2, 1, 2, 3, 4, 5, 6];
+/// let packed = encode_tga(&pixels, 2, 5);
+/// assert_eq!(packed, [0x82, 1, 2, 0x01, 3, 4, 5, 6]);
+/// assert_eq!(decode_tga(&packed, 2, 5).unwrap(), pixels);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `bytes_per_pixel` is not between 1 and 4, or if the pixels
+/// cannot be split evenly into rows of `width` pixels.
+pub fn encode_tga(pixels: &[u8], bytes_per_pixel: usize, width: usize) -> Vec<u8> {
+    assert!(
+        (1..=4).contains(&bytes_per_pixel),
+        "TGA pixels must be 1 to 4 bytes"
+    );
+    let row_len = width * bytes_per_pixel;
+    assert!(
+        width > 0 && pixels.len().is_multiple_of(row_len),
+        "pixels must be whole rows"
+    );
+
+    let mut packed = Vec::with_capacity(pixels.len() + pixels.len() / 64);
+    for row in pixels.chunks(row_len) {
+        let row: Vec<&[u8]> = row.chunks(bytes_per_pixel).collect();
+        let mut raw = 0;
+        let mut i = 0;
+        while i < row.len() {
+            let len = run_len(&row[i..]);
+            if len < 2 {
+                i += 1;
+                continue;
+            }
+            push_raw(&mut packed, &row[raw..i]);
+            for num in chunk_lens(len) {
+                if num == 1 {
+                    push_raw(&mut packed, &row[i..=i]);
+                } else {
+                    packed.push(0x80 | (num - 1) as u8);
+                    packed.extend_from_slice(row[i]);
+                }
+                i += num;
+            }
+            raw = i;
+        }
+        push_raw(&mut packed, &row[raw..]);
+    }
+    packed
+}
+
+/// Splits a number of pixels into packets of up to 128 pixels.
+fn chunk_lens(mut len: usize) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let num = len.min(128);
+        len -= num;
+        (num > 0).then_some(num)
+    })
+}
+
+fn push_raw(packed: &mut Vec<u8>, pixels: &[&[u8]]) {
+    for chunk in pixels.chunks(128) {
+        packed.push((chunk.len() - 1) as u8);
+        for pixel in chunk {
+            packed.extend_from_slice(pixel);
+        }
+    }
+}
+
+/// Decodes TGA run-length packets (see [encode_tga]) until `pixel_count`
+/// pixels have been read, returning the pixel bytes. Any bytes after the
+/// last packet (such as the TGA footer) are ignored.
+///
+/// # Errors
+///
+/// Returns a [TruncatedStream](Error::TruncatedStream) error if the bytes
+/// end before all of the pixels were read, or an [InvalidBytes](Error::InvalidBytes)
+/// error with the offset of the packet that goes past the last pixel (or 0
+/// if `pixel_count` is too large for its bytes to be counted at all).
+///
+/// # Panics
+///
+/// Panics if `bytes_per_pixel` is not between 1 and 4.
+pub fn decode_tga(
+    packed: &[u8],
+    bytes_per_pixel: usize,
+    pixel_count: usize,
+) -> Result<Vec<u8>, Error> {
+    assert!(
+        (1..=4).contains(&bytes_per_pixel),
+        "TGA pixels must be 1 to 4 bytes"
+    );
+    let size = pixel_count
+        .checked_mul(bytes_per_pixel)
+        .ok_or(Error::InvalidBytes(0))?;
+
+    // The pixel count may come from an untrusted header, so the output grows
+    // with the packets rather than being allocated up front
+    let mut pixels = Vec::new();
+    let mut offset = 0;
+    while pixels.len() < size {
+        let header = *packed.get(offset).ok_or(Error::TruncatedStream)?;
+        let num = (header & 0x7F) as usize + 1;
+        if pixels.len() + num * bytes_per_pixel > size {
+            return Err(Error::InvalidBytes(offset));
+        }
+        let payload = if header & 0x80 != 0 { 1 } else { num } * bytes_per_pixel;
+        let data = packed
+            .get(offset + 1..offset + 1 + payload)
+            .ok_or(Error::TruncatedStream)?;
+        if header & 0x80 != 0 {
+            for _ in 0..num {
+                pixels.extend_from_slice(data);
+            }
+        } else {
+            pixels.extend_from_slice(data);
+        }
+        offset += 1 + payload;
+    }
+    Ok(pixels)
+}
@@ -0,0 +1,109 @@
+use crate::TableItem;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result};
+use std::hash::{Hash, Hasher};
+
+macro_rules! total_float {
+    ($name:ident, $float:ty, $bits:ty, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// Floats are compared with their `total_cmp` method, so the wrapper is
+        /// [Ord] and [Eq] and can be stored in a [Table](crate::Table). This
+        /// means two floats are only the same item if they have the same bits:
+        /// `-0.0` and `0.0` are different items, while a NaN is equal to
+        /// itself (but not to NaNs with a different sign or payload). Negative
+        /// NaNs sort before every other value, and positive NaNs after.
+        ///
+        /// A slice of floats can be borrowed as a slice of wrappers with
+        #[doc = concat!("[from_slice](", stringify!($name), "::from_slice), without copying it.")]
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(transparent)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        pub struct $name(pub $float);
+
+        impl $name {
+            /// Borrows a slice of floats as a slice of wrappers.
+            pub fn from_slice(floats: &[$float]) -> &[Self] {
+                // SAFETY: the wrapper is `repr(transparent)`, so it has the
+                // same layout as the float it wraps.
+                unsafe { &*(floats as *const [$float] as *const [Self]) }
+            }
+
+            /// Borrows a slice of wrappers as a slice of floats.
+            pub fn as_floats(items: &[Self]) -> &[$float] {
+                // SAFETY: see `from_slice`.
+                unsafe { &*(items as *const [Self] as *const [$float]) }
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl From<$float> for $name {
+            fn from(float: $float) -> Self {
+                Self(float)
+            }
+        }
+
+        impl From<$name> for $float {
+            fn from(float: $name) -> Self {
+                float.0
+            }
+        }
+
+        /// Stored as the little-endian bytes of the float's bits.
+        impl TableItem for $name {
+            fn write_bytes(&self, bytes: &mut Vec<u8>) {
+                self.0.to_bits().write_bytes(bytes);
+            }
+
+            fn read_bytes(bytes: &[u8]) -> Option<(Self, usize)> {
+                let (bits, size) = <$bits>::read_bytes(bytes)?;
+                Some((Self(<$float>::from_bits(bits)), size))
+            }
+        }
+    };
+}
+
+total_float!(
+    TotalF32,
+    f32,
+    u32,
+    "An `f32` that is totally ordered, so that it can be run-length encoded."
+);
+total_float!(
+    TotalF64,
+    f64,
+    u64,
+    "An `f64` that is totally ordered, so that it can be run-length encoded."
+);
@@ -0,0 +1,106 @@
+use crate::Table;
+use std::ops::{Deref, DerefMut};
+
+/// A guard that undoes any items added to a table unless it is committed,
+/// so that an encode that fails or is abandoned partway (such as with
+/// [encode_bytes_mut](Table::encode_bytes_mut)) doesn't leave a shared table
+/// with items that nothing was encoded with. See [transaction](Table::transaction).
+///
+/// It derefs to the table, so items can be added through it with any of the
+/// table's methods. Dropping it without calling [commit](TableTransaction::commit)
+/// removes every item added since it was created. Only added items are undone,
+/// so methods that remove or move existing items (such as [remove](Table::remove))
+/// should not be used through it.
+///
+/// ```
+/// # use rle::{Error, Table};
+/// let mut table = Table::from_slice(&['A', 'B']);
+/// let str: Vec<char> = "AACDDB".chars().collect();
+///
+/// // Give up if the output would be more than 4 bytes
+/// let mut tx = table.transaction();
+/// let bytes: Result<Vec<u8>, Error> = tx.encode_bytes_mut(&str).unwrap().take(4).collect();
+/// assert_eq!(tx.added(), ['C', 'D']);
+/// drop(tx);
+/// assert_eq!(table.as_ref(), ['A', 'B']);
+///
+/// let mut tx = table.transaction();
+/// let bytes: Result<Vec<u8>, Error> = tx.encode_bytes_mut(&str).unwrap().collect();
+/// assert_eq!(bytes.unwrap().len(), 6);
+/// tx.commit();
+/// assert_eq!(table.as_ref(), ['A', 'B', 'C', 'D']);
+/// ```
+#[derive(Debug)]
+pub struct TableTransaction<'a, T>
+where
+    T: Ord + Clone,
+{
+    table: &'a mut Table<T>,
+    len: usize,
+    committed: bool,
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone,
+{
+    /// Starts a transaction, which removes any items added through it unless
+    /// it is committed. See [TableTransaction].
+    pub fn transaction(&mut self) -> TableTransaction<'_, T> {
+        TableTransaction {
+            len: self.len(),
+            table: self,
+            committed: false,
+        }
+    }
+}
+
+impl<'a, T> TableTransaction<'a, T>
+where
+    T: Ord + Clone,
+{
+    /// The items added to the table since the transaction started.
+    pub fn added(&self) -> &[T] {
+        &self.table.as_ref()[self.len.min(self.table.len())..]
+    }
+
+    /// Keeps the items added during the transaction.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+
+    /// Removes the items added during the transaction. This is the same as
+    /// dropping it.
+    pub fn rollback(self) {}
+}
+
+impl<'a, T> Deref for TableTransaction<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Target = Table<T>;
+
+    fn deref(&self) -> &Table<T> {
+        self.table
+    }
+}
+
+impl<'a, T> DerefMut for TableTransaction<'a, T>
+where
+    T: Ord + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Table<T> {
+        self.table
+    }
+}
+
+impl<'a, T> Drop for TableTransaction<'a, T>
+where
+    T: Ord + Clone,
+{
+    fn drop(&mut self) {
+        if !self.committed {
+            self.table.truncate(self.len);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::bulk::read_run;
+use crate::{Error, Table, TableStore};
+
+/// A summary of a valid run-length encoded byte sequence.
+/// See [validate_bytes](crate::Table::validate_bytes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DecodeSummary {
+    /// The number of items the bytes decode to.
+    pub items: usize,
+
+    /// The number of runs in the bytes.
+    pub runs: usize,
+
+    /// The length of the longest run.
+    pub longest_run: usize,
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Checks that the bytes (see [decode_bytes](Table::decode_bytes)) can be
+    /// decoded with this table, and summarizes what they decode to, without
+    /// decoding any items. This is useful for checking bytes from an untrusted
+    /// source before allocating space for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if a run refers
+    /// to an index not in the table, or [TruncatedStream](Error::TruncatedStream)
+    /// if the bytes end in the middle of a run.
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B', 'C']);
+    /// let summary = table.validate_bytes(&[0x01, 0x05, 0x03, 0x0A, 0x04]).unwrap();
+    /// assert_eq!(summary.items, 16);
+    /// assert_eq!(summary.runs, 3);
+    /// assert_eq!(summary.longest_run, 10);
+    ///
+    /// assert!(table.validate_bytes(&[0x01, 0x05, 0x06]).is_err());
+    /// assert!(table.validate_bytes(&[0x01, 0x05, 0x03]).is_err());
+    /// ```
+    pub fn validate_bytes(&self, bytes: &[u8]) -> Result<DecodeSummary, Error> {
+        let mut summary = DecodeSummary::default();
+        let mut offset = 0;
+        while let Some((ind, len, size)) = read_run(bytes, offset)? {
+            if ind >= self.len() {
+                return Err(Error::UnknownIndex(ind));
+            }
+            summary.items += len;
+            summary.runs += 1;
+            summary.longest_run = summary.longest_run.max(len);
+            offset += size;
+        }
+        Ok(summary)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Error, Table, TableStore};
+use std::iter::repeat_n;
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Run-length encodes the items as a sequence of bytes like
+    /// [encode_bytes](Table::encode_bytes), but with run lengths stored as
+    /// LEB128 varints so that long runs are never split.
+    ///
+    /// # Format
+    ///
+    /// Each run starts with the same header byte as [encode_bytes](Table::encode_bytes).
+    /// If the length flag is set, the length follows in 7-bit groups, lowest
+    /// first, with the high bit of each byte set if another byte follows. Runs
+    /// up to 127 items long are stored exactly as they are in the plain format.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [encode_bytes](Table::encode_bytes).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let items = vec![0u8; 100_000];
+    /// let table = Table::from_slice(&[0]);
+    /// let bytes = table.encode_bytes_varint(&items).unwrap();
+    /// assert_eq!(bytes, [0x01, 0xA0, 0x8D, 0x06]);
+    /// assert_eq!(table.decode_bytes_varint(&bytes).unwrap(), items);
+    /// ```
+    pub fn encode_bytes_varint(&self, items: &[T]) -> Result<Vec<u8>, Error> {
+        if self.len() >= 128 {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        let mut bytes = Vec::new();
+        let mut i = 0;
+        while i < items.len() {
+            let ind = self
+                .get_index(&items[i])
+                .ok_or(Error::TableMissingItems(i))? as u8;
+            let len = self.run_len(&items[i..]);
+            i += len;
+            if len == 1 {
+                bytes.push(ind << 1);
+            } else {
+                bytes.push((ind << 1) | 1);
+                push_varint(&mut bytes, len);
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes bytes written by [encode_bytes_varint](Table::encode_bytes_varint).
+    ///
+    /// Since lengths of up to 127 are stored the same way in both formats,
+    /// this also decodes bytes written by [encode_bytes](Table::encode_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [UnknownIndex](Error::UnknownIndex) error if a run refers
+    /// to an index not in the table, [TruncatedStream](Error::TruncatedStream)
+    /// if the bytes end in the middle of a run, or [InvalidBytes](Error::InvalidBytes)
+    /// if a length is too large to fit in a `usize` or to decode.
+    pub fn decode_bytes_varint(&self, bytes: &[u8]) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        for run in self.decode_bytes_varint_runs(bytes) {
+            let (offset, item, len) = run?;
+            // The length is untrusted, so reserving for it may fail
+            if items.len().checked_add(len).is_none() || items.try_reserve(len).is_err() {
+                return Err(Error::InvalidBytes(offset));
+            }
+            items.extend(repeat_n(item.clone(), len));
+        }
+        Ok(items)
+    }
+
+    /// Returns an iterator over the runs of bytes written by [encode_bytes_varint](Table::encode_bytes_varint),
+    /// yielding the offset, item and length of each run without decoding it,
+    /// so that long runs can be handled without allocating for them. Iteration
+    /// ends after the first error, which is one of those returned by [decode_bytes_varint](Table::decode_bytes_varint).
+    ///
+    /// ```
+    /// # use rle::Table;
+    /// let table = Table::from_slice(&['A', 'B']);
+    /// let runs: Vec<_> = table
+    ///     .decode_bytes_varint_runs(&[0x01, 0xA0, 0x8D, 0x06, 0x02])
+    ///     .map(Result::unwrap)
+    ///     .collect();
+    /// assert_eq!(runs, [(0, &'A', 100_000), (4, &'B', 1)]);
+    /// ```
+    pub fn decode_bytes_varint_runs<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = Result<(usize, &'a T, usize), Error>> + 'a {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            let start = offset;
+            let &header = bytes.get(start)?;
+            let run = if (header & 1) == 1 {
+                read_varint(&bytes[start + 1..])
+                    .map(|(len, size)| (len.max(1), 1 + size))
+                    .map_err(|err| err.unwrap_or(Error::InvalidBytes(start)))
+            } else {
+                Ok((1, 1))
+            }
+            .and_then(|(len, size)| {
+                let ind = (header >> 1) as usize;
+                let item = self.item(ind).ok_or(Error::UnknownIndex(ind))?;
+                Ok((start, item, len, size))
+            });
+            Some(match run {
+                Ok((start, item, len, size)) => {
+                    offset += size;
+                    Ok((start, item, len))
+                }
+                Err(err) => {
+                    offset = bytes.len();
+                    Err(err)
+                }
+            })
+        })
+    }
+}
+
+/// Appends `value` as a LEB128 varint.
+pub(crate) fn push_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a LEB128 varint from the front of `bytes`, returning its value
+/// and size. Fails with a [TruncatedStream](Error::TruncatedStream) error if
+/// the bytes end early, or `None` if the value overflows a `usize`.
+pub(crate) fn read_varint(bytes: &[u8]) -> Result<(usize, usize), Option<Error>> {
+    let mut value = 0usize;
+    for (i, &byte) in bytes.iter().enumerate() {
+        let shift = i * 7;
+        let bits = (byte & 0x7F) as usize;
+        if shift >= usize::BITS as usize || (bits << shift) >> shift != bits {
+            return Err(None);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(Some(Error::TruncatedStream))
+}
@@ -0,0 +1,59 @@
+use crate::bulk::read_run;
+use crate::Table;
+
+/// Returned by the callback of [decode_bytes_visit](crate::Table::decode_bytes_visit)
+/// to decide whether decoding should continue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Continue on to the next run.
+    Continue,
+
+    /// Stop decoding.
+    Break,
+}
+
+impl<T> Table<T>
+where
+    T: Ord + Clone,
+{
+    /// Decodes the run-length encoded bytes by calling `visit` once for every
+    /// run, with the run's item and length, until it returns [Break](Control::Break).
+    ///
+    /// Runs that were split across several packets when encoded are joined back
+    /// together. Decoding also stops at the first run whose index is not in
+    /// the table, or if the bytes end in the middle of a run.
+    ///
+    /// Returns the number of bytes that were read.
+    pub fn decode_bytes_visit<F>(&self, bytes: &[u8], mut visit: F) -> usize
+    where
+        F: FnMut(&T, usize) -> Control,
+    {
+        let mut offset = 0;
+        let mut end = 0;
+        let mut pending: Option<(usize, usize)> = None;
+        loop {
+            let run = read_run(bytes, offset)
+                .ok()
+                .flatten()
+                .map(|(ind, len, size)| {
+                    offset += size;
+                    (ind, len)
+                })
+                .filter(|&(ind, _)| ind < self.len());
+            match (pending, run) {
+                (Some((ind, len)), Some((next, more))) if ind == next => {
+                    pending = Some((ind, len + more));
+                }
+                (Some((ind, len)), run) => {
+                    if visit(&self[ind], len) == Control::Break || run.is_none() {
+                        return end;
+                    }
+                    pending = run;
+                }
+                (None, Some(run)) => pending = Some(run),
+                (None, None) => return end,
+            }
+            end = offset;
+        }
+    }
+}
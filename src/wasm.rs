@@ -0,0 +1,91 @@
+use crate::{EncodeOptions, Error, Table};
+use wasm_bindgen::prelude::*;
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/// A table of byte symbols for use from JavaScript, exported as `RleTable`.
+/// Only available with the `wasm` feature.
+///
+/// ```js
+/// const table = RleTable.fromData(level);
+/// const packed = table.encode(level);
+/// const unpacked = table.decode(packed);
+/// ```
+#[wasm_bindgen(js_name = RleTable)]
+pub struct WasmTable {
+    table: Table<u8>,
+}
+
+#[wasm_bindgen(js_class = RleTable)]
+impl WasmTable {
+    /// Constructs a table from an array of symbols, in index order.
+    #[wasm_bindgen(constructor)]
+    pub fn new(items: Vec<u8>) -> WasmTable {
+        WasmTable {
+            table: Table::from_slice(&items),
+        }
+    }
+
+    /// Constructs a table with every symbol that appears in the data, with
+    /// the most common symbols first.
+    #[wasm_bindgen(js_name = fromData)]
+    pub fn from_data(data: &[u8]) -> WasmTable {
+        let mut table = Table::default();
+        table.optimize_for(data);
+        WasmTable { table }
+    }
+
+    /// Loads a table saved with `toBytes`.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmTable, JsError> {
+        let table = Table::from_bytes(bytes).map_err(js_error)?;
+        Ok(WasmTable { table })
+    }
+
+    /// Saves the table (see [to_bytes](crate::Table::to_bytes)).
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsError> {
+        self.table.to_bytes().map_err(js_error)
+    }
+
+    /// The number of symbols in the table.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.table.len()
+    }
+
+    /// The symbols in the table, in index order.
+    pub fn symbols(&self) -> Vec<u8> {
+        self.table.as_ref().to_vec()
+    }
+
+    /// Encodes the data in the byte format (see [encode_bytes](crate::Table::encode_bytes)).
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>, JsError> {
+        self.table
+            .encode_bytes_with(data, EncodeOptions::new())
+            .map_err(js_error)
+    }
+
+    /// Decodes bytes written by `encode`.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, JsError> {
+        self.table
+            .decode_bytes_with(bytes, EncodeOptions::new())
+            .map_err(js_error)
+    }
+
+    /// Encodes the data with its table embedded (see [encode_container](crate::Table::encode_container)),
+    /// so it can be decoded with `RleTable.decodeContainer`.
+    #[wasm_bindgen(js_name = encodeContainer)]
+    pub fn encode_container(&self, data: &[u8]) -> Result<Vec<u8>, JsError> {
+        self.table.encode_container(data).map_err(js_error)
+    }
+
+    /// Decodes bytes written by `encodeContainer`.
+    #[wasm_bindgen(js_name = decodeContainer)]
+    pub fn decode_container(bytes: &[u8]) -> Result<Vec<u8>, JsError> {
+        let (_, data) = Table::<u8>::decode_container(bytes).map_err(js_error)?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{Encoder, Error, Index, SortedStore, Table, TableStore};
+
+/// The size of each index and length in a wide byte format.
+/// See [encode_bytes_wide](crate::Table::encode_bytes_wide).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Width {
+    /// 1 byte, which is identical to the format of [encode_bytes](crate::Table::encode_bytes).
+    U8,
+
+    /// 2 bytes, allowing tables of up to 32767 items.
+    U16,
+
+    /// 4 bytes, allowing tables of up to 2147483647 items.
+    U32,
+}
+
+impl Width {
+    /// The number of bytes each index and length takes up.
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+        }
+    }
+
+    /// The largest table that can be encoded with this width.
+    pub fn max_table_len(self) -> usize {
+        (1 << (self.bytes() * 8 - 1)) - 1
+    }
+
+    /// The longest run that can be stored in a single packet. Longer runs
+    /// are split across several packets.
+    pub fn max_run_len(self) -> usize {
+        match self {
+            Self::U8 => 127,
+            Self::U16 => u16::MAX as usize,
+            Self::U32 => u32::MAX as usize,
+        }
+    }
+
+    fn write(self, value: usize, packet: &mut [u8; 8], len: &mut usize) {
+        let bytes = (value as u32).to_le_bytes();
+        packet[*len..*len + self.bytes()].copy_from_slice(&bytes[..self.bytes()]);
+        *len += self.bytes();
+    }
+
+    pub(crate) fn read(self, bytes: &mut &[u8]) -> Option<usize> {
+        let word = bytes.get(..self.bytes())?;
+        let mut buf = [0; 4];
+        buf[..word.len()].copy_from_slice(word);
+        *bytes = &bytes[self.bytes()..];
+        Some(u32::from_le_bytes(buf) as usize)
+    }
+}
+
+/// An iterator that run-length encodes a sequence of `T` values into a
+/// compressed byte format with wide indices. See [encode_bytes_wide](crate::Table::encode_bytes_wide).
+pub struct WideBytesEncoder<'a, T, S = SortedStore<T>> {
+    pub(crate) rle: Encoder<'a, T, S>,
+    pub(crate) width: Width,
+    pub(crate) run: Option<(Index, usize)>,
+    pub(crate) packet: [u8; 8],
+    pub(crate) pos: usize,
+    pub(crate) len: usize,
+}
+
+impl<'a, T, S> Iterator for WideBytesEncoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos < self.len {
+            self.pos += 1;
+            return Some(self.packet[self.pos - 1]);
+        }
+        let (ind, len) = self.run.take().or_else(|| self.rle.next())?;
+        let num = len.min(self.width.max_run_len());
+        self.pos = 1;
+        self.len = 0;
+        if num > 1 {
+            self.width
+                .write((ind << 1) | 1, &mut self.packet, &mut self.len);
+            self.width.write(num, &mut self.packet, &mut self.len);
+        } else {
+            self.width.write(ind << 1, &mut self.packet, &mut self.len);
+        }
+        if len > num {
+            self.run = Some((ind, len - num));
+        }
+        Some(self.packet[0])
+    }
+}
+
+/// An iterator that decodes a run-length encoded sequence of bytes with wide
+/// indices into a series of `T` values fetched from the table. See [decode_bytes_wide](crate::Table::decode_bytes_wide).
+pub struct WideBytesDecoder<'a, T, S = SortedStore<T>> {
+    pub(crate) table: &'a Table<T, S>,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) width: Width,
+    pub(crate) run: Option<(Index, usize)>,
+}
+
+impl<'a, T, S> Iterator for WideBytesDecoder<'a, T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ind, len) = match self.run.take() {
+            Some(run) => run,
+            None => self.read_run().ok()??,
+        };
+        if len > 1 {
+            self.run = Some((ind, len - 1));
+        }
+        self.table.item(ind)
+    }
+}
+
+impl<'a, T, S> WideBytesDecoder<'a, T, S> {
+    /// Reads the next packet, or fails with a [TruncatedStream](Error::TruncatedStream)
+    /// error if the bytes end in the middle of one. A length of 0 reads as a
+    /// run of 1, as in [encode_bytes](Table::encode_bytes).
+    pub(crate) fn read_run(&mut self) -> Result<Option<(Index, usize)>, Error> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let ind = self
+            .width
+            .read(&mut self.bytes)
+            .ok_or(Error::TruncatedStream)?;
+        let len = if (ind & 1) == 1 {
+            self.width
+                .read(&mut self.bytes)
+                .ok_or(Error::TruncatedStream)?
+        } else {
+            1
+        };
+        Ok(Some((ind >> 1, len.max(1))))
+    }
+}
+
+impl<T, S> Table<T, S>
+where
+    T: PartialEq + Clone,
+    S: TableStore<T>,
+{
+    /// Returns an iterator to run-length encode the items as a sequence of bytes,
+    /// storing each index and length in `width` bytes so that larger tables
+    /// can be used.
+    ///
+    /// The format is the same as [encode_bytes](Table::encode_bytes), except
+    /// that every index and length is a little-endian word of `width` bytes
+    /// instead of a single byte. The lowest bit of the index word is still the
+    /// flag that says whether a length word follows.
+    ///
+    /// # Errors
+    ///
+    /// If `items` contains any elements not found in the table, this will return a
+    /// [TableMissingItems](Error::TableMissingItems) error. If the table contains
+    /// more than [max_table_len](Width::max_table_len) items, this will return a
+    /// [TableTooLarge](Error::TableTooLarge) error.
+    pub fn encode_bytes_wide<'a>(
+        &'a self,
+        items: &'a [T],
+        width: Width,
+    ) -> Result<WideBytesEncoder<'a, T, S>, Error> {
+        if self.len() > width.max_table_len() {
+            return Err(Error::TableTooLarge(self.len()));
+        }
+        Ok(WideBytesEncoder {
+            rle: self.encode(items)?,
+            width,
+            run: None,
+            packet: [0; 8],
+            pos: 0,
+            len: 0,
+        })
+    }
+
+    /// Return an iterator that decodes bytes produced by [encode_bytes_wide](Table::encode_bytes_wide)
+    /// with the same `width`, using this table as the index lookup for the elements.
+    pub fn decode_bytes_wide<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        width: Width,
+    ) -> WideBytesDecoder<'a, T, S> {
+        WideBytesDecoder {
+            table: self,
+            bytes,
+            width,
+            run: None,
+        }
+    }
+}
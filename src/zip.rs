@@ -0,0 +1,159 @@
+use std::iter::FusedIterator;
+
+/// A tuple of run iterators that can be decoded in lockstep with [zip_decode]
+/// or [zip_runs].
+///
+/// This is implemented for tuples of 2 to 4 iterators over `(item, len)`
+/// runs, such as [RunDecoder](crate::RunDecoder) and [BytesRunDecoder](crate::BytesRunDecoder),
+/// which can be mixed freely.
+pub trait ZipLayers {
+    /// A tuple of one item from each layer.
+    type Items: Clone;
+
+    /// The run each layer is partway through.
+    #[doc(hidden)]
+    type Runs: Default;
+
+    /// Returns the items of every layer and how long they all stay the same,
+    /// or `None` if any layer has ended.
+    #[doc(hidden)]
+    fn next_span(&mut self, runs: &mut Self::Runs) -> Option<(Self::Items, usize)>;
+}
+
+macro_rules! impl_zip_layers {
+    ($($i:tt $I:ident $X:ident),+) => {
+        impl<$($I, $X),+> ZipLayers for ($($I,)+)
+        where
+            $($I: Iterator<Item = ($X, usize)>, $X: Clone,)+
+        {
+            type Items = ($($X,)+);
+            type Runs = ($(Option<($X, usize)>,)+);
+
+            fn next_span(&mut self, runs: &mut Self::Runs) -> Option<(Self::Items, usize)> {
+                // Refill every layer that has used up its run
+                $(
+                    while runs.$i.as_ref().is_none_or(|(_, len)| *len == 0) {
+                        runs.$i = Some(self.$i.next()?);
+                    }
+                )+
+                let mut len = usize::MAX;
+                $(len = len.min(runs.$i.as_ref()?.1);)+
+                $(runs.$i.as_mut()?.1 -= len;)+
+                Some((($(runs.$i.as_ref()?.0.clone(),)+), len))
+            }
+        }
+    };
+}
+
+impl_zip_layers!(0 A X0, 1 B X1);
+impl_zip_layers!(0 A X0, 1 B X1, 2 C X2);
+impl_zip_layers!(0 A X0, 1 B X1, 2 C X2, 3 D X3);
+
+/// Decodes several layers of runs in lockstep, such as the tile, collision
+/// and metadata layers of a map, yielding a tuple of each layer's item at
+/// every position. The layers are a tuple of run iterators (see [ZipLayers]).
+///
+/// Rather than decoding each layer separately and zipping them, this only
+/// advances a layer when its run ends, so [nth](Iterator::nth) and
+/// [skip](Iterator::skip) jump over long stretches where every layer is
+/// inside a run without visiting each position. Use [zip_runs] to get the
+/// stretches themselves.
+///
+/// Like [zip](Iterator::zip), decoding stops as soon as any layer ends.
+///
+/// ```
+/// # use rle::{zip_decode, Table};
+/// let tiles = Table::from_slice(&['.', '#']);
+/// let solid = Table::from_slice(&[false, true]);
+/// let tile_bytes: Vec<u8> = tiles.encode_bytes(&['.', '.', '#', '#']).unwrap().collect();
+///
+/// let cells: Vec<_> = zip_decode((
+///     tiles.decode_bytes_runs(&tile_bytes),
+///     solid.decode_runs(&[(0, 2usize), (1, 2)]),
+/// ))
+/// .collect();
+/// assert_eq!(cells, [(&'.', &false), (&'.', &false), (&'#', &true), (&'#', &true)]);
+/// ```
+pub fn zip_decode<L: ZipLayers>(layers: L) -> ZipDecoder<L> {
+    ZipDecoder {
+        runs: zip_runs(layers),
+        span: None,
+    }
+}
+
+/// Decodes several layers of runs in lockstep like [zip_decode], but yields
+/// each stretch where no layer changes as a tuple of the items and how long
+/// the stretch is. A new stretch starts wherever any layer's run does.
+///
+/// ```
+/// # use rle::{zip_runs, Table};
+/// let tiles = Table::from_slice(&['.', '#']);
+/// let zones = Table::from_slice(&[1u8, 2]);
+/// let spans: Vec<_> = zip_runs((
+///     tiles.decode_runs(&[(0, 5usize), (1, 5)]),
+///     zones.decode_runs(&[(0, 3usize), (1, 7)]),
+/// ))
+/// .collect();
+/// assert_eq!(spans, [((&'.', &1), 3), ((&'.', &2), 2), ((&'#', &2), 5)]);
+/// ```
+pub fn zip_runs<L: ZipLayers>(layers: L) -> ZipRuns<L> {
+    ZipRuns {
+        layers,
+        runs: Some(L::Runs::default()),
+    }
+}
+
+/// An iterator that decodes several layers of runs into stretches where
+/// no layer changes. See [zip_runs].
+pub struct ZipRuns<L: ZipLayers> {
+    layers: L,
+
+    /// The runs each layer is partway through, or `None` once a layer ends.
+    runs: Option<L::Runs>,
+}
+
+impl<L: ZipLayers> Iterator for ZipRuns<L> {
+    type Item = (L::Items, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.layers.next_span(self.runs.as_mut()?);
+        if span.is_none() {
+            self.runs = None;
+        }
+        span
+    }
+}
+
+impl<L: ZipLayers> FusedIterator for ZipRuns<L> {}
+
+/// An iterator that decodes several layers of runs in lockstep into a tuple
+/// of each layer's item at every position. See [zip_decode].
+pub struct ZipDecoder<L: ZipLayers> {
+    runs: ZipRuns<L>,
+    span: Option<(L::Items, usize)>,
+}
+
+impl<L: ZipLayers> Iterator for ZipDecoder<L> {
+    type Item = L::Items;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            if let Some((items, len)) = &mut self.span {
+                // Skip whole stretches until the one containing the position
+                if n < *len {
+                    *len -= n + 1;
+                    return Some(items.clone());
+                }
+                n -= *len;
+            }
+            self.span = self.runs.next();
+            self.span.as_ref()?;
+        }
+    }
+}
+
+impl<L: ZipLayers> FusedIterator for ZipDecoder<L> {}